@@ -0,0 +1,42 @@
+use anchor_lang::error::ErrorCode;
+use anchor_lang::{AccountDeserialize, Owner, Result};
+use solana_sdk::account::Account;
+
+pub use bark_blinks::blink::Blink;
+pub use bark_blinks::collection::Collection;
+pub use bark_blinks::donations::Donation;
+pub use bark_blinks::nft::NFT;
+pub use bark_blinks::payments::Payment;
+pub use bark_blinks::swap::Swap;
+
+// Decodes any program account, checking its owner and discriminator first
+pub fn decode_account<T: AccountDeserialize + Owner>(account: &Account) -> Result<T> {
+    if account.owner != T::owner() {
+        return Err(ErrorCode::AccountOwnedByWrongProgram.into());
+    }
+    T::try_deserialize(&mut account.data.as_slice())
+}
+
+pub fn decode_blink(account: &Account) -> Result<Blink> {
+    decode_account(account)
+}
+
+pub fn decode_nft(account: &Account) -> Result<NFT> {
+    decode_account(account)
+}
+
+pub fn decode_collection(account: &Account) -> Result<Collection> {
+    decode_account(account)
+}
+
+pub fn decode_donation(account: &Account) -> Result<Donation> {
+    decode_account(account)
+}
+
+pub fn decode_payment(account: &Account) -> Result<Payment> {
+    decode_account(account)
+}
+
+pub fn decode_swap(account: &Account) -> Result<Swap> {
+    decode_account(account)
+}
@@ -0,0 +1,136 @@
+use anchor_lang::error::ERROR_CODE_OFFSET;
+use bark_blinks::batch::BATCH_ITEM_LABEL;
+use bark_blinks::errors::BlinkError;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::transaction::TransactionError;
+
+// Every variant in declaration order, so a variant's error code is its index plus
+// ERROR_CODE_OFFSET. Variants appended to `BlinkError` must be appended here too.
+pub const BLINK_ERRORS: &[BlinkError] = &[
+    BlinkError::NameTooLong,
+    BlinkError::DescriptionTooLong,
+    BlinkError::InvalidBlinkType,
+    BlinkError::Unauthorized,
+    BlinkError::InvalidNFTMetadata,
+    BlinkError::CollectionFull,
+    BlinkError::SymbolTooLong,
+    BlinkError::InvalidCurrency,
+    BlinkError::MessageTooLong,
+    BlinkError::InvalidPaymentStatus,
+    BlinkError::InvalidSwapParameters,
+    BlinkError::MathOverflow,
+    BlinkError::InsufficientFunds,
+    BlinkError::InvalidTokenAccount,
+    BlinkError::InvalidMint,
+    BlinkError::InvalidMetadata,
+    BlinkError::InvalidCollection,
+    BlinkError::BlinkNotFound,
+    BlinkError::NFTNotFound,
+    BlinkError::CollectionNotFound,
+    BlinkError::SwapNotFound,
+    BlinkError::DonationNotFound,
+    BlinkError::PaymentNotFound,
+    BlinkError::InvalidFee,
+    BlinkError::FeeTooHigh,
+    BlinkError::SlippageExceeded,
+    BlinkError::DeadlineExceeded,
+    BlinkError::InvalidSignature,
+    BlinkError::AccountAlreadyInitialized,
+    BlinkError::AccountNotInitialized,
+    BlinkError::InvalidProgramAddress,
+    BlinkError::InvalidSystemProgram,
+    BlinkError::InvalidTokenProgram,
+    BlinkError::InvalidAssociatedTokenProgram,
+    BlinkError::InvalidRentSysvar,
+    BlinkError::InvalidBlinkConfig,
+    BlinkError::TooManySuggestedAmounts,
+    BlinkError::TooManyPollOptions,
+    BlinkError::PollOptionTooLong,
+    BlinkError::PollClosed,
+    BlinkError::AlreadyVoted,
+    BlinkError::InvalidPollOption,
+    BlinkError::InvalidPollCloseTime,
+    BlinkError::GiftAlreadySettled,
+    BlinkError::GiftExpired,
+    BlinkError::GiftNotExpired,
+    BlinkError::InvalidGiftSecret,
+    BlinkError::InvalidRecipient,
+    BlinkError::TooManyTrackedMints,
+    BlinkError::InvalidBlinkHistory,
+    BlinkError::BlinkPaused,
+    BlinkError::BlinkExpired,
+    BlinkError::BlinkNotStarted,
+    BlinkError::InvalidSchedule,
+    BlinkError::InvalidPermissions,
+    BlinkError::TooManyEditors,
+    BlinkError::UrlTooLong,
+    BlinkError::InvalidAttribute,
+    BlinkError::TooManyAttributes,
+    BlinkError::AttributeAlreadyExists,
+    BlinkError::AttributeNotFound,
+    BlinkError::InvalidAbility,
+    BlinkError::TooManyAbilities,
+    BlinkError::AbilityAlreadyExists,
+    BlinkError::AbilityNotFound,
+    BlinkError::TooManyHookPrograms,
+    BlinkError::HookProgramNotAllowed,
+    BlinkError::HookUnavailable,
+    BlinkError::InvalidHandle,
+    BlinkError::HandleReserved,
+    BlinkError::BlinkFrozen,
+    BlinkError::TooManyModerators,
+    BlinkError::NotModerated,
+    BlinkError::AppealAlreadyFiled,
+    BlinkError::InvalidBatchSize,
+    BlinkError::InvalidUriScheme,
+    BlinkError::ContentHashNotSet,
+    BlinkError::ContentHashMismatch,
+    BlinkError::TokenGateNotMet,
+    BlinkError::InvalidFeeBasisPoints,
+    BlinkError::FeatureDisabled,
+    BlinkError::InvalidTreasuryAccount,
+    BlinkError::ModulePaused,
+    BlinkError::BlinkVaultsOpen,
+    BlinkError::PollStillOpen,
+    BlinkError::MintSupplyExhausted,
+    BlinkError::BatchItemFailed,
+    BlinkError::PriceMismatch,
+    BlinkError::BlinkHasHandle,
+    BlinkError::InvalidHandleAccount,
+    BlinkError::RegistryFull,
+    BlinkError::InvalidRegistryAccount,
+];
+
+pub fn blink_error_from_code(code: u32) -> Option<BlinkError> {
+    let index = code.checked_sub(ERROR_CODE_OFFSET)?;
+    BLINK_ERRORS.get(index as usize).copied()
+}
+
+pub fn decode_instruction_error(error: &InstructionError) -> Option<BlinkError> {
+    match error {
+        InstructionError::Custom(code) => blink_error_from_code(*code),
+        _ => None,
+    }
+}
+
+// Returns the index of the failing instruction with its error. Other programs reuse the
+// same custom codes, so this is only meaningful when that instruction targets this program.
+pub fn decode_transaction_error(error: &TransactionError) -> Option<(u8, BlinkError)> {
+    match error {
+        TransactionError::InstructionError(index, error) => {
+            decode_instruction_error(error).map(|blink_error| (*index, blink_error))
+        }
+        _ => None,
+    }
+}
+
+// Index of the item that failed a batch instruction, read from the transaction logs. The
+// error itself is that item's own and comes from `decode_transaction_error`.
+pub fn decode_batch_item_index(logs: &[String]) -> Option<usize> {
+    let label = format!("Program log: Left: {}", BATCH_ITEM_LABEL);
+    let position = logs.iter().position(|log| *log == label)?;
+    logs.get(position + 1)?
+        .strip_prefix("Program log: Right: ")?
+        .parse()
+        .ok()
+}
@@ -0,0 +1,1150 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::{system_program, sysvar};
+use anchor_lang::{InstructionData, ToAccountMetas};
+use bark_blinks::accounts as ix_accounts;
+use bark_blinks::batch::{BlinkInput, BlinkUpdate};
+use bark_blinks::blink::BlinkConfig;
+use bark_blinks::execute::ExecuteBlinkArgs;
+use bark_blinks::gate::TokenGate;
+use bark_blinks::hooks::BlinkHook;
+use bark_blinks::instruction as ix_data;
+use bark_blinks::moderation::ModerationStatus;
+use bark_blinks::protocol::{ProtocolFeatures, ProtocolFees, ProtocolModule};
+use crate::pda::{
+    find_blink_address, find_blink_attributes_address, find_blink_history_address,
+    find_blink_vault_address, find_gift_escrow_address, find_gift_vault_address,
+    find_handle_address, find_hook_allowlist_address, find_metadata_address,
+    find_moderators_address, find_owner_registry_address, find_poll_address,
+    find_poll_vault_address, find_program_data_address, find_protocol_config_address,
+    find_template_address, find_vote_receipt_address,
+};
+
+fn instruction(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    instruction_with_remaining(accounts, data, &[])
+}
+
+fn instruction_with_remaining(
+    accounts: impl ToAccountMetas,
+    data: impl InstructionData,
+    remaining_accounts: &[AccountMeta],
+) -> Instruction {
+    let mut metas = accounts.to_account_metas(None);
+    metas.extend_from_slice(remaining_accounts);
+    Instruction {
+        program_id: bark_blinks::ID,
+        accounts: metas,
+        data: data.data(),
+    }
+}
+
+fn protocol_config() -> Pubkey {
+    find_protocol_config_address().0
+}
+
+// `index` is the owner registry's current `blink_count`, or 0 for the owner's first blink
+pub fn create_blink(owner: &Pubkey, index: u64, input: BlinkInput) -> Instruction {
+    let (registry, _) = find_owner_registry_address(owner);
+    let (blink, _) = find_blink_address(&registry, index);
+    instruction(
+        ix_accounts::CreateBlink {
+            registry,
+            blink,
+            owner: *owner,
+            system_program: system_program::ID,
+            protocol_config: protocol_config(),
+        },
+        ix_data::CreateBlink {
+            name: input.name,
+            description: input.description,
+            blink_type: input.blink_type,
+            image_url: input.image_url,
+            config: input.config,
+        },
+    )
+}
+
+pub fn update_blink(
+    blink: &Pubkey,
+    authority: &Pubkey,
+    owner: &Pubkey,
+    update: BlinkUpdate,
+) -> Instruction {
+    instruction(
+        ix_accounts::UpdateBlink {
+            blink: *blink,
+            authority: *authority,
+            owner: *owner,
+            system_program: system_program::ID,
+            protocol_config: protocol_config(),
+        },
+        ix_data::UpdateBlink {
+            name: update.name,
+            description: update.description,
+            image_url: update.image_url,
+            config: update.config,
+        },
+    )
+}
+
+// `handle` is the blink's handle, if it has one
+// `blink_registry` is the registry the blink was created in (`Blink::registry`)
+pub fn delete_blink(
+    blink: &Pubkey,
+    blink_registry: &Pubkey,
+    owner: &Pubkey,
+    handle: Option<&str>,
+) -> Instruction {
+    let registry = find_owner_registry_address(owner).0;
+    instruction(
+        ix_accounts::DeleteBlink {
+            blink: *blink,
+            registry,
+            owner: *owner,
+            handle_account: handle.map(|handle| find_handle_address(handle).0),
+            attributes: find_blink_attributes_address(blink).0,
+            creator_registry: (*blink_registry != registry).then_some(*blink_registry),
+        },
+        ix_data::DeleteBlink {},
+    )
+}
+
+fn manage_blink(blink: &Pubkey, authority: &Pubkey) -> ix_accounts::ManageBlink {
+    ix_accounts::ManageBlink {
+        blink: *blink,
+        authority: *authority,
+        protocol_config: protocol_config(),
+    }
+}
+
+pub fn set_blink_paused(blink: &Pubkey, authority: &Pubkey, paused: bool) -> Instruction {
+    instruction(
+        manage_blink(blink, authority),
+        ix_data::SetBlinkPaused { paused },
+    )
+}
+
+pub fn set_blink_schedule(
+    blink: &Pubkey,
+    authority: &Pubkey,
+    starts_at: i64,
+    ends_at: Option<i64>,
+) -> Instruction {
+    instruction(
+        manage_blink(blink, authority),
+        ix_data::SetBlinkSchedule { starts_at, ends_at },
+    )
+}
+
+pub fn transfer_blink_ownership(
+    blink: &Pubkey,
+    owner: &Pubkey,
+    new_owner: Option<Pubkey>,
+) -> Instruction {
+    instruction(
+        ix_accounts::TransferBlinkOwnership {
+            blink: *blink,
+            owner: *owner,
+            protocol_config: protocol_config(),
+        },
+        ix_data::TransferBlinkOwnership { new_owner },
+    )
+}
+
+// `owner` is the blink's current owner; `handle` is the blink's handle, if it has one
+pub fn accept_blink_ownership(
+    blink: &Pubkey,
+    owner: &Pubkey,
+    new_owner: &Pubkey,
+    handle: Option<&str>,
+) -> Instruction {
+    instruction(
+        ix_accounts::AcceptBlinkOwnership {
+            blink: *blink,
+            new_owner: *new_owner,
+            protocol_config: protocol_config(),
+            handle_account: handle.map(|handle| find_handle_address(handle).0),
+            previous_registry: find_owner_registry_address(owner).0,
+            new_registry: find_owner_registry_address(new_owner).0,
+            system_program: system_program::ID,
+        },
+        ix_data::AcceptBlinkOwnership {},
+    )
+}
+
+pub fn set_blink_editor(
+    blink: &Pubkey,
+    owner: &Pubkey,
+    editor: &Pubkey,
+    permissions: u8,
+) -> Instruction {
+    instruction(
+        ix_accounts::SetBlinkEditor {
+            blink: *blink,
+            owner: *owner,
+            protocol_config: protocol_config(),
+        },
+        ix_data::SetBlinkEditor {
+            editor: *editor,
+            permissions,
+        },
+    )
+}
+
+pub fn set_blink_image_hash(
+    blink: &Pubkey,
+    authority: &Pubkey,
+    image_hash: Option<[u8; 32]>,
+) -> Instruction {
+    instruction(
+        manage_blink(blink, authority),
+        ix_data::SetBlinkImageHash { image_hash },
+    )
+}
+
+pub fn verify_blink_content(blink: &Pubkey, data: Vec<u8>) -> Instruction {
+    instruction(
+        ix_accounts::VerifyBlinkContent { blink: *blink },
+        ix_data::VerifyBlinkContent { data },
+    )
+}
+
+// Both `nft` and `mint` must sign
+pub fn create_nft(
+    nft: &Pubkey,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    token_account: &Pubkey,
+    name: String,
+    symbol: String,
+    uri: String,
+) -> Instruction {
+    instruction(
+        ix_accounts::CreateNFT {
+            nft: *nft,
+            owner: *owner,
+            system_program: system_program::ID,
+            token_program: anchor_spl::token::ID,
+            metadata: find_metadata_address(mint).0,
+            mint: *mint,
+            token_account: *token_account,
+            metadata_program: mpl_token_metadata::ID,
+            rent: sysvar::rent::ID,
+            protocol_config: protocol_config(),
+        },
+        ix_data::CreateNft { name, symbol, uri },
+    )
+}
+
+pub fn set_nft_uri_hash(nft: &Pubkey, owner: &Pubkey, uri_hash: Option<[u8; 32]>) -> Instruction {
+    instruction(
+        ix_accounts::SetNFTUriHash {
+            nft: *nft,
+            owner: *owner,
+        },
+        ix_data::SetNftUriHash { uri_hash },
+    )
+}
+
+pub fn verify_nft_content(nft: &Pubkey, data: Vec<u8>) -> Instruction {
+    instruction(
+        ix_accounts::VerifyNFTContent { nft: *nft },
+        ix_data::VerifyNftContent { data },
+    )
+}
+
+pub fn create_collection(
+    collection: &Pubkey,
+    owner: &Pubkey,
+    name: String,
+    symbol: String,
+    uri: String,
+) -> Instruction {
+    instruction(
+        ix_accounts::CreateCollection {
+            collection: *collection,
+            owner: *owner,
+            system_program: system_program::ID,
+            protocol_config: protocol_config(),
+        },
+        ix_data::CreateCollection { name, symbol, uri },
+    )
+}
+
+pub fn add_nft_to_collection(
+    nft: &Pubkey,
+    collection: &Pubkey,
+    owner: &Pubkey,
+    nft_owner: &Pubkey,
+) -> Instruction {
+    instruction(
+        ix_accounts::AddNFTToCollection {
+            nft: *nft,
+            collection: *collection,
+            owner: *owner,
+            nft_owner: *nft_owner,
+            protocol_config: protocol_config(),
+        },
+        ix_data::AddNftToCollection {},
+    )
+}
+
+// Routes a donation or payment into a blink's vault. Remaining accounts are the token
+// gate proof when the blink is gated, then its hook accounts when it has a hook.
+#[derive(Clone, Default)]
+pub struct BlinkRoute {
+    pub blink: Pubkey,
+    pub history: Option<Pubkey>,
+    pub remaining_accounts: Vec<AccountMeta>,
+}
+
+// `treasury_token_account` is required when a protocol fee is owed
+#[allow(clippy::too_many_arguments)]
+pub fn create_donation(
+    donation: &Pubkey,
+    donor: &Pubkey,
+    recipient: &Pubkey,
+    donor_token_account: &Pubkey,
+    recipient_token_account: &Pubkey,
+    treasury_token_account: Option<&Pubkey>,
+    route: Option<&BlinkRoute>,
+    amount: u64,
+    currency: String,
+    message: String,
+) -> Instruction {
+    instruction_with_remaining(
+        ix_accounts::CreateDonation {
+            donation: *donation,
+            donor: *donor,
+            recipient: *recipient,
+            donor_token_account: *donor_token_account,
+            recipient_token_account: *recipient_token_account,
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+            protocol_config: protocol_config(),
+            treasury_token_account: treasury_token_account.copied(),
+            blink: route.map(|route| route.blink),
+            history: route.and_then(|route| route.history),
+        },
+        ix_data::CreateDonation {
+            amount,
+            currency,
+            message,
+        },
+        route.map_or(&[][..], |route| route.remaining_accounts.as_slice()),
+    )
+}
+
+// `treasury_token_account` is required when a protocol fee is owed
+#[allow(clippy::too_many_arguments)]
+pub fn create_payment(
+    payment: &Pubkey,
+    payer: &Pubkey,
+    recipient: &Pubkey,
+    payer_token_account: &Pubkey,
+    recipient_token_account: &Pubkey,
+    treasury_token_account: Option<&Pubkey>,
+    route: Option<&BlinkRoute>,
+    amount: u64,
+    currency: String,
+    description: String,
+) -> Instruction {
+    instruction_with_remaining(
+        ix_accounts::CreatePayment {
+            payment: *payment,
+            payer: *payer,
+            recipient: *recipient,
+            payer_token_account: *payer_token_account,
+            recipient_token_account: *recipient_token_account,
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+            protocol_config: protocol_config(),
+            treasury_token_account: treasury_token_account.copied(),
+            blink: route.map(|route| route.blink),
+            history: route.and_then(|route| route.history),
+        },
+        ix_data::CreatePayment {
+            amount,
+            currency,
+            description,
+        },
+        route.map_or(&[][..], |route| route.remaining_accounts.as_slice()),
+    )
+}
+
+// `mint` must already be allocated to the token program; `treasury` is the protocol
+// config's treasury, which receives the mint fee
+pub fn create_mint(
+    mint_authority: &Pubkey,
+    mint: &Pubkey,
+    treasury: &Pubkey,
+    name: String,
+    symbol: String,
+    uri: String,
+) -> Instruction {
+    instruction(
+        ix_accounts::CreateMint {
+            mint_authority: *mint_authority,
+            mint: *mint,
+            token_program: anchor_spl::token::ID,
+            rent: sysvar::rent::ID,
+            system_program: system_program::ID,
+            metadata: find_metadata_address(mint).0,
+            token_metadata_program: mpl_token_metadata::ID,
+            protocol_config: protocol_config(),
+            treasury: *treasury,
+        },
+        ix_data::CreateMint { name, symbol, uri },
+    )
+}
+
+pub fn create_swap(
+    swap: &Pubkey,
+    owner: &Pubkey,
+    token_a: &Pubkey,
+    token_b: &Pubkey,
+    amount_a: u64,
+    amount_b: u64,
+    fee: u64,
+) -> Instruction {
+    instruction(
+        ix_accounts::CreateSwap {
+            swap: *swap,
+            owner: *owner,
+            token_a: *token_a,
+            token_b: *token_b,
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+            protocol_config: protocol_config(),
+        },
+        ix_data::CreateSwap {
+            amount_a,
+            amount_b,
+            fee,
+        },
+    )
+}
+
+// `treasury_token_account` holds token A and is required when a protocol fee is owed
+pub fn execute_swap(
+    swap: &Pubkey,
+    owner: &Pubkey,
+    token_a_source: &Pubkey,
+    token_a_destination: &Pubkey,
+    token_b_source: &Pubkey,
+    token_b_destination: &Pubkey,
+    treasury_token_account: Option<&Pubkey>,
+) -> Instruction {
+    instruction(
+        ix_accounts::ExecuteSwap {
+            swap: *swap,
+            owner: *owner,
+            token_a_source: *token_a_source,
+            token_a_destination: *token_a_destination,
+            token_b_source: *token_b_source,
+            token_b_destination: *token_b_destination,
+            token_program: anchor_spl::token::ID,
+            protocol_config: protocol_config(),
+            treasury_token_account: treasury_token_account.copied(),
+        },
+        ix_data::ExecuteSwap {},
+    )
+}
+
+// Token-weighted polls also create the vault votes are locked in
+pub fn open_poll(
+    blink: &Pubkey,
+    owner: &Pubkey,
+    closes_at: i64,
+    vote_mint: Option<Pubkey>,
+) -> Instruction {
+    let (poll, _) = find_poll_address(blink);
+    instruction(
+        ix_accounts::OpenPoll {
+            blink: *blink,
+            poll,
+            owner: *owner,
+            system_program: system_program::ID,
+            protocol_config: protocol_config(),
+            vote_mint,
+            vote_vault: vote_mint.map(|_| find_poll_vault_address(&poll).0),
+            token_program: anchor_spl::token::ID,
+            rent: sysvar::rent::ID,
+        },
+        ix_data::OpenPoll { closes_at },
+    )
+}
+
+// Remaining accounts: the token gate proof when the blink is gated
+pub fn cast_vote(
+    blink: &Pubkey,
+    voter: &Pubkey,
+    voter_token_account: Option<&Pubkey>,
+    history: Option<&Pubkey>,
+    option: u8,
+    remaining_accounts: &[AccountMeta],
+) -> Instruction {
+    let (poll, _) = find_poll_address(blink);
+    instruction_with_remaining(
+        ix_accounts::CastVote {
+            blink: *blink,
+            poll,
+            vote_receipt: find_vote_receipt_address(&poll, voter).0,
+            voter: *voter,
+            voter_token_account: voter_token_account.copied(),
+            system_program: system_program::ID,
+            history: history.copied(),
+            protocol_config: protocol_config(),
+            vote_vault: voter_token_account.map(|_| find_poll_vault_address(&poll).0),
+            token_program: anchor_spl::token::ID,
+        },
+        ix_data::CastVote { option },
+        remaining_accounts,
+    )
+}
+
+pub fn withdraw_vote_tokens(
+    blink: &Pubkey,
+    voter: &Pubkey,
+    voter_token_account: &Pubkey,
+) -> Instruction {
+    let (poll, _) = find_poll_address(blink);
+    instruction(
+        ix_accounts::WithdrawVoteTokens {
+            poll,
+            vote_receipt: find_vote_receipt_address(&poll, voter).0,
+            voter: *voter,
+            voter_token_account: *voter_token_account,
+            vote_vault: find_poll_vault_address(&poll).0,
+            token_program: anchor_spl::token::ID,
+        },
+        ix_data::WithdrawVoteTokens {},
+    )
+}
+
+pub fn get_poll_results(blink: &Pubkey) -> Instruction {
+    instruction(
+        ix_accounts::GetPollResults {
+            poll: find_poll_address(blink).0,
+        },
+        ix_data::GetPollResults {},
+    )
+}
+
+pub fn fund_gift(
+    blink: &Pubkey,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    owner_token_account: &Pubkey,
+) -> Instruction {
+    instruction(
+        ix_accounts::FundGift {
+            blink: *blink,
+            escrow: find_gift_escrow_address(blink).0,
+            vault: find_gift_vault_address(blink).0,
+            mint: *mint,
+            owner_token_account: *owner_token_account,
+            owner: *owner,
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+            protocol_config: protocol_config(),
+        },
+        ix_data::FundGift {},
+    )
+}
+
+// Remaining accounts: the token gate proof when the blink is gated, then the blink's
+// hook accounts when it has a hook
+pub fn claim_gift(
+    blink: &Pubkey,
+    sender: &Pubkey,
+    claimant: &Pubkey,
+    claimant_token_account: &Pubkey,
+    history: Option<&Pubkey>,
+    claim_key: Option<&Pubkey>,
+    remaining_accounts: &[AccountMeta],
+) -> Instruction {
+    instruction_with_remaining(
+        ix_accounts::ClaimGift {
+            blink: *blink,
+            escrow: find_gift_escrow_address(blink).0,
+            vault: find_gift_vault_address(blink).0,
+            sender: *sender,
+            claimant: *claimant,
+            claimant_token_account: *claimant_token_account,
+            token_program: anchor_spl::token::ID,
+            history: history.copied(),
+            protocol_config: protocol_config(),
+            claim_key: claim_key.copied(),
+        },
+        ix_data::ClaimGift {},
+        remaining_accounts,
+    )
+}
+
+pub fn reclaim_gift(blink: &Pubkey, sender: &Pubkey, sender_token_account: &Pubkey) -> Instruction {
+    instruction(
+        ix_accounts::ReclaimGift {
+            escrow: find_gift_escrow_address(blink).0,
+            vault: find_gift_vault_address(blink).0,
+            sender: *sender,
+            sender_token_account: *sender_token_account,
+            token_program: anchor_spl::token::ID,
+        },
+        ix_data::ReclaimGift {},
+    )
+}
+
+pub fn open_blink_vault(blink: &Pubkey, mint: &Pubkey, payer: &Pubkey) -> Instruction {
+    instruction(
+        ix_accounts::OpenBlinkVault {
+            blink: *blink,
+            vault: find_blink_vault_address(blink, mint).0,
+            mint: *mint,
+            payer: *payer,
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+            protocol_config: protocol_config(),
+        },
+        ix_data::OpenBlinkVault {},
+    )
+}
+
+pub fn withdraw_from_blink_vault(
+    blink: &Pubkey,
+    mint: &Pubkey,
+    destination: &Pubkey,
+    authority: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    instruction(
+        ix_accounts::WithdrawFromBlinkVault {
+            blink: *blink,
+            vault: find_blink_vault_address(blink, mint).0,
+            destination: *destination,
+            authority: *authority,
+            token_program: anchor_spl::token::ID,
+        },
+        ix_data::WithdrawFromBlinkVault { amount },
+    )
+}
+
+pub fn close_blink_vault(
+    blink: &Pubkey,
+    mint: &Pubkey,
+    destination: &Pubkey,
+    authority: &Pubkey,
+    owner: &Pubkey,
+) -> Instruction {
+    instruction(
+        ix_accounts::CloseBlinkVault {
+            blink: *blink,
+            vault: find_blink_vault_address(blink, mint).0,
+            destination: *destination,
+            authority: *authority,
+            owner: *owner,
+            token_program: anchor_spl::token::ID,
+        },
+        ix_data::CloseBlinkVault {},
+    )
+}
+
+// Remaining accounts follow the layout documented on `bark_blinks::execute::ExecuteBlink`:
+// the token gate proof, the accounts for the blink's action, then its hook accounts
+pub fn execute_blink(
+    blink: &Pubkey,
+    executor: &Pubkey,
+    history: Option<&Pubkey>,
+    treasury_token_account: Option<&Pubkey>,
+    args: ExecuteBlinkArgs,
+    remaining_accounts: &[AccountMeta],
+) -> Instruction {
+    instruction_with_remaining(
+        ix_accounts::ExecuteBlink {
+            blink: *blink,
+            executor: *executor,
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+            history: history.copied(),
+            protocol_config: protocol_config(),
+            treasury_token_account: treasury_token_account.copied(),
+        },
+        ix_data::ExecuteBlink { args },
+        remaining_accounts,
+    )
+}
+
+pub fn open_blink_history(blink: &Pubkey, owner: &Pubkey) -> Instruction {
+    instruction(
+        ix_accounts::OpenBlinkHistory {
+            blink: *blink,
+            history: find_blink_history_address(blink).0,
+            owner: *owner,
+            system_program: system_program::ID,
+            protocol_config: protocol_config(),
+        },
+        ix_data::OpenBlinkHistory {},
+    )
+}
+
+fn manage_attributes(
+    blink: &Pubkey,
+    authority: &Pubkey,
+    owner: &Pubkey,
+) -> ix_accounts::ManageBlinkAttributes {
+    ix_accounts::ManageBlinkAttributes {
+        blink: *blink,
+        attributes: find_blink_attributes_address(blink).0,
+        authority: *authority,
+        owner: *owner,
+        system_program: system_program::ID,
+        protocol_config: protocol_config(),
+    }
+}
+
+pub fn add_blink_attribute(
+    blink: &Pubkey,
+    authority: &Pubkey,
+    owner: &Pubkey,
+    trait_type: String,
+    value: String,
+    numeric_value: Option<u64>,
+) -> Instruction {
+    instruction(
+        manage_attributes(blink, authority, owner),
+        ix_data::AddBlinkAttribute {
+            trait_type,
+            value,
+            numeric_value,
+        },
+    )
+}
+
+pub fn update_blink_attribute(
+    blink: &Pubkey,
+    authority: &Pubkey,
+    owner: &Pubkey,
+    trait_type: String,
+    value: String,
+    numeric_value: Option<u64>,
+) -> Instruction {
+    instruction(
+        manage_attributes(blink, authority, owner),
+        ix_data::UpdateBlinkAttribute {
+            trait_type,
+            value,
+            numeric_value,
+        },
+    )
+}
+
+pub fn remove_blink_attribute(
+    blink: &Pubkey,
+    authority: &Pubkey,
+    owner: &Pubkey,
+    trait_type: String,
+) -> Instruction {
+    instruction(
+        manage_attributes(blink, authority, owner),
+        ix_data::RemoveBlinkAttribute { trait_type },
+    )
+}
+
+pub fn add_blink_ability(
+    blink: &Pubkey,
+    authority: &Pubkey,
+    owner: &Pubkey,
+    name: String,
+    description: String,
+    cooldown: u32,
+    power_cost: u32,
+) -> Instruction {
+    instruction(
+        manage_attributes(blink, authority, owner),
+        ix_data::AddBlinkAbility {
+            name,
+            description,
+            cooldown,
+            power_cost,
+        },
+    )
+}
+
+pub fn update_blink_ability(
+    blink: &Pubkey,
+    authority: &Pubkey,
+    owner: &Pubkey,
+    name: String,
+    description: String,
+    cooldown: u32,
+    power_cost: u32,
+) -> Instruction {
+    instruction(
+        manage_attributes(blink, authority, owner),
+        ix_data::UpdateBlinkAbility {
+            name,
+            description,
+            cooldown,
+            power_cost,
+        },
+    )
+}
+
+pub fn remove_blink_ability(
+    blink: &Pubkey,
+    authority: &Pubkey,
+    owner: &Pubkey,
+    name: String,
+) -> Instruction {
+    instruction(
+        manage_attributes(blink, authority, owner),
+        ix_data::RemoveBlinkAbility { name },
+    )
+}
+
+pub fn set_hook_program_allowed(admin: &Pubkey, program: &Pubkey, allowed: bool) -> Instruction {
+    instruction(
+        ix_accounts::SetHookProgramAllowed {
+            allowlist: find_hook_allowlist_address().0,
+            protocol_config: protocol_config(),
+            admin: *admin,
+            system_program: system_program::ID,
+        },
+        ix_data::SetHookProgramAllowed {
+            program: *program,
+            allowed,
+        },
+    )
+}
+
+pub fn set_blink_hook(blink: &Pubkey, owner: &Pubkey, hook: Option<BlinkHook>) -> Instruction {
+    instruction(
+        ix_accounts::SetBlinkHook {
+            blink: *blink,
+            allowlist: find_hook_allowlist_address().0,
+            owner: *owner,
+            protocol_config: protocol_config(),
+        },
+        ix_data::SetBlinkHook { hook },
+    )
+}
+
+pub fn register_handle(blink: &Pubkey, owner: &Pubkey, handle: String) -> Instruction {
+    instruction(
+        ix_accounts::RegisterHandle {
+            blink: *blink,
+            handle_account: find_handle_address(&handle).0,
+            owner: *owner,
+            system_program: system_program::ID,
+            protocol_config: protocol_config(),
+        },
+        ix_data::RegisterHandle { handle },
+    )
+}
+
+// Both the current and the new owner must sign; `blink` is the blink the handle points at
+pub fn transfer_handle(
+    handle: &str,
+    blink: &Pubkey,
+    owner: &Pubkey,
+    new_blink: &Pubkey,
+    new_owner: &Pubkey,
+) -> Instruction {
+    instruction(
+        ix_accounts::TransferHandle {
+            handle_account: find_handle_address(handle).0,
+            blink: *blink,
+            owner: *owner,
+            new_blink: *new_blink,
+            new_owner: *new_owner,
+            protocol_config: protocol_config(),
+        },
+        ix_data::TransferHandle {},
+    )
+}
+
+pub fn release_handle(handle: &str, blink: &Pubkey, owner: &Pubkey) -> Instruction {
+    instruction(
+        ix_accounts::ReleaseHandle {
+            handle_account: find_handle_address(handle).0,
+            blink: *blink,
+            owner: *owner,
+        },
+        ix_data::ReleaseHandle {},
+    )
+}
+
+pub fn set_moderator(admin: &Pubkey, moderator: &Pubkey, enabled: bool) -> Instruction {
+    instruction(
+        ix_accounts::SetModerator {
+            moderators: find_moderators_address().0,
+            protocol_config: protocol_config(),
+            admin: *admin,
+            system_program: system_program::ID,
+        },
+        ix_data::SetModerator {
+            moderator: *moderator,
+            enabled,
+        },
+    )
+}
+
+fn moderate(blink: &Pubkey, moderator: &Pubkey) -> ix_accounts::ModerateBlink {
+    ix_accounts::ModerateBlink {
+        blink: *blink,
+        moderators: find_moderators_address().0,
+        moderator: *moderator,
+    }
+}
+
+pub fn moderate_blink(
+    blink: &Pubkey,
+    moderator: &Pubkey,
+    status: ModerationStatus,
+    reason: u16,
+) -> Instruction {
+    instruction(
+        moderate(blink, moderator),
+        ix_data::ModerateBlink { status, reason },
+    )
+}
+
+pub fn clear_moderation(blink: &Pubkey, moderator: &Pubkey) -> Instruction {
+    instruction(moderate(blink, moderator), ix_data::ClearModeration {})
+}
+
+pub fn appeal_moderation(blink: &Pubkey, owner: &Pubkey) -> Instruction {
+    instruction(
+        ix_accounts::AppealModeration {
+            blink: *blink,
+            owner: *owner,
+        },
+        ix_data::AppealModeration {},
+    )
+}
+
+pub fn create_blink_template(
+    author: &Pubkey,
+    seed: u64,
+    name: String,
+    description: String,
+    image_url: String,
+    config: BlinkConfig,
+    fee_lamports: u64,
+) -> Instruction {
+    instruction(
+        ix_accounts::CreateBlinkTemplate {
+            template: find_template_address(author, seed).0,
+            author: *author,
+            system_program: system_program::ID,
+            protocol_config: protocol_config(),
+        },
+        ix_data::CreateBlinkTemplate {
+            seed,
+            name,
+            description,
+            image_url,
+            config,
+            fee_lamports,
+        },
+    )
+}
+
+pub fn set_template_fee(template: &Pubkey, author: &Pubkey, fee_lamports: u64) -> Instruction {
+    instruction(
+        ix_accounts::SetTemplateFee {
+            template: *template,
+            author: *author,
+        },
+        ix_data::SetTemplateFee { fee_lamports },
+    )
+}
+
+pub fn close_blink_template(template: &Pubkey, author: &Pubkey) -> Instruction {
+    instruction(
+        ix_accounts::CloseBlinkTemplate {
+            template: *template,
+            author: *author,
+        },
+        ix_data::CloseBlinkTemplate {},
+    )
+}
+
+// `author` is the template's author, who receives its fee; `index` is the owner
+// registry's current `blink_count`. The instruction fails if the template's fee has
+// risen above `max_fee_lamports`.
+pub fn create_blink_from_template(
+    template: &Pubkey,
+    author: &Pubkey,
+    owner: &Pubkey,
+    index: u64,
+    name: Option<String>,
+    config: Option<BlinkConfig>,
+    max_fee_lamports: u64,
+) -> Instruction {
+    let (registry, _) = find_owner_registry_address(owner);
+    instruction(
+        ix_accounts::CreateBlinkFromTemplate {
+            template: *template,
+            author: *author,
+            registry,
+            blink: find_blink_address(&registry, index).0,
+            owner: *owner,
+            system_program: system_program::ID,
+            protocol_config: protocol_config(),
+        },
+        ix_data::CreateBlinkFromTemplate {
+            name,
+            config,
+            max_fee_lamports,
+        },
+    )
+}
+
+// New blinks take the indices from `first_index`, the owner registry's current `blink_count`
+pub fn batch_create_blinks(
+    owner: &Pubkey,
+    first_index: u64,
+    items: Vec<BlinkInput>,
+) -> Instruction {
+    let (registry, _) = find_owner_registry_address(owner);
+    let blinks: Vec<AccountMeta> = (first_index..first_index + items.len() as u64)
+        .map(|index| AccountMeta::new(find_blink_address(&registry, index).0, false))
+        .collect();
+    instruction_with_remaining(
+        ix_accounts::BatchCreateBlinks {
+            registry,
+            owner: *owner,
+            system_program: system_program::ID,
+            protocol_config: protocol_config(),
+        },
+        ix_data::BatchCreateBlinks { items },
+        &blinks,
+    )
+}
+
+pub fn batch_update_blinks(
+    authority: &Pubkey,
+    owner: &Pubkey,
+    updates: Vec<(Pubkey, BlinkUpdate)>,
+) -> Instruction {
+    let (blinks, items): (Vec<Pubkey>, Vec<BlinkUpdate>) = updates.into_iter().unzip();
+    let blinks: Vec<AccountMeta> = blinks
+        .into_iter()
+        .map(|blink| AccountMeta::new(blink, false))
+        .collect();
+    instruction_with_remaining(
+        ix_accounts::BatchUpdateBlinks {
+            authority: *authority,
+            owner: *owner,
+            system_program: system_program::ID,
+            protocol_config: protocol_config(),
+        },
+        ix_data::BatchUpdateBlinks { items },
+        &blinks,
+    )
+}
+
+// Every blink must be counted in `registry`
+// Each blink is passed with its attributes PDA, which is closed along with it
+pub fn batch_delete_blinks(registry: &Pubkey, owner: &Pubkey, blinks: &[Pubkey]) -> Instruction {
+    let blinks: Vec<AccountMeta> = blinks
+        .iter()
+        .flat_map(|blink| {
+            [
+                AccountMeta::new(*blink, false),
+                AccountMeta::new(find_blink_attributes_address(blink).0, false),
+            ]
+        })
+        .collect();
+    instruction_with_remaining(
+        ix_accounts::BatchDeleteBlinks {
+            registry: *registry,
+            owner: *owner,
+        },
+        ix_data::BatchDeleteBlinks {},
+        &blinks,
+    )
+}
+
+pub fn set_blink_gate(blink: &Pubkey, authority: &Pubkey, gate: Option<TokenGate>) -> Instruction {
+    instruction(
+        manage_blink(blink, authority),
+        ix_data::SetBlinkGate { gate },
+    )
+}
+
+// `authority` must be the program's upgrade authority
+pub fn initialize_protocol_config(
+    authority: &Pubkey,
+    admin: &Pubkey,
+    treasury: &Pubkey,
+    fees: ProtocolFees,
+    features: ProtocolFeatures,
+) -> Instruction {
+    instruction(
+        ix_accounts::InitializeProtocolConfig {
+            protocol_config: protocol_config(),
+            authority: *authority,
+            program: bark_blinks::ID,
+            program_data: find_program_data_address().0,
+            system_program: system_program::ID,
+        },
+        ix_data::InitializeProtocolConfig {
+            admin: *admin,
+            treasury: *treasury,
+            fees,
+            features,
+        },
+    )
+}
+
+fn update_config(admin: &Pubkey) -> ix_accounts::UpdateProtocolConfig {
+    ix_accounts::UpdateProtocolConfig {
+        protocol_config: protocol_config(),
+        admin: *admin,
+    }
+}
+
+pub fn update_protocol_config(
+    admin: &Pubkey,
+    treasury: &Pubkey,
+    fees: ProtocolFees,
+    features: ProtocolFeatures,
+) -> Instruction {
+    instruction(
+        update_config(admin),
+        ix_data::UpdateProtocolConfig {
+            treasury: *treasury,
+            fees,
+            features,
+        },
+    )
+}
+
+pub fn set_module_paused(admin: &Pubkey, module: ProtocolModule, paused: bool) -> Instruction {
+    instruction(
+        update_config(admin),
+        ix_data::SetModulePaused { module, paused },
+    )
+}
+
+// Passing None cancels a pending handover
+pub fn propose_protocol_admin(admin: &Pubkey, new_admin: Option<Pubkey>) -> Instruction {
+    instruction(
+        update_config(admin),
+        ix_data::ProposeProtocolAdmin { new_admin },
+    )
+}
+
+pub fn accept_protocol_admin(new_admin: &Pubkey) -> Instruction {
+    instruction(
+        ix_accounts::AcceptProtocolAdmin {
+            protocol_config: protocol_config(),
+            new_admin: *new_admin,
+        },
+        ix_data::AcceptProtocolAdmin {},
+    )
+}
@@ -0,0 +1,9 @@
+//! Rust client for the BARK BLINKS program: instruction builders, PDA helpers, account
+//! decoders and `BlinkError` decoding.
+
+pub mod accounts;
+pub mod errors;
+pub mod instructions;
+pub mod pda;
+
+pub use bark_blinks::{id, ID};
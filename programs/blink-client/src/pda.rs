@@ -0,0 +1,27 @@
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+use solana_sdk::pubkey::Pubkey;
+
+pub use bark_blinks::attributes::find_blink_attributes_address;
+pub use bark_blinks::blink::{find_blink_address, find_owner_registry_address};
+pub use bark_blinks::gift::{find_gift_escrow_address, find_gift_vault_address};
+pub use bark_blinks::handle::find_handle_address;
+pub use bark_blinks::history::find_blink_history_address;
+pub use bark_blinks::hooks::find_hook_allowlist_address;
+pub use bark_blinks::moderation::find_moderators_address;
+pub use bark_blinks::poll::{find_poll_address, find_poll_vault_address, find_vote_receipt_address};
+pub use bark_blinks::protocol::find_protocol_config_address;
+pub use bark_blinks::template::find_template_address;
+pub use bark_blinks::vault::find_blink_vault_address;
+
+// Upgradeable loader account holding the program's upgrade authority
+pub fn find_program_data_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[bark_blinks::ID.as_ref()], &bpf_loader_upgradeable::ID)
+}
+
+// Metaplex metadata account for `mint`, used by NFT and mint creation
+pub fn find_metadata_address(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"metadata", mpl_token_metadata::ID.as_ref(), mint.as_ref()],
+        &mpl_token_metadata::ID,
+    )
+}
@@ -0,0 +1,338 @@
+use anchor_lang::error::ERROR_CODE_OFFSET;
+use bark_blinks::batch::{BlinkInput, BlinkUpdate};
+use bark_blinks::blink::{BlinkConfig, BlinkType, MAX_NAME_LEN};
+use bark_blinks::errors::BlinkError;
+use bark_blinks::execute::ExecuteBlinkArgs;
+use bark_blinks::payments::PaymentStatus;
+use bark_blinks::protocol::ProtocolModule;
+use bark_blinks_client::accounts::{
+    decode_blink, decode_collection, decode_donation, decode_nft, decode_payment, decode_swap,
+};
+use bark_blinks_client::errors::{
+    blink_error_from_code, decode_batch_item_index, decode_transaction_error, BLINK_ERRORS,
+};
+use bark_blinks_client::instructions;
+use bark_blinks_client::pda::{find_blink_address, find_owner_registry_address};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+#[path = "../../blink/tests/common/mod.rs"]
+mod common;
+
+use common::{
+    create_mint_account, create_token_account, get_account, process, program_test,
+    program_test_with_admin, token_balance, try_process, try_process_with_logs,
+};
+
+// Variant names in the order `BlinkError` declares them, read from the program source so
+// a variant missing from `BLINK_ERRORS` fails the test below
+fn declared_error_names() -> Vec<&'static str> {
+    include_str!("../../blink/src/errors.rs")
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| !line.starts_with("pub enum BlinkError"))
+        .skip(1)
+        .take_while(|line| *line != "}")
+        .filter(|line| !line.is_empty() && !line.starts_with("#[") && !line.starts_with("//"))
+        .map(|line| line.trim_end_matches(','))
+        .collect()
+}
+
+#[test]
+fn error_table_covers_every_variant() {
+    let names: Vec<String> = BLINK_ERRORS.iter().map(|error| error.name()).collect();
+    assert_eq!(names, declared_error_names());
+}
+
+#[test]
+fn error_table_matches_error_codes() {
+    for (index, error) in BLINK_ERRORS.iter().enumerate() {
+        let code = u32::from(*error);
+        assert_eq!(code, ERROR_CODE_OFFSET + index as u32);
+        assert_eq!(blink_error_from_code(code).unwrap().name(), error.name());
+    }
+    assert!(blink_error_from_code(ERROR_CODE_OFFSET - 1).is_none());
+    assert!(blink_error_from_code(ERROR_CODE_OFFSET + BLINK_ERRORS.len() as u32).is_none());
+}
+
+#[tokio::test]
+async fn blink_lifecycle() {
+    let mut context = program_test().start_with_context().await;
+    let owner = context.payer.pubkey();
+    let (registry, _) = find_owner_registry_address(&owner);
+    let (blink, _) = find_blink_address(&registry, 0);
+
+    let input = BlinkInput {
+        name: "Client Blink".to_string(),
+        description: "Created through the client".to_string(),
+        blink_type: BlinkType::Standard,
+        image_url: "https://example.com/image.png".to_string(),
+        config: BlinkConfig::Standard,
+    };
+    process(
+        &mut context,
+        &[instructions::create_blink(&owner, 0, input)],
+        &[],
+    )
+    .await;
+
+    let account = decode_blink(&get_account(&mut context, blink).await).unwrap();
+    assert_eq!(account.owner, owner);
+    assert_eq!(account.registry, registry);
+    assert_eq!(account.name, "Client Blink");
+
+    let update = BlinkUpdate {
+        name: "Updated Blink".to_string(),
+        description: "Updated through the client".to_string(),
+        image_url: "https://example.com/new-image.png".to_string(),
+        config: None,
+    };
+    let update_ix = instructions::update_blink(&blink, &owner, &owner, update);
+    process(&mut context, &[update_ix], &[]).await;
+
+    let account = decode_blink(&get_account(&mut context, blink).await).unwrap();
+    assert_eq!(account.name, "Updated Blink");
+
+    // Standard blinks have no action, so executing one fails with a program error
+    let execute_ix =
+        instructions::execute_blink(&blink, &owner, None, None, ExecuteBlinkArgs::default(), &[]);
+    let error = try_process(&mut context, &[execute_ix], &[])
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        decode_transaction_error(&error),
+        Some((0, BlinkError::InvalidBlinkType))
+    ));
+
+    let delete_ix = instructions::delete_blink(&blink, &registry, &owner, None);
+    process(&mut context, &[delete_ix], &[]).await;
+    assert!(context
+        .banks_client
+        .get_account(blink)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn donation_and_payment() {
+    let mut context = program_test().start_with_context().await;
+    let payer = context.payer.pubkey();
+    let recipient = Pubkey::new_unique();
+
+    let mint = Keypair::new();
+    create_mint_account(&mut context, &mint).await;
+    let payer_token_account =
+        create_token_account(&mut context, &mint.pubkey(), &payer, 1_000).await;
+    let recipient_token_account =
+        create_token_account(&mut context, &mint.pubkey(), &recipient, 0).await;
+
+    let donation = Keypair::new();
+    let donation_ix = instructions::create_donation(
+        &donation.pubkey(),
+        &payer,
+        &recipient,
+        &payer_token_account,
+        &recipient_token_account,
+        None,
+        None,
+        100,
+        "BARK".to_string(),
+        "Test donation".to_string(),
+    );
+    process(&mut context, &[donation_ix], &[&donation]).await;
+
+    let account = decode_donation(&get_account(&mut context, donation.pubkey()).await).unwrap();
+    assert_eq!(account.donor, payer);
+    assert_eq!(account.amount, 100);
+    assert_eq!(account.fee, 0);
+
+    let payment = Keypair::new();
+    let payment_ix = instructions::create_payment(
+        &payment.pubkey(),
+        &payer,
+        &recipient,
+        &payer_token_account,
+        &recipient_token_account,
+        None,
+        None,
+        250,
+        "BARK".to_string(),
+        "Test payment".to_string(),
+    );
+    process(&mut context, &[payment_ix], &[&payment]).await;
+
+    let account = decode_payment(&get_account(&mut context, payment.pubkey()).await).unwrap();
+    assert_eq!(account.amount, 250);
+    assert!(account.status == PaymentStatus::Completed);
+    assert_eq!(
+        token_balance(&mut context, recipient_token_account).await,
+        350
+    );
+
+    // Decoders check the account type
+    assert!(decode_payment(&get_account(&mut context, donation.pubkey()).await).is_err());
+    assert!(decode_donation(&get_account(&mut context, payer_token_account).await).is_err());
+}
+
+#[tokio::test]
+async fn nft_collection_and_swap() {
+    let mut context = program_test().start_with_context().await;
+    let owner = context.payer.pubkey();
+
+    let collection = Keypair::new();
+    let collection_ix = instructions::create_collection(
+        &collection.pubkey(),
+        &owner,
+        "Test Collection".to_string(),
+        "TCOL".to_string(),
+        "https://example.com/collection.json".to_string(),
+    );
+    process(&mut context, &[collection_ix], &[&collection]).await;
+
+    let account = decode_collection(&get_account(&mut context, collection.pubkey()).await).unwrap();
+    assert_eq!(account.owner, owner);
+    assert_eq!(account.symbol, "TCOL");
+
+    let mint = Keypair::new();
+    create_mint_account(&mut context, &mint).await;
+    let token_account = create_token_account(&mut context, &mint.pubkey(), &owner, 1).await;
+
+    let nft = Keypair::new();
+    let nft_ix = instructions::create_nft(
+        &nft.pubkey(),
+        &owner,
+        &mint.pubkey(),
+        &token_account,
+        "Test NFT".to_string(),
+        "TNFT".to_string(),
+        "https://example.com/nft-metadata.json".to_string(),
+    );
+    let add_ix =
+        instructions::add_nft_to_collection(&nft.pubkey(), &collection.pubkey(), &owner, &owner);
+    process(&mut context, &[nft_ix, add_ix], &[&nft, &mint]).await;
+
+    let account = decode_nft(&get_account(&mut context, nft.pubkey()).await).unwrap();
+    assert_eq!(account.mint, mint.pubkey());
+    assert_eq!(account.collection, collection.pubkey());
+
+    let counterparty = Pubkey::new_unique();
+    let mint_a = Keypair::new();
+    let mint_b = Keypair::new();
+    create_mint_account(&mut context, &mint_a).await;
+    create_mint_account(&mut context, &mint_b).await;
+    let token_a_source = create_token_account(&mut context, &mint_a.pubkey(), &owner, 500).await;
+    let token_a_destination =
+        create_token_account(&mut context, &mint_a.pubkey(), &counterparty, 0).await;
+    let token_b_source = create_token_account(&mut context, &mint_b.pubkey(), &owner, 500).await;
+    let token_b_destination =
+        create_token_account(&mut context, &mint_b.pubkey(), &counterparty, 0).await;
+
+    let swap = Keypair::new();
+    let create_swap_ix = instructions::create_swap(
+        &swap.pubkey(),
+        &owner,
+        &token_a_source,
+        &token_b_source,
+        50,
+        100,
+        1,
+    );
+    process(&mut context, &[create_swap_ix], &[&swap]).await;
+
+    let account = decode_swap(&get_account(&mut context, swap.pubkey()).await).unwrap();
+    assert_eq!(account.amount_a, 50);
+    assert!(account.executed_at.is_none());
+
+    let execute_swap_ix = instructions::execute_swap(
+        &swap.pubkey(),
+        &owner,
+        &token_a_source,
+        &token_a_destination,
+        &token_b_source,
+        &token_b_destination,
+        None,
+    );
+    process(&mut context, &[execute_swap_ix], &[]).await;
+
+    let account = decode_swap(&get_account(&mut context, swap.pubkey()).await).unwrap();
+    assert!(account.executed_at.is_some());
+    assert_eq!(token_balance(&mut context, token_a_destination).await, 50);
+    assert_eq!(token_balance(&mut context, token_b_destination).await, 100);
+}
+
+#[tokio::test]
+async fn paused_module_error_is_decoded() {
+    let admin = Keypair::new();
+    let mut context = program_test_with_admin(admin.pubkey()).start_with_context().await;
+    let owner = context.payer.pubkey();
+
+    let pause_ix =
+        instructions::set_module_paused(&admin.pubkey(), ProtocolModule::Collections, true);
+    process(&mut context, &[pause_ix], &[&admin]).await;
+
+    let collection = Keypair::new();
+    let collection_ix = instructions::create_collection(
+        &collection.pubkey(),
+        &owner,
+        "Paused".to_string(),
+        "PSD".to_string(),
+        "https://example.com/collection.json".to_string(),
+    );
+    let error = try_process(&mut context, &[collection_ix], &[&collection])
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        decode_transaction_error(&error),
+        Some((0, BlinkError::ModulePaused))
+    ));
+
+    // Only the admin can change the config
+    let unpause_ix = instructions::set_module_paused(&owner, ProtocolModule::Collections, false);
+    let error = try_process(&mut context, &[unpause_ix], &[])
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        decode_transaction_error(&error),
+        Some((0, BlinkError::Unauthorized))
+    ));
+}
+
+#[tokio::test]
+async fn batch_item_error_is_decoded() {
+    let mut context = program_test().start_with_context().await;
+    let owner = context.payer.pubkey();
+
+    let item = |name: &str, blink_type: BlinkType| BlinkInput {
+        name: name.to_string(),
+        description: "Batch blink".to_string(),
+        blink_type,
+        image_url: "https://example.com/image.png".to_string(),
+        config: BlinkConfig::Standard,
+    };
+    // The third item's name is over the limit
+    let items = vec![
+        item("First", BlinkType::Standard),
+        item("Second", BlinkType::Standard),
+        item(&"x".repeat(MAX_NAME_LEN + 1), BlinkType::Standard),
+    ];
+    let batch_ix = instructions::batch_create_blinks(&owner, 0, items);
+    let (result, logs) = try_process_with_logs(&mut context, &[batch_ix], &[]).await;
+    assert!(matches!(
+        decode_transaction_error(&result.unwrap_err()),
+        Some((0, BlinkError::NameTooLong))
+    ));
+    assert_eq!(decode_batch_item_index(&logs), Some(2));
+
+    // Errors outside a batch carry no item index
+    let single_ix = instructions::create_blink(
+        &owner,
+        0,
+        item(&"x".repeat(MAX_NAME_LEN + 1), BlinkType::Standard),
+    );
+    let (result, logs) = try_process_with_logs(&mut context, &[single_ix], &[]).await;
+    assert!(result.is_err());
+    assert_eq!(decode_batch_item_index(&logs), None);
+}
@@ -0,0 +1,287 @@
+use anchor_lang::prelude::*;
+use crate::blink::{Blink, PERMISSION_EDIT_METADATA};
+use crate::errors::BlinkError;
+use crate::protocol::{ProtocolConfig, ProtocolModule, PROTOCOL_CONFIG_SEED};
+use crate::utils::{close_account, resize_account};
+
+pub const BLINK_ATTRIBUTES_SEED: &[u8] = b"attributes";
+pub const MAX_ATTRIBUTES: usize = 16;
+pub const MAX_TRAIT_TYPE_LEN: usize = 32;
+pub const MAX_ATTRIBUTE_VALUE_LEN: usize = 64;
+pub const MAX_ABILITIES: usize = 8;
+pub const MAX_ABILITY_NAME_LEN: usize = 32;
+pub const MAX_ABILITY_DESCRIPTION_LEN: usize = 128;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub struct BlinkAttribute {
+    #[max_len(MAX_TRAIT_TYPE_LEN)]
+    pub trait_type: String,
+    #[max_len(MAX_ATTRIBUTE_VALUE_LEN)]
+    pub value: String,
+    // Optional number for traits that other programs compare or sum
+    pub numeric_value: Option<u64>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub struct BlinkAbility {
+    #[max_len(MAX_ABILITY_NAME_LEN)]
+    pub name: String,
+    #[max_len(MAX_ABILITY_DESCRIPTION_LEN)]
+    pub description: String,
+    // In seconds
+    pub cooldown: u32,
+    pub power_cost: u32,
+}
+
+// Traits live in their own PDA so blinks without them pay nothing; the account is
+// resized on every change to fit exactly what it holds.
+#[account]
+#[derive(InitSpace)]
+pub struct BlinkAttributes {
+    pub blink: Pubkey,
+    #[max_len(MAX_ATTRIBUTES)]
+    pub attributes: Vec<BlinkAttribute>,
+    #[max_len(MAX_ABILITIES)]
+    pub abilities: Vec<BlinkAbility>,
+    pub bump: u8,
+}
+
+impl BlinkAttributes {
+    // Size of an account with no attributes or abilities
+    pub const EMPTY_LEN: usize = 8 + 32 + 4 + 4 + 1;
+
+    pub fn space(&self) -> usize {
+        8 + self
+            .try_to_vec()
+            .map(|data| data.len())
+            .unwrap_or(Self::INIT_SPACE)
+    }
+
+    pub fn attribute(&self, trait_type: &str) -> Option<&BlinkAttribute> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.trait_type == trait_type)
+    }
+
+    pub fn ability(&self, name: &str) -> Option<&BlinkAbility> {
+        self.abilities.iter().find(|ability| ability.name == name)
+    }
+}
+
+pub fn find_blink_attributes_address(blink: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[BLINK_ATTRIBUTES_SEED, blink.as_ref()], &crate::ID)
+}
+
+#[derive(Accounts)]
+pub struct ManageBlinkAttributes<'info> {
+    pub blink: Account<'info, Blink>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        // Checked against the existing account, which is resized to fit its contents
+        space = attributes.data_len().max(BlinkAttributes::EMPTY_LEN),
+        seeds = [BLINK_ATTRIBUTES_SEED, blink.key().as_ref()],
+        bump
+    )]
+    pub attributes: Account<'info, BlinkAttributes>,
+    // Pays for any extra space the change needs
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: Receives the rent refund when the account shrinks; must be the blink owner
+    #[account(mut, address = blink.owner @ BlinkError::Unauthorized)]
+    pub owner: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+// Deleting a blink closes its attributes account too, if one was ever created
+pub(crate) fn close_blink_attributes<'info>(
+    attributes: &AccountInfo<'info>,
+    owner: &AccountInfo<'info>,
+) -> Result<()> {
+    if attributes.owner != &crate::ID {
+        return Ok(());
+    }
+
+    close_account(attributes, owner)
+}
+
+pub fn add_blink_attribute(
+    ctx: Context<ManageBlinkAttributes>,
+    trait_type: String,
+    value: String,
+    numeric_value: Option<u64>,
+) -> Result<()> {
+    prepare(ctx.accounts, ctx.bumps.attributes)?;
+    validate_attribute(&trait_type, &value)?;
+
+    let attributes = &mut ctx.accounts.attributes;
+    if attributes.attribute(&trait_type).is_some() {
+        return Err(BlinkError::AttributeAlreadyExists.into());
+    }
+    if attributes.attributes.len() >= MAX_ATTRIBUTES {
+        return Err(BlinkError::TooManyAttributes.into());
+    }
+
+    attributes.attributes.push(BlinkAttribute {
+        trait_type,
+        value,
+        numeric_value,
+    });
+
+    fit_to_contents(ctx.accounts)
+}
+
+pub fn update_blink_attribute(
+    ctx: Context<ManageBlinkAttributes>,
+    trait_type: String,
+    value: String,
+    numeric_value: Option<u64>,
+) -> Result<()> {
+    prepare(ctx.accounts, ctx.bumps.attributes)?;
+    validate_attribute(&trait_type, &value)?;
+
+    let attribute = ctx
+        .accounts
+        .attributes
+        .attributes
+        .iter_mut()
+        .find(|attribute| attribute.trait_type == trait_type)
+        .ok_or(BlinkError::AttributeNotFound)?;
+    attribute.value = value;
+    attribute.numeric_value = numeric_value;
+
+    fit_to_contents(ctx.accounts)
+}
+
+pub fn remove_blink_attribute(
+    ctx: Context<ManageBlinkAttributes>,
+    trait_type: String,
+) -> Result<()> {
+    prepare(ctx.accounts, ctx.bumps.attributes)?;
+
+    let attributes = &mut ctx.accounts.attributes;
+    let count = attributes.attributes.len();
+    attributes
+        .attributes
+        .retain(|attribute| attribute.trait_type != trait_type);
+    if attributes.attributes.len() == count {
+        return Err(BlinkError::AttributeNotFound.into());
+    }
+
+    fit_to_contents(ctx.accounts)
+}
+
+pub fn add_blink_ability(
+    ctx: Context<ManageBlinkAttributes>,
+    name: String,
+    description: String,
+    cooldown: u32,
+    power_cost: u32,
+) -> Result<()> {
+    prepare(ctx.accounts, ctx.bumps.attributes)?;
+    validate_ability(&name, &description)?;
+
+    let attributes = &mut ctx.accounts.attributes;
+    if attributes.ability(&name).is_some() {
+        return Err(BlinkError::AbilityAlreadyExists.into());
+    }
+    if attributes.abilities.len() >= MAX_ABILITIES {
+        return Err(BlinkError::TooManyAbilities.into());
+    }
+
+    attributes.abilities.push(BlinkAbility {
+        name,
+        description,
+        cooldown,
+        power_cost,
+    });
+
+    fit_to_contents(ctx.accounts)
+}
+
+pub fn update_blink_ability(
+    ctx: Context<ManageBlinkAttributes>,
+    name: String,
+    description: String,
+    cooldown: u32,
+    power_cost: u32,
+) -> Result<()> {
+    prepare(ctx.accounts, ctx.bumps.attributes)?;
+    validate_ability(&name, &description)?;
+
+    let ability = ctx
+        .accounts
+        .attributes
+        .abilities
+        .iter_mut()
+        .find(|ability| ability.name == name)
+        .ok_or(BlinkError::AbilityNotFound)?;
+    ability.description = description;
+    ability.cooldown = cooldown;
+    ability.power_cost = power_cost;
+
+    fit_to_contents(ctx.accounts)
+}
+
+pub fn remove_blink_ability(ctx: Context<ManageBlinkAttributes>, name: String) -> Result<()> {
+    prepare(ctx.accounts, ctx.bumps.attributes)?;
+
+    let attributes = &mut ctx.accounts.attributes;
+    let count = attributes.abilities.len();
+    attributes.abilities.retain(|ability| ability.name != name);
+    if attributes.abilities.len() == count {
+        return Err(BlinkError::AbilityNotFound.into());
+    }
+
+    fit_to_contents(ctx.accounts)
+}
+
+// Checks the caller may edit the blink and fills in a freshly created account
+fn prepare(accounts: &mut ManageBlinkAttributes, bump: u8) -> Result<()> {
+    accounts.protocol_config.check_not_paused(ProtocolModule::Blinks)?;
+    accounts
+        .blink
+        .check_permission(accounts.authority.key, PERMISSION_EDIT_METADATA)?;
+
+    let attributes = &mut accounts.attributes;
+    if attributes.blink == Pubkey::default() {
+        attributes.blink = accounts.blink.key();
+        attributes.bump = bump;
+    }
+
+    Ok(())
+}
+
+fn fit_to_contents(accounts: &ManageBlinkAttributes) -> Result<()> {
+    resize_account(
+        &accounts.attributes.to_account_info(),
+        accounts.attributes.space(),
+        &accounts.authority.to_account_info(),
+        &accounts.owner,
+        &accounts.system_program.to_account_info(),
+    )
+}
+
+fn validate_attribute(trait_type: &str, value: &str) -> Result<()> {
+    if trait_type.is_empty()
+        || trait_type.len() > MAX_TRAIT_TYPE_LEN
+        || value.len() > MAX_ATTRIBUTE_VALUE_LEN
+    {
+        return Err(BlinkError::InvalidAttribute.into());
+    }
+
+    Ok(())
+}
+
+fn validate_ability(name: &str, description: &str) -> Result<()> {
+    if name.is_empty()
+        || name.len() > MAX_ABILITY_NAME_LEN
+        || description.len() > MAX_ABILITY_DESCRIPTION_LEN
+    {
+        return Err(BlinkError::InvalidAbility.into());
+    }
+
+    Ok(())
+}
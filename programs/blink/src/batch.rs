@@ -0,0 +1,268 @@
+use anchor_lang::prelude::*;
+use crate::attributes::{close_blink_attributes, find_blink_attributes_address};
+use crate::blink::{
+    find_blink_address, register_blink, release_from_creator_registry, Blink, BlinkConfig,
+    BlinkType, OwnerRegistry, BLINK_SEED, OWNER_REGISTRY_SEED, PERMISSION_EDIT_METADATA,
+};
+use crate::errors::BlinkError;
+use crate::events::{BlinkDeleted, BlinkUpdated};
+use crate::handle::check_blink_handle;
+use crate::protocol::{ProtocolConfig, ProtocolModule, PROTOCOL_CONFIG_SEED};
+use crate::utils::{create_pda_account, resize_account};
+
+pub const MAX_BATCH_SIZE: usize = 10;
+// Names the failing item's index in the values logged with a batch error
+pub const BATCH_ITEM_LABEL: &str = "batch item";
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BlinkInput {
+    pub name: String,
+    pub description: String,
+    pub blink_type: BlinkType,
+    pub image_url: String,
+    pub config: BlinkConfig,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BlinkUpdate {
+    pub name: String,
+    pub description: String,
+    pub image_url: String,
+    pub config: Option<BlinkConfig>,
+}
+
+// Remaining accounts: one blink per item, in order. New blinks take the registry's
+// next indices.
+#[derive(Accounts)]
+pub struct BatchCreateBlinks<'info> {
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + OwnerRegistry::INIT_SPACE,
+        seeds = [OWNER_REGISTRY_SEED, owner.key().as_ref()],
+        bump
+    )]
+    pub registry: Account<'info, OwnerRegistry>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+// Remaining accounts: one blink per item, in order; all must belong to `owner`
+#[derive(Accounts)]
+pub struct BatchUpdateBlinks<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: Receives rent refunds when blinks shrink; checked against each blink's owner
+    #[account(mut)]
+    pub owner: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+// Remaining accounts: the blinks to close, each followed by its attributes PDA; all must
+// belong to `owner`
+#[derive(Accounts)]
+pub struct BatchDeleteBlinks<'info> {
+    #[account(mut, seeds = [OWNER_REGISTRY_SEED, owner.key().as_ref()], bump = registry.bump)]
+    pub registry: Account<'info, OwnerRegistry>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+pub fn batch_create_blinks<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BatchCreateBlinks<'info>>,
+    items: Vec<BlinkInput>,
+) -> Result<()> {
+    ctx.accounts.protocol_config.check_not_paused(ProtocolModule::Blinks)?;
+
+    let blinks = batch_accounts(ctx.remaining_accounts, items.len(), 1)?;
+    let clock = Clock::get()?;
+
+    for (item_index, (item, blink_info)) in items.into_iter().zip(blinks).enumerate() {
+        create_item(
+            &mut ctx.accounts.registry,
+            ctx.bumps.registry,
+            &ctx.accounts.owner,
+            &ctx.accounts.system_program,
+            blink_info,
+            item,
+            clock.unix_timestamp,
+        )
+        .map_err(|error| item_failed(item_index, error))?;
+    }
+
+    Ok(())
+}
+
+pub fn batch_update_blinks<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BatchUpdateBlinks<'info>>,
+    items: Vec<BlinkUpdate>,
+) -> Result<()> {
+    ctx.accounts.protocol_config.check_not_paused(ProtocolModule::Blinks)?;
+
+    let blinks = batch_accounts(ctx.remaining_accounts, items.len(), 1)?;
+    let clock = Clock::get()?;
+
+    for (item_index, (item, blink_info)) in items.into_iter().zip(blinks).enumerate() {
+        update_item(ctx.accounts, blink_info, item, clock.unix_timestamp)
+            .map_err(|error| item_failed(item_index, error))?;
+    }
+
+    Ok(())
+}
+
+pub fn batch_delete_blinks<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BatchDeleteBlinks<'info>>,
+) -> Result<()> {
+    let item_count = ctx.remaining_accounts.len() / 2;
+    let blinks = batch_accounts(ctx.remaining_accounts, item_count, 2)?;
+    let clock = Clock::get()?;
+
+    for (item_index, item_accounts) in blinks.chunks(2).enumerate() {
+        let (blink_info, attributes_info) = (&item_accounts[0], &item_accounts[1]);
+        delete_item(ctx.accounts, blink_info, attributes_info, clock.unix_timestamp)
+            .map_err(|error| item_failed(item_index, error))?;
+    }
+
+    Ok(())
+}
+
+fn batch_accounts<'a, 'info>(
+    remaining: &'a [AccountInfo<'info>],
+    item_count: usize,
+    accounts_per_item: usize,
+) -> Result<&'a [AccountInfo<'info>]> {
+    if item_count == 0 || item_count > MAX_BATCH_SIZE {
+        return Err(BlinkError::InvalidBatchSize.into());
+    }
+    if remaining.len() != item_count * accounts_per_item {
+        return Err(ErrorCode::AccountNotEnoughKeys.into());
+    }
+
+    Ok(remaining)
+}
+
+// The whole instruction fails on the first bad item, with that item's own error. Its index
+// is logged with the error as "Left: batch item" and "Right: <index>".
+fn item_failed(item_index: usize, error: Error) -> Error {
+    error.with_values((BATCH_ITEM_LABEL, item_index))
+}
+
+fn create_item<'info>(
+    registry: &mut Account<'info, OwnerRegistry>,
+    registry_bump: u8,
+    owner: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    blink_info: &'info AccountInfo<'info>,
+    item: BlinkInput,
+    now: i64,
+) -> Result<()> {
+    Blink::validate_metadata(&item.name, &item.description, &item.image_url)?;
+    if item.config.blink_type() != item.blink_type {
+        return Err(BlinkError::InvalidBlinkType.into());
+    }
+    item.config.validate(now)?;
+
+    let registry_key = registry.key();
+    let index = registry.blink_count.to_le_bytes();
+    let (expected, bump) = find_blink_address(&registry_key, registry.blink_count);
+    if blink_info.key() != expected {
+        return Err(BlinkError::InvalidProgramAddress.into());
+    }
+
+    // Create the blink the same way `create_blink` does through its `init` constraint
+    let space = Blink::space_for(&item.name, &item.description, &item.image_url, &item.config);
+    let blink_seeds: &[&[&[u8]]] = &[&[BLINK_SEED, registry_key.as_ref(), &index, &[bump]]];
+    create_pda_account(
+        blink_info,
+        space,
+        &owner.to_account_info(),
+        &system_program.to_account_info(),
+        blink_seeds,
+    )?;
+
+    let mut blink = Account::<Blink>::try_from_unchecked(blink_info)?;
+    blink.name = item.name;
+    blink.description = item.description;
+    blink.image_url = item.image_url;
+    blink.blink_type = item.blink_type;
+    blink.config = item.config;
+    register_blink(registry, registry_bump, &mut blink, bump, owner.key, now)?;
+
+    blink.exit(&crate::ID)
+}
+
+fn update_item<'info>(
+    accounts: &BatchUpdateBlinks<'info>,
+    blink_info: &'info AccountInfo<'info>,
+    item: BlinkUpdate,
+    now: i64,
+) -> Result<()> {
+    let mut blink = Account::<Blink>::try_from(blink_info)?;
+
+    if blink.owner != accounts.owner.key() {
+        return Err(BlinkError::Unauthorized.into());
+    }
+    blink.check_permission(accounts.authority.key, PERMISSION_EDIT_METADATA)?;
+    blink.apply_update(item.name, item.description, item.image_url, item.config, now)?;
+
+    let new_len = blink.space();
+    resize_account(
+        blink_info,
+        new_len,
+        &accounts.authority.to_account_info(),
+        &accounts.owner,
+        &accounts.system_program.to_account_info(),
+    )?;
+
+    emit!(BlinkUpdated {
+        blink: blink.key(),
+        authority: accounts.authority.key(),
+        name: blink.name.clone(),
+        description: blink.description.clone(),
+        image_url: blink.image_url.clone(),
+        config: blink.config.clone(),
+        timestamp: now,
+    });
+
+    blink.exit(&crate::ID)
+}
+
+fn delete_item<'info>(
+    accounts: &mut BatchDeleteBlinks<'info>,
+    blink_info: &'info AccountInfo<'info>,
+    attributes_info: &'info AccountInfo<'info>,
+    now: i64,
+) -> Result<()> {
+    let blink = Account::<Blink>::try_from(blink_info)?;
+    let registry = &mut accounts.registry;
+
+    if blink.owner != accounts.owner.key() {
+        return Err(BlinkError::Unauthorized.into());
+    }
+    blink.check_deletable()?;
+    // Batches don't take handle accounts, so a blink with a handle is deleted on its own
+    check_blink_handle(&blink, None)?;
+    // Nor do they take creators' registries, so neither is a blink received from another owner
+    release_from_creator_registry(&blink, &registry.key(), None)?;
+    if attributes_info.key() != find_blink_attributes_address(&blink.key()).0 {
+        return Err(BlinkError::InvalidProgramAddress.into());
+    }
+    close_blink_attributes(attributes_info, &accounts.owner.to_account_info())?;
+
+    registry.remove_blink(&blink.key());
+
+    emit!(BlinkDeleted {
+        blink: blink.key(),
+        owner: accounts.owner.key(),
+        registry: registry.key(),
+        index: blink.index,
+        timestamp: now,
+    });
+
+    blink.close(accounts.owner.to_account_info())
+}
@@ -1,6 +1,22 @@
 use anchor_lang::prelude::*;
+use crate::attributes::{close_blink_attributes, BLINK_ATTRIBUTES_SEED};
+use crate::errors::BlinkError;
+use crate::events::{
+    BlinkCreated, BlinkDeleted, BlinkEditorSet, BlinkOwnershipProposed, BlinkOwnershipTransferred,
+    BlinkPauseSet, BlinkScheduleSet, BlinkUpdated,
+};
+use crate::gate::TokenGate;
+use crate::handle::{check_blink_handle, BlinkHandle};
+use crate::hooks::BlinkHook;
+use crate::moderation::{Moderation, ModerationStatus};
+use crate::protocol::{ProtocolConfig, ProtocolModule, PROTOCOL_CONFIG_SEED};
+use crate::utils::{resize_account, validate_uri, verify_content_hash};
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub const MAX_NAME_LEN: usize = 50;
+pub const MAX_DESCRIPTION_LEN: usize = 200;
+pub const MAX_IMAGE_URL_LEN: usize = 200;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
 pub enum BlinkType {
     Standard,
     NFT,
@@ -10,39 +26,649 @@ pub enum BlinkType {
     Poll,
 }
 
+pub const MAX_SUGGESTED_AMOUNTS: usize = 5;
+pub const MAX_POLL_OPTIONS: usize = 10;
+pub const MAX_POLL_OPTION_LEN: usize = 32;
+
+// Per-type settings stored alongside the blink; the variant must match `blink_type`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub enum BlinkConfig {
+    Standard,
+    NFT {
+        mint: Pubkey,
+        collection: Option<Pubkey>,
+        // Executions stop minting once the mint's supply reaches this
+        max_supply: u64,
+    },
+    Donation {
+        recipient: Pubkey,
+        mint: Pubkey,
+        #[max_len(MAX_SUGGESTED_AMOUNTS)]
+        suggested_amounts: Vec<u64>,
+    },
+    Gift {
+        mint: Pubkey,
+        amount: u64,
+        recipient: Option<Pubkey>,
+        // Anyone holding this keypair can claim by having it sign; share it as the gift link
+        claim_key: Option<Pubkey>,
+        expires_at: i64,
+    },
+    Payment {
+        recipient: Pubkey,
+        mint: Pubkey,
+        price: u64,
+    },
+    Poll {
+        #[max_len(MAX_POLL_OPTIONS, MAX_POLL_OPTION_LEN)]
+        options: Vec<String>,
+    },
+}
+
+impl BlinkConfig {
+    // Serialized size of this particular configuration
+    pub fn space(&self) -> usize {
+        self.try_to_vec()
+            .map(|data| data.len())
+            .unwrap_or(BlinkConfig::INIT_SPACE)
+    }
+
+    pub fn blink_type(&self) -> BlinkType {
+        match self {
+            BlinkConfig::Standard => BlinkType::Standard,
+            BlinkConfig::NFT { .. } => BlinkType::NFT,
+            BlinkConfig::Donation { .. } => BlinkType::Donation,
+            BlinkConfig::Gift { .. } => BlinkType::Gift,
+            BlinkConfig::Payment { .. } => BlinkType::Payment,
+            BlinkConfig::Poll { .. } => BlinkType::Poll,
+        }
+    }
+
+    pub fn validate(&self, now: i64) -> Result<()> {
+        match self {
+            BlinkConfig::Standard => {}
+            BlinkConfig::NFT {
+                mint, max_supply, ..
+            } => {
+                require_keys_neq!(*mint, Pubkey::default(), BlinkError::InvalidMint);
+                require!(*max_supply > 0, BlinkError::InvalidBlinkConfig);
+            }
+            BlinkConfig::Donation {
+                recipient,
+                mint,
+                suggested_amounts,
+            } => {
+                require_keys_neq!(*recipient, Pubkey::default(), BlinkError::InvalidBlinkConfig);
+                require_keys_neq!(*mint, Pubkey::default(), BlinkError::InvalidMint);
+                require!(
+                    suggested_amounts.len() <= MAX_SUGGESTED_AMOUNTS,
+                    BlinkError::TooManySuggestedAmounts
+                );
+                require!(
+                    suggested_amounts.iter().all(|amount| *amount > 0),
+                    BlinkError::InvalidBlinkConfig
+                );
+            }
+            BlinkConfig::Gift {
+                mint,
+                amount,
+                recipient,
+                claim_key,
+                expires_at,
+            } => {
+                require_keys_neq!(*mint, Pubkey::default(), BlinkError::InvalidMint);
+                require!(*amount > 0, BlinkError::InvalidBlinkConfig);
+                // A gift must be claimable by someone: a named recipient or a claim key holder
+                require!(
+                    recipient.is_some() || claim_key.is_some(),
+                    BlinkError::InvalidBlinkConfig
+                );
+                require!(*expires_at > now, BlinkError::InvalidBlinkConfig);
+            }
+            BlinkConfig::Payment {
+                recipient,
+                mint,
+                price,
+            } => {
+                require_keys_neq!(*recipient, Pubkey::default(), BlinkError::InvalidBlinkConfig);
+                require_keys_neq!(*mint, Pubkey::default(), BlinkError::InvalidMint);
+                require!(*price > 0, BlinkError::InvalidBlinkConfig);
+            }
+            BlinkConfig::Poll { options } => {
+                require!(options.len() >= 2, BlinkError::InvalidBlinkConfig);
+                require!(options.len() <= MAX_POLL_OPTIONS, BlinkError::TooManyPollOptions);
+                require!(
+                    options
+                        .iter()
+                        .all(|option| !option.is_empty() && option.len() <= MAX_POLL_OPTION_LEN),
+                    BlinkError::PollOptionTooLong
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub const MAX_TRACKED_MINTS: usize = 4;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub struct MintVolume {
+    pub mint: Pubkey,
+    pub total: u64,
+}
+
+pub const MAX_EDITORS: usize = 5;
+
+// Permission bits that a blink owner can grant to delegated editors
+pub const PERMISSION_EDIT_METADATA: u8 = 1 << 0;
+pub const PERMISSION_PAUSE: u8 = 1 << 1;
+pub const PERMISSION_WITHDRAW: u8 = 1 << 2;
+pub const ALL_PERMISSIONS: u8 = PERMISSION_EDIT_METADATA | PERMISSION_PAUSE | PERMISSION_WITHDRAW;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub struct BlinkEditor {
+    pub editor: Pubkey,
+    pub permissions: u8,
+}
+
+// Rarity follows level, so it can only be earned through executions
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum BlinkRarity {
+    Common,
+    Uncommon,
+    Rare,
+    Epic,
+    Legendary,
+    Mythic,
+    Unique,
+}
+
+impl BlinkRarity {
+    pub fn for_level(level: u16) -> Self {
+        match level {
+            0..=4 => BlinkRarity::Common,
+            5..=9 => BlinkRarity::Uncommon,
+            10..=19 => BlinkRarity::Rare,
+            20..=34 => BlinkRarity::Epic,
+            35..=49 => BlinkRarity::Legendary,
+            50..=99 => BlinkRarity::Mythic,
+            _ => BlinkRarity::Unique,
+        }
+    }
+}
+
+// The most XP a single execution can earn
+pub const XP_PER_EXECUTION: u64 = 10;
+// Reaching level L takes XP_LEVEL_BASE * (L - 1)^2 XP
+pub const XP_LEVEL_BASE: u64 = 50;
+pub const MAX_LEVEL: u16 = 100;
+
+pub fn level_for_xp(xp: u64) -> u16 {
+    let mut level = 1;
+    while level < MAX_LEVEL && XP_LEVEL_BASE * (level as u64).pow(2) <= xp {
+        level += 1;
+    }
+    level
+}
+
+pub const BLINK_SEED: &[u8] = b"blink";
+pub const OWNER_REGISTRY_SEED: &[u8] = b"owner_registry";
+
 #[account]
+#[derive(InitSpace)]
 pub struct Blink {
     pub owner: Pubkey,
+    pub registry: Pubkey,
+    pub index: u64,
+    pub bump: u8,
+    #[max_len(MAX_NAME_LEN)]
     pub name: String,
+    #[max_len(MAX_DESCRIPTION_LEN)]
     pub description: String,
+    #[max_len(MAX_IMAGE_URL_LEN)]
     pub image_url: String,
+    // SHA-256 of the image, cleared whenever image_url changes
+    pub image_hash: Option<[u8; 32]>,
     pub blink_type: BlinkType,
+    pub config: BlinkConfig,
+    pub execution_count: u64,
+    #[max_len(MAX_TRACKED_MINTS)]
+    pub volumes: Vec<MintVolume>,
+    // Token vaults owned by this blink; it can't be deleted until they are closed
+    pub open_vaults: u16,
+    pub last_executed_at: i64,
+    pub starts_at: i64,
+    pub ends_at: Option<i64>,
+    pub paused: bool,
+    pub pending_owner: Option<Pubkey>,
+    #[max_len(MAX_EDITORS)]
+    pub editors: Vec<BlinkEditor>,
+    pub xp: u64,
+    pub level: u16,
+    pub rarity: BlinkRarity,
+    // Program invoked after donations, payments and gift claims
+    pub hook: Option<BlinkHook>,
+    // Set by protocol moderators; a frozen blink can only be closed by its owner
+    pub moderation: Option<Moderation>,
+    // Template this blink was created from, if any
+    pub template: Option<Pubkey>,
+    // Holding required of anyone executing the blink
+    pub gate: Option<TokenGate>,
+    // Handle account resolving to this blink; it is released when the blink is deleted
+    pub handle: Option<Pubkey>,
     pub created_at: i64,
     pub updated_at: i64,
 }
 
+impl Blink {
+    // Account size for the given metadata: strings and config are sized to their actual
+    // length, while counters and editor lists keep their full reservation so actions
+    // never need to realloc. Oversized inputs saturate here and are rejected by
+    // `validate_metadata`.
+    pub fn space_for(
+        name: &str,
+        description: &str,
+        image_url: &str,
+        config: &BlinkConfig,
+    ) -> usize {
+        8 + Blink::INIT_SPACE
+            - MAX_NAME_LEN.saturating_sub(name.len())
+            - MAX_DESCRIPTION_LEN.saturating_sub(description.len())
+            - MAX_IMAGE_URL_LEN.saturating_sub(image_url.len())
+            - BlinkConfig::INIT_SPACE.saturating_sub(config.space())
+    }
+
+    pub fn space(&self) -> usize {
+        Blink::space_for(&self.name, &self.description, &self.image_url, &self.config)
+    }
+
+    pub fn validate_metadata(name: &str, description: &str, image_url: &str) -> Result<()> {
+        if name.len() > MAX_NAME_LEN {
+            return Err(BlinkError::NameTooLong.into());
+        }
+
+        if description.len() > MAX_DESCRIPTION_LEN {
+            return Err(BlinkError::DescriptionTooLong.into());
+        }
+
+        if image_url.len() > MAX_IMAGE_URL_LEN {
+            return Err(BlinkError::UrlTooLong.into());
+        }
+        validate_uri(image_url)?;
+
+        Ok(())
+    }
+
+    // The owner holds every permission; editors only those they were granted
+    pub fn apply_update(
+        &mut self,
+        name: String,
+        description: String,
+        image_url: String,
+        config: Option<BlinkConfig>,
+        now: i64,
+    ) -> Result<()> {
+        Blink::validate_metadata(&name, &description, &image_url)?;
+
+        // The blink type is fixed at creation; only its settings can change
+        if let Some(config) = config {
+            if config.blink_type() != self.blink_type {
+                return Err(BlinkError::InvalidBlinkType.into());
+            }
+            config.validate(now)?;
+            self.config = config;
+        }
+
+        if image_url != self.image_url {
+            self.image_hash = None;
+        }
+
+        self.name = name;
+        self.description = description;
+        self.image_url = image_url;
+        self.updated_at = now;
+
+        Ok(())
+    }
+
+    pub fn check_not_frozen(&self) -> Result<()> {
+        if let Some(moderation) = &self.moderation {
+            if moderation.status == ModerationStatus::Frozen {
+                return Err(BlinkError::BlinkFrozen.into());
+            }
+        }
+
+        Ok(())
+    }
+
+    // The blink is the only authority over its vaults, so closing it first would lock
+    // their balances
+    pub fn check_deletable(&self) -> Result<()> {
+        if self.open_vaults > 0 {
+            return Err(BlinkError::BlinkVaultsOpen.into());
+        }
+
+        Ok(())
+    }
+
+    pub fn check_permission(&self, authority: &Pubkey, permission: u8) -> Result<()> {
+        self.check_not_frozen()?;
+
+        let granted = *authority == self.owner
+            || self.editors.iter().any(|editor| {
+                editor.editor == *authority && editor.permissions & permission == permission
+            });
+        if !granted {
+            return Err(BlinkError::Unauthorized.into());
+        }
+
+        Ok(())
+    }
+
+    // Every action that targets a blink must pass this before doing anything else
+    pub fn check_active(&self, now: i64) -> Result<()> {
+        self.check_not_frozen()?;
+        if self.paused {
+            return Err(BlinkError::BlinkPaused.into());
+        }
+        if now < self.starts_at {
+            return Err(BlinkError::BlinkNotStarted.into());
+        }
+        if matches!(self.ends_at, Some(ends_at) if now >= ends_at) {
+            return Err(BlinkError::BlinkExpired.into());
+        }
+
+        Ok(())
+    }
+
+    // Called by every action that targets this blink; `mint` is None for actions
+    // that move no tokens (votes).
+    pub fn record_execution(&mut self, mint: Option<Pubkey>, amount: u64, now: i64) -> Result<()> {
+        self.execution_count = self
+            .execution_count
+            .checked_add(1)
+            .ok_or(BlinkError::MathOverflow)?;
+        self.last_executed_at = now;
+
+        if let Some(mint) = mint {
+            if let Some(volume) = self.volumes.iter_mut().find(|volume| volume.mint == mint) {
+                volume.total = volume
+                    .total
+                    .checked_add(amount)
+                    .ok_or(BlinkError::MathOverflow)?;
+            } else if self.volumes.len() < MAX_TRACKED_MINTS {
+                self.volumes.push(MintVolume { mint, total: amount });
+            } else if let Some(smallest) = self
+                .volumes
+                .iter_mut()
+                .min_by_key(|volume| volume.total)
+                .filter(|volume| volume.total < amount)
+            {
+                // Once every slot is taken the smallest total gives way to a larger
+                // amount, so dust in throwaway mints can't crowd out real volume.
+                // Anything smaller goes untracked rather than failing the action.
+                *smallest = MintVolume { mint, total: amount };
+            }
+        }
+
+        Ok(())
+    }
+
+    // Owners earn nothing from their own blinks. Token amounts earn XP by order of
+    // magnitude, so splitting a transfer into dust earns nothing; mints and votes earn
+    // the full amount since supply and one vote per wallet already limit them.
+    pub fn execution_xp(&self, executor: &Pubkey, amount: u64) -> u64 {
+        if *executor == self.owner {
+            return 0;
+        }
+
+        match self.blink_type {
+            BlinkType::NFT | BlinkType::Poll => XP_PER_EXECUTION,
+            _ => (amount.checked_ilog10().unwrap_or(0) as u64).min(XP_PER_EXECUTION),
+        }
+    }
+
+    // Returns true when the award takes the blink to a new level
+    pub fn award_xp(&mut self, xp: u64) -> bool {
+        self.xp = self.xp.saturating_add(xp);
+
+        let level = level_for_xp(self.xp);
+        if level <= self.level {
+            return false;
+        }
+
+        self.level = level;
+        self.rarity = BlinkRarity::for_level(level);
+        true
+    }
+}
+
+pub const MAX_RECEIVED_BLINKS: usize = 16;
+pub const MAX_TRANSFERRED_BLINKS: usize = 64;
+
+// Tracks how many blinks an owner has created so their addresses can be re-derived
+// as [BLINK_SEED, registry, index] without scanning program accounts. A blink keeps its
+// address when ownership changes, so transfers are recorded on both owners' registries.
+#[account]
+#[derive(InitSpace)]
+pub struct OwnerRegistry {
+    pub owner: Pubkey,
+    pub blink_count: u64,
+    pub active_count: u64,
+    // Blinks created under other registries and transferred to this owner
+    #[max_len(MAX_RECEIVED_BLINKS)]
+    pub received: Vec<Pubkey>,
+    // Indices of blinks created here that now belong to someone else
+    #[max_len(MAX_TRANSFERRED_BLINKS)]
+    pub transferred: Vec<u64>,
+    pub bump: u8,
+}
+
+impl OwnerRegistry {
+    // Every blink the owner holds, plus deleted blinks created here, which no longer exist
+    pub fn blink_addresses(&self, registry: &Pubkey) -> Vec<Pubkey> {
+        (0..self.blink_count)
+            .filter(|index| !self.transferred.contains(index))
+            .map(|index| find_blink_address(registry, index).0)
+            .chain(self.received.iter().copied())
+            .collect()
+    }
+
+    pub fn send_blink(&mut self, registry: &Pubkey, blink: &Account<Blink>) -> Result<()> {
+        if blink.registry == *registry {
+            if self.transferred.len() >= MAX_TRANSFERRED_BLINKS {
+                return Err(BlinkError::RegistryFull.into());
+            }
+            self.transferred.push(blink.index);
+        } else {
+            self.received.retain(|received| *received != blink.key());
+        }
+        self.active_count = self.active_count.saturating_sub(1);
+
+        Ok(())
+    }
+
+    pub fn receive_blink(&mut self, registry: &Pubkey, blink: &Account<Blink>) -> Result<()> {
+        if blink.registry == *registry {
+            self.transferred.retain(|index| *index != blink.index);
+        } else {
+            if self.received.len() >= MAX_RECEIVED_BLINKS {
+                return Err(BlinkError::RegistryFull.into());
+            }
+            self.received.push(blink.key());
+        }
+        self.active_count = self
+            .active_count
+            .checked_add(1)
+            .ok_or(BlinkError::MathOverflow)?;
+
+        Ok(())
+    }
+
+    // The registry keeps its blink_count so indices are never reused
+    pub fn remove_blink(&mut self, blink: &Pubkey) {
+        self.received.retain(|received| received != blink);
+        self.active_count = self.active_count.saturating_sub(1);
+    }
+}
+
+// A deleted blink that was created under another owner's registry is still listed there as
+// transferred; `creator_registry` must be that registry so the entry can be dropped
+pub(crate) fn release_from_creator_registry(
+    blink: &Blink,
+    registry: &Pubkey,
+    creator_registry: Option<&mut Account<OwnerRegistry>>,
+) -> Result<()> {
+    match creator_registry {
+        None if blink.registry == *registry => Ok(()),
+        Some(creator_registry)
+            if blink.registry != *registry && creator_registry.key() == blink.registry =>
+        {
+            creator_registry.transferred.retain(|index| *index != blink.index);
+            Ok(())
+        }
+        _ => Err(BlinkError::InvalidRegistryAccount.into()),
+    }
+}
+
+pub fn find_owner_registry_address(owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[OWNER_REGISTRY_SEED, owner.as_ref()], &crate::ID)
+}
+
+pub fn find_blink_address(registry: &Pubkey, index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[BLINK_SEED, registry.as_ref(), &index.to_le_bytes()],
+        &crate::ID,
+    )
+}
+
 #[derive(Accounts)]
+#[instruction(
+    name: String,
+    description: String,
+    blink_type: BlinkType,
+    image_url: String,
+    config: BlinkConfig
+)]
 pub struct CreateBlink<'info> {
-    #[account(init, payer = owner, space = 8 + 32 + 4 + 200 + 4 + 1000 + 4 + 200 + 1 + 8 + 8)]
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + OwnerRegistry::INIT_SPACE,
+        seeds = [OWNER_REGISTRY_SEED, owner.key().as_ref()],
+        bump
+    )]
+    pub registry: Account<'info, OwnerRegistry>,
+    #[account(
+        init,
+        payer = owner,
+        space = Blink::space_for(&name, &description, &image_url, &config),
+        seeds = [BLINK_SEED, registry.key().as_ref(), &registry.blink_count.to_le_bytes()],
+        bump
+    )]
     pub blink: Account<'info, Blink>,
     #[account(mut)]
     pub owner: Signer<'info>,
     pub system_program: Program<'info, System>,
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
 }
 
 #[derive(Accounts)]
 pub struct UpdateBlink<'info> {
+    #[account(mut)]
+    pub blink: Account<'info, Blink>,
+    // Pays for any extra space the new metadata needs
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: Receives the rent refund when the blink shrinks; must be the blink owner
+    #[account(mut, address = blink.owner @ BlinkError::Unauthorized)]
+    pub owner: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+#[derive(Accounts)]
+pub struct ManageBlink<'info> {
+    #[account(mut)]
+    pub blink: Account<'info, Blink>,
+    pub authority: Signer<'info>,
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyBlinkContent<'info> {
+    pub blink: Account<'info, Blink>,
+}
+
+#[derive(Accounts)]
+pub struct TransferBlinkOwnership<'info> {
+    #[account(mut, has_one = owner)]
+    pub blink: Account<'info, Blink>,
+    pub owner: Signer<'info>,
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptBlinkOwnership<'info> {
+    #[account(mut)]
+    pub blink: Account<'info, Blink>,
+    #[account(mut)]
+    pub new_owner: Signer<'info>,
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    // Required when the blink has a handle, which moves to the new owner with it
+    #[account(mut)]
+    pub handle_account: Option<Account<'info, BlinkHandle>>,
+    #[account(
+        mut,
+        seeds = [OWNER_REGISTRY_SEED, blink.owner.as_ref()],
+        bump = previous_registry.bump
+    )]
+    pub previous_registry: Account<'info, OwnerRegistry>,
+    #[account(
+        init_if_needed,
+        payer = new_owner,
+        space = 8 + OwnerRegistry::INIT_SPACE,
+        seeds = [OWNER_REGISTRY_SEED, new_owner.key().as_ref()],
+        bump
+    )]
+    pub new_registry: Account<'info, OwnerRegistry>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetBlinkEditor<'info> {
     #[account(mut, has_one = owner)]
     pub blink: Account<'info, Blink>,
     pub owner: Signer<'info>,
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
 }
 
 #[derive(Accounts)]
 pub struct DeleteBlink<'info> {
     #[account(mut, has_one = owner, close = owner)]
     pub blink: Account<'info, Blink>,
+    // The current owner's registry, which may differ from the one the blink was created in
+    #[account(mut, seeds = [OWNER_REGISTRY_SEED, owner.key().as_ref()], bump = registry.bump)]
+    pub registry: Account<'info, OwnerRegistry>,
     #[account(mut)]
     pub owner: Signer<'info>,
+    // Required when the blink has a handle, which is released with it
+    #[account(mut, close = owner)]
+    pub handle_account: Option<Account<'info, BlinkHandle>>,
+    /// CHECK: The blink's attributes PDA, closed with the blink if it was ever created
+    #[account(mut, seeds = [BLINK_ATTRIBUTES_SEED, blink.key().as_ref()], bump)]
+    pub attributes: UncheckedAccount<'info>,
+    // Required when the blink was created by another owner, whose registry stops listing
+    // it as transferred
+    #[account(mut, address = blink.registry @ BlinkError::InvalidRegistryAccount)]
+    pub creator_registry: Option<Account<'info, OwnerRegistry>>,
 }
 
 pub fn create_blink(
@@ -51,26 +677,81 @@ pub fn create_blink(
     description: String,
     blink_type: BlinkType,
     image_url: String,
+    config: BlinkConfig,
 ) -> Result<()> {
+    ctx.accounts.protocol_config.check_not_paused(ProtocolModule::Blinks)?;
+
     let blink = &mut ctx.accounts.blink;
-    let owner = &ctx.accounts.owner;
     let clock = Clock::get()?;
 
-    if name.len() > 50 {
-        return Err(BlinkError::NameTooLong.into());
-    }
+    Blink::validate_metadata(&name, &description, &image_url)?;
 
-    if description.len() > 200 {
-        return Err(BlinkError::DescriptionTooLong.into());
+    if config.blink_type() != blink_type {
+        return Err(BlinkError::InvalidBlinkType.into());
     }
+    config.validate(clock.unix_timestamp)?;
 
-    blink.owner = *owner.key;
     blink.name = name;
     blink.description = description;
     blink.image_url = image_url;
     blink.blink_type = blink_type;
-    blink.created_at = clock.unix_timestamp;
-    blink.updated_at = clock.unix_timestamp;
+    blink.config = config;
+
+    register_blink(
+        &mut ctx.accounts.registry,
+        ctx.bumps.registry,
+        blink,
+        ctx.bumps.blink,
+        ctx.accounts.owner.key,
+        clock.unix_timestamp,
+    )
+}
+
+// Fills in the bookkeeping fields of a newly created blink and counts it in the
+// owner's registry; the caller sets its metadata and config.
+pub(crate) fn register_blink(
+    registry: &mut Account<OwnerRegistry>,
+    registry_bump: u8,
+    blink: &mut Account<Blink>,
+    bump: u8,
+    owner: &Pubkey,
+    now: i64,
+) -> Result<()> {
+    if registry.owner == Pubkey::default() {
+        registry.owner = *owner;
+        registry.bump = registry_bump;
+    }
+
+    blink.owner = *owner;
+    blink.registry = registry.key();
+    blink.index = registry.blink_count;
+    blink.bump = bump;
+    blink.starts_at = now;
+    blink.level = 1;
+    blink.rarity = BlinkRarity::Common;
+    blink.created_at = now;
+    blink.updated_at = now;
+
+    registry.blink_count = registry
+        .blink_count
+        .checked_add(1)
+        .ok_or(BlinkError::MathOverflow)?;
+    registry.active_count = registry
+        .active_count
+        .checked_add(1)
+        .ok_or(BlinkError::MathOverflow)?;
+
+    emit!(BlinkCreated {
+        blink: blink.key(),
+        owner: *owner,
+        registry: registry.key(),
+        index: blink.index,
+        name: blink.name.clone(),
+        blink_type: blink.blink_type.clone(),
+        config: blink.config.clone(),
+        template: blink.template,
+        timestamp: now,
+    });
 
     Ok(())
 }
@@ -80,27 +761,249 @@ pub fn update_blink(
     name: String,
     description: String,
     image_url: String,
+    config: Option<BlinkConfig>,
 ) -> Result<()> {
+    ctx.accounts.protocol_config.check_not_paused(ProtocolModule::Blinks)?;
+
     let blink = &mut ctx.accounts.blink;
     let clock = Clock::get()?;
 
-    if name.len() > 50 {
-        return Err(BlinkError::NameTooLong.into());
+    blink.check_permission(ctx.accounts.authority.key, PERMISSION_EDIT_METADATA)?;
+    blink.apply_update(name, description, image_url, config, clock.unix_timestamp)?;
+
+    // Grow or shrink the account to fit the new metadata
+    let new_len = blink.space();
+    resize_account(
+        &blink.to_account_info(),
+        new_len,
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.owner,
+        &ctx.accounts.system_program.to_account_info(),
+    )?;
+
+    emit!(BlinkUpdated {
+        blink: blink.key(),
+        authority: ctx.accounts.authority.key(),
+        name: blink.name.clone(),
+        description: blink.description.clone(),
+        image_url: blink.image_url.clone(),
+        config: blink.config.clone(),
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn delete_blink(ctx: Context<DeleteBlink>) -> Result<()> {
+    ctx.accounts.blink.check_deletable()?;
+    check_blink_handle(&ctx.accounts.blink, ctx.accounts.handle_account.as_ref())?;
+    close_blink_attributes(
+        &ctx.accounts.attributes.to_account_info(),
+        &ctx.accounts.owner.to_account_info(),
+    )?;
+
+    // The account will be automatically closed and lamports returned to the owner.
+    // This is allowed even while the blink is frozen so the owner can recover rent.
+    let registry = &mut ctx.accounts.registry;
+    release_from_creator_registry(
+        &ctx.accounts.blink,
+        &registry.key(),
+        ctx.accounts.creator_registry.as_mut(),
+    )?;
+    registry.remove_blink(&ctx.accounts.blink.key());
+
+    emit!(BlinkDeleted {
+        blink: ctx.accounts.blink.key(),
+        owner: ctx.accounts.owner.key(),
+        registry: registry.key(),
+        index: ctx.accounts.blink.index,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn set_blink_paused(ctx: Context<ManageBlink>, paused: bool) -> Result<()> {
+    ctx.accounts.protocol_config.check_not_paused(ProtocolModule::Blinks)?;
+
+    let blink = &mut ctx.accounts.blink;
+    let clock = Clock::get()?;
+
+    blink.check_permission(ctx.accounts.authority.key, PERMISSION_PAUSE)?;
+
+    blink.paused = paused;
+    blink.updated_at = clock.unix_timestamp;
+
+    emit!(BlinkPauseSet {
+        blink: blink.key(),
+        authority: ctx.accounts.authority.key(),
+        paused,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn set_blink_schedule(
+    ctx: Context<ManageBlink>,
+    starts_at: i64,
+    ends_at: Option<i64>,
+) -> Result<()> {
+    ctx.accounts.protocol_config.check_not_paused(ProtocolModule::Blinks)?;
+
+    let blink = &mut ctx.accounts.blink;
+    let clock = Clock::get()?;
+
+    blink.check_permission(ctx.accounts.authority.key, PERMISSION_EDIT_METADATA)?;
+
+    if matches!(ends_at, Some(ends_at) if ends_at <= starts_at) {
+        return Err(BlinkError::InvalidSchedule.into());
     }
 
-    if description.len() > 200 {
-        return Err(BlinkError::DescriptionTooLong.into());
+    blink.starts_at = starts_at;
+    blink.ends_at = ends_at;
+    blink.updated_at = clock.unix_timestamp;
+
+    emit!(BlinkScheduleSet {
+        blink: blink.key(),
+        authority: ctx.accounts.authority.key(),
+        starts_at,
+        ends_at,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Passing None cancels a pending transfer
+pub fn transfer_blink_ownership(
+    ctx: Context<TransferBlinkOwnership>,
+    new_owner: Option<Pubkey>,
+) -> Result<()> {
+    ctx.accounts.protocol_config.check_not_paused(ProtocolModule::Blinks)?;
+
+    let blink = &mut ctx.accounts.blink;
+    let clock = Clock::get()?;
+
+    blink.check_not_frozen()?;
+
+    if new_owner == Some(blink.owner) {
+        return Err(BlinkError::InvalidRecipient.into());
     }
 
-    blink.name = name;
-    blink.description = description;
-    blink.image_url = image_url;
+    blink.pending_owner = new_owner;
+    blink.updated_at = clock.unix_timestamp;
+
+    emit!(BlinkOwnershipProposed {
+        blink: blink.key(),
+        owner: blink.owner,
+        pending_owner: new_owner,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn accept_blink_ownership(ctx: Context<AcceptBlinkOwnership>) -> Result<()> {
+    ctx.accounts.protocol_config.check_not_paused(ProtocolModule::Blinks)?;
+
+    let blink = &mut ctx.accounts.blink;
+    let new_owner = &ctx.accounts.new_owner;
+    let clock = Clock::get()?;
+
+    blink.check_not_frozen()?;
+
+    if blink.pending_owner != Some(new_owner.key()) {
+        return Err(BlinkError::Unauthorized.into());
+    }
+    check_blink_handle(blink, ctx.accounts.handle_account.as_ref())?;
+    if let Some(handle_account) = ctx.accounts.handle_account.as_mut() {
+        handle_account.owner = new_owner.key();
+    }
+
+    // The blink keeps its address, so the registries record the move
+    let previous_registry_key = ctx.accounts.previous_registry.key();
+    ctx.accounts.previous_registry.send_blink(&previous_registry_key, blink)?;
+    let new_registry_key = ctx.accounts.new_registry.key();
+    let new_registry = &mut ctx.accounts.new_registry;
+    if new_registry.owner == Pubkey::default() {
+        new_registry.owner = new_owner.key();
+        new_registry.bump = ctx.bumps.new_registry;
+    }
+    new_registry.receive_blink(&new_registry_key, blink)?;
+
+    // Delegations were granted by the previous owner and don't carry over
+    let previous_owner = blink.owner;
+    blink.owner = new_owner.key();
+    blink.pending_owner = None;
+    blink.editors.clear();
+    blink.updated_at = clock.unix_timestamp;
+
+    emit!(BlinkOwnershipTransferred {
+        blink: blink.key(),
+        previous_owner,
+        new_owner: new_owner.key(),
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Zero permissions removes the editor
+pub fn set_blink_editor(
+    ctx: Context<SetBlinkEditor>,
+    editor: Pubkey,
+    permissions: u8,
+) -> Result<()> {
+    ctx.accounts.protocol_config.check_not_paused(ProtocolModule::Blinks)?;
+
+    let blink = &mut ctx.accounts.blink;
+    let clock = Clock::get()?;
+
+    blink.check_not_frozen()?;
+
+    if permissions & !ALL_PERMISSIONS != 0 {
+        return Err(BlinkError::InvalidPermissions.into());
+    }
+
+    blink.editors.retain(|existing| existing.editor != editor);
+    if permissions != 0 {
+        if blink.editors.len() >= MAX_EDITORS {
+            return Err(BlinkError::TooManyEditors.into());
+        }
+        blink.editors.push(BlinkEditor {
+            editor,
+            permissions,
+        });
+    }
     blink.updated_at = clock.unix_timestamp;
 
+    emit!(BlinkEditorSet {
+        blink: blink.key(),
+        owner: ctx.accounts.owner.key(),
+        editor,
+        permissions,
+        timestamp: clock.unix_timestamp,
+    });
+
     Ok(())
 }
 
-pub fn delete_blink(_ctx: Context<DeleteBlink>) -> Result<()> {
-    // The account will be automatically closed and lamports returned to the owner
+pub fn set_blink_image_hash(ctx: Context<ManageBlink>, image_hash: Option<[u8; 32]>) -> Result<()> {
+    ctx.accounts.protocol_config.check_not_paused(ProtocolModule::Blinks)?;
+
+    let blink = &mut ctx.accounts.blink;
+    let clock = Clock::get()?;
+
+    blink.check_permission(ctx.accounts.authority.key, PERMISSION_EDIT_METADATA)?;
+
+    blink.image_hash = image_hash;
+    blink.updated_at = clock.unix_timestamp;
+
     Ok(())
-}
\ No newline at end of file
+}
+
+// Only practical for small payloads since `data` must fit in a transaction
+pub fn verify_blink_content(ctx: Context<VerifyBlinkContent>, data: Vec<u8>) -> Result<()> {
+    verify_content_hash(ctx.accounts.blink.image_hash, &data)
+}
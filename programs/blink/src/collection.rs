@@ -1,30 +1,55 @@
 use anchor_lang::prelude::*;
+use crate::errors::BlinkError;
+use crate::events::{CollectionCreated, NFTAddedToCollection};
+use crate::nft::{validate_token_metadata, MAX_NFT_NAME_LEN, MAX_SYMBOL_LEN, MAX_URI_LEN};
+use crate::protocol::{ProtocolConfig, ProtocolModule, PROTOCOL_CONFIG_SEED};
 
 #[account]
+#[derive(InitSpace)]
 pub struct Collection {
     pub owner: Pubkey,
+    #[max_len(MAX_NFT_NAME_LEN)]
     pub name: String,
+    #[max_len(MAX_SYMBOL_LEN)]
     pub symbol: String,
+    #[max_len(MAX_URI_LEN)]
     pub uri: String,
     pub created_at: i64,
 }
 
+impl Collection {
+    pub fn space_for(name: &str, symbol: &str, uri: &str) -> usize {
+        8 + Collection::INIT_SPACE
+            - MAX_NFT_NAME_LEN.saturating_sub(name.len())
+            - MAX_SYMBOL_LEN.saturating_sub(symbol.len())
+            - MAX_URI_LEN.saturating_sub(uri.len())
+    }
+}
+
 #[derive(Accounts)]
+#[instruction(name: String, symbol: String, uri: String)]
 pub struct CreateCollection<'info> {
-    #[account(init, payer = owner, space = 8 + 32 + 4 + 50 + 4 + 10 + 4 + 200 + 8)]
+    #[account(init, payer = owner, space = Collection::space_for(&name, &symbol, &uri))]
     pub collection: Account<'info, Collection>,
     #[account(mut)]
     pub owner: Signer<'info>,
     pub system_program: Program<'info, System>,
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
 }
 
+// Membership can gate blinks, so the NFT's owner has to agree to it as well as the
+// collection's
 #[derive(Accounts)]
 pub struct AddNFTToCollection<'info> {
-    #[account(mut)]
+    #[account(mut, constraint = nft.owner == nft_owner.key() @ BlinkError::Unauthorized)]
     pub nft: Account<'info, super::nft::NFT>,
     #[account(mut, has_one = owner)]
     pub collection: Account<'info, Collection>,
     pub owner: Signer<'info>,
+    pub nft_owner: Signer<'info>,
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
 }
 
 pub fn create_collection(
@@ -33,17 +58,13 @@ pub fn create_collection(
     symbol: String,
     uri: String,
 ) -> Result<()> {
+    ctx.accounts.protocol_config.check_not_paused(ProtocolModule::Collections)?;
+
     let collection = &mut ctx.accounts.collection;
     let owner = &ctx.accounts.owner;
     let clock = Clock::get()?;
 
-    if name.len() > 32 {
-        return Err(BlinkError::NameTooLong.into());
-    }
-
-    if symbol.len() > 10 {
-        return Err(BlinkError::SymbolTooLong.into());
-    }
+    validate_token_metadata(&name, &symbol, &uri)?;
 
     collection.owner = *owner.key;
     collection.name = name;
@@ -51,14 +72,35 @@ pub fn create_collection(
     collection.uri = uri;
     collection.created_at = clock.unix_timestamp;
 
+    emit!(CollectionCreated {
+        collection: collection.key(),
+        owner: collection.owner,
+        name: collection.name.clone(),
+        symbol: collection.symbol.clone(),
+        uri: collection.uri.clone(),
+        timestamp: clock.unix_timestamp,
+    });
+
     Ok(())
 }
 
 pub fn add_nft_to_collection(ctx: Context<AddNFTToCollection>) -> Result<()> {
+    ctx.accounts.protocol_config.check_not_paused(ProtocolModule::Collections)?;
+
     let nft = &mut ctx.accounts.nft;
     let collection = &ctx.accounts.collection;
+    let clock = Clock::get()?;
 
+    let previous_collection = nft.collection;
     nft.collection = collection.key();
 
+    emit!(NFTAddedToCollection {
+        nft: nft.key(),
+        collection: collection.key(),
+        previous_collection,
+        owner: ctx.accounts.owner.key(),
+        timestamp: clock.unix_timestamp,
+    });
+
     Ok(())
 }
\ No newline at end of file
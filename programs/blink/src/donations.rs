@@ -1,19 +1,33 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::blink::{Blink, BlinkConfig};
+use crate::errors::BlinkError;
+use crate::events::DonationCreated;
+use crate::gate::check_token_gate;
+use crate::history::{record_blink_execution, BlinkHistory};
+use crate::hooks::{run_post_action_hook, BlinkHookPayload, HookAction};
+use crate::protocol::{
+    collect_token_fee, FeeSource, ProtocolConfig, ProtocolModule, PROTOCOL_CONFIG_SEED,
+};
+use crate::vault::check_blink_vault;
 
 #[account]
 pub struct Donation {
     pub donor: Pubkey,
     pub recipient: Pubkey,
     pub amount: u64,
+    // Protocol fee taken out of `amount`
+    pub fee: u64,
     pub currency: String,
     pub message: String,
     pub timestamp: i64,
 }
 
+// Remaining accounts: the token gate proof when the blink is gated, then the blink's
+// hook accounts when it has a hook
 #[derive(Accounts)]
 pub struct CreateDonation<'info> {
-    #[account(init, payer = donor, space = 8 + 32 + 32 + 8 + 4 + 10 + 4 + 200 + 8)]
+    #[account(init, payer = donor, space = 8 + 32 + 32 + 8 + 8 + 4 + 10 + 4 + 200 + 8)]
     pub donation: Account<'info, Donation>,
     #[account(mut)]
     pub donor: Signer<'info>,
@@ -25,14 +39,26 @@ pub struct CreateDonation<'info> {
     pub recipient_token_account: Account<'info, TokenAccount>,
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    // Required when a protocol fee is owed
+    #[account(mut)]
+    pub treasury_token_account: Option<Account<'info, TokenAccount>>,
+    // When set, the donation is routed to this blink's vault instead of a wallet
+    #[account(mut)]
+    pub blink: Option<Account<'info, Blink>>,
+    #[account(mut)]
+    pub history: Option<Account<'info, BlinkHistory>>,
 }
 
-pub fn create_donation(
-    ctx: Context<CreateDonation>,
+pub fn create_donation<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreateDonation<'info>>,
     amount: u64,
     currency: String,
     message: String,
 ) -> Result<()> {
+    ctx.accounts.protocol_config.check_not_paused(ProtocolModule::Donations)?;
+
     let donation = &mut ctx.accounts.donation;
     let clock = Clock::get()?;
 
@@ -44,6 +70,32 @@ pub fn create_donation(
         return Err(BlinkError::MessageTooLong.into());
     }
 
+    let mut hook_accounts = ctx.remaining_accounts;
+    if let Some(blink) = &ctx.accounts.blink {
+        if !ctx.accounts.protocol_config.features.blink_routing {
+            return Err(BlinkError::FeatureDisabled.into());
+        }
+        ctx.accounts.protocol_config.check_not_paused(ProtocolModule::Blinks)?;
+        blink.check_active(clock.unix_timestamp)?;
+        if amount == 0 {
+            return Err(BlinkError::InsufficientFunds.into());
+        }
+        hook_accounts = check_token_gate(blink, ctx.accounts.donor.key, hook_accounts)?;
+        if ctx.accounts.recipient.key() != blink.key() {
+            return Err(BlinkError::InvalidRecipient.into());
+        }
+        check_blink_vault(&blink.key(), &ctx.accounts.recipient_token_account)?;
+        // A blink only takes donations in its configured mint
+        match &blink.config {
+            BlinkConfig::Donation { mint, .. } => {
+                if ctx.accounts.recipient_token_account.mint != *mint {
+                    return Err(BlinkError::InvalidMint.into());
+                }
+            }
+            _ => return Err(BlinkError::InvalidBlinkType.into()),
+        }
+    }
+
     donation.donor = ctx.accounts.donor.key();
     donation.recipient = ctx.accounts.recipient.key();
     donation.amount = amount;
@@ -51,7 +103,18 @@ pub fn create_donation(
     donation.message = message;
     donation.timestamp = clock.unix_timestamp;
 
-    // Transfer tokens from donor to recipient
+    let fee = collect_token_fee(
+        &ctx.accounts.protocol_config,
+        FeeSource::Donation,
+        amount,
+        &ctx.accounts.donor_token_account,
+        &ctx.accounts.donor.to_account_info(),
+        ctx.accounts.treasury_token_account.as_ref(),
+        &ctx.accounts.token_program.to_account_info(),
+    )?;
+    donation.fee = fee;
+
+    // Transfer the rest from donor to recipient
     let cpi_accounts = Transfer {
         from: ctx.accounts.donor_token_account.to_account_info(),
         to: ctx.accounts.recipient_token_account.to_account_info(),
@@ -59,7 +122,44 @@ pub fn create_donation(
     };
     let cpi_program = ctx.accounts.token_program.to_account_info();
     let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-    token::transfer(cpi_ctx, amount)?;
+    token::transfer(cpi_ctx, amount - fee)?;
+
+    emit!(DonationCreated {
+        donation: donation.key(),
+        donor: donation.donor,
+        recipient: donation.recipient,
+        mint: ctx.accounts.recipient_token_account.mint,
+        amount,
+        fee,
+        currency: donation.currency.clone(),
+        blink: ctx.accounts.blink.as_ref().map(|blink| blink.key()),
+        timestamp: clock.unix_timestamp,
+    });
+
+    if let Some(blink) = ctx.accounts.blink.as_mut() {
+        let mint = ctx.accounts.recipient_token_account.mint;
+        record_blink_execution(
+            blink,
+            ctx.accounts.history.as_mut(),
+            ctx.accounts.donor.key,
+            Some(mint),
+            amount,
+        )?;
+
+        let payload = BlinkHookPayload {
+            action: HookAction::Donation,
+            blink: blink.key(),
+            payer: ctx.accounts.donor.key(),
+            mint,
+            amount,
+        };
+        run_post_action_hook(
+            blink,
+            &ctx.accounts.donor.to_account_info(),
+            payload,
+            hook_accounts,
+        )?;
+    }
 
     Ok(())
 }
\ No newline at end of file
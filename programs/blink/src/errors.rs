@@ -72,4 +72,121 @@ pub enum BlinkError {
     InvalidAssociatedTokenProgram,
     #[msg("Invalid rent sysvar")]
     InvalidRentSysvar,
+    #[msg("Invalid blink configuration")]
+    InvalidBlinkConfig,
+    #[msg("Too many suggested amounts")]
+    TooManySuggestedAmounts,
+    #[msg("Too many poll options")]
+    TooManyPollOptions,
+    #[msg("Poll option is empty or too long")]
+    PollOptionTooLong,
+    #[msg("Poll is closed")]
+    PollClosed,
+    #[msg("Wallet has already voted in this poll")]
+    AlreadyVoted,
+    #[msg("Invalid poll option")]
+    InvalidPollOption,
+    #[msg("Poll close time must be in the future")]
+    InvalidPollCloseTime,
+    #[msg("Gift has already been claimed or reclaimed")]
+    GiftAlreadySettled,
+    #[msg("Gift has expired")]
+    GiftExpired,
+    #[msg("Gift has not expired yet")]
+    GiftNotExpired,
+    #[msg("Invalid gift secret")]
+    InvalidGiftSecret,
+    #[msg("Invalid recipient")]
+    InvalidRecipient,
+    // Reserved: volume tracking now evicts or skips a mint when its slots are full instead
+    // of failing. Kept so later codes don't shift.
+    #[msg("Reserved: no longer returned")]
+    TooManyTrackedMints,
+    #[msg("History account does not belong to this blink")]
+    InvalidBlinkHistory,
+    #[msg("Blink is paused")]
+    BlinkPaused,
+    #[msg("Blink has expired")]
+    BlinkExpired,
+    #[msg("Blink has not started yet")]
+    BlinkNotStarted,
+    #[msg("Blink end time must be after its start time")]
+    InvalidSchedule,
+    #[msg("Invalid editor permissions")]
+    InvalidPermissions,
+    #[msg("Blink already has the maximum number of editors")]
+    TooManyEditors,
+    #[msg("URL too long")]
+    UrlTooLong,
+    #[msg("Attribute trait type or value is empty or too long")]
+    InvalidAttribute,
+    #[msg("Blink already has the maximum number of attributes")]
+    TooManyAttributes,
+    #[msg("Attribute already exists")]
+    AttributeAlreadyExists,
+    #[msg("Attribute not found")]
+    AttributeNotFound,
+    #[msg("Ability name or description is empty or too long")]
+    InvalidAbility,
+    #[msg("Blink already has the maximum number of abilities")]
+    TooManyAbilities,
+    #[msg("Ability already exists")]
+    AbilityAlreadyExists,
+    #[msg("Ability not found")]
+    AbilityNotFound,
+    #[msg("Hook allowlist is full")]
+    TooManyHookPrograms,
+    #[msg("Hook program is not on the allowlist")]
+    HookProgramNotAllowed,
+    #[msg("Blink hook could not be run")]
+    HookUnavailable,
+    #[msg("Handle must be 3-32 lowercase letters, digits, '-' or '_'")]
+    InvalidHandle,
+    #[msg("Handle is reserved")]
+    HandleReserved,
+    #[msg("Blink has been frozen by a moderator")]
+    BlinkFrozen,
+    #[msg("Moderator list is full")]
+    TooManyModerators,
+    #[msg("Blink is not under moderation")]
+    NotModerated,
+    #[msg("An appeal has already been filed")]
+    AppealAlreadyFiled,
+    #[msg("Batch must contain between 1 and 10 items")]
+    InvalidBatchSize,
+    #[msg("URI must use https, ipfs or ar")]
+    InvalidUriScheme,
+    #[msg("No content hash has been set")]
+    ContentHashNotSet,
+    #[msg("Content does not match the stored hash")]
+    ContentHashMismatch,
+    #[msg("Executor does not hold the tokens required by this blink")]
+    TokenGateNotMet,
+    #[msg("Fee basis points cannot exceed 10000")]
+    InvalidFeeBasisPoints,
+    #[msg("This feature is disabled by the protocol")]
+    FeatureDisabled,
+    #[msg("Invalid treasury account")]
+    InvalidTreasuryAccount,
+    #[msg("This module is paused")]
+    ModulePaused,
+    #[msg("Close the blink's vaults before deleting it")]
+    BlinkVaultsOpen,
+    #[msg("Poll is still open")]
+    PollStillOpen,
+    #[msg("Every token this blink can mint has been minted")]
+    MintSupplyExhausted,
+    // Reserved: batch items now fail with their own error. Kept so later codes don't shift.
+    #[msg("Reserved: batch items fail with their own error and log their index")]
+    BatchItemFailed,
+    #[msg("Amount does not match the blink's price")]
+    PriceMismatch,
+    #[msg("Blink already has a handle")]
+    BlinkHasHandle,
+    #[msg("Handle account does not match the blink's handle")]
+    InvalidHandleAccount,
+    #[msg("Owner registry can't track any more transferred blinks")]
+    RegistryFull,
+    #[msg("Registry account does not match the blink's creator registry")]
+    InvalidRegistryAccount,
 }
\ No newline at end of file
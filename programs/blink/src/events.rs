@@ -0,0 +1,396 @@
+use anchor_lang::prelude::*;
+use crate::blink::{BlinkConfig, BlinkRarity, BlinkType};
+use crate::gate::TokenGate;
+use crate::hooks::BlinkHook;
+use crate::moderation::ModerationStatus;
+use crate::payments::PaymentStatus;
+use crate::protocol::{FeeSource, ProtocolFeatures, ProtocolFees, ProtocolModule};
+
+#[event]
+pub struct BlinkCreated {
+    pub blink: Pubkey,
+    pub owner: Pubkey,
+    pub registry: Pubkey,
+    pub index: u64,
+    pub name: String,
+    pub blink_type: BlinkType,
+    pub config: BlinkConfig,
+    pub template: Option<Pubkey>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BlinkUpdated {
+    pub blink: Pubkey,
+    pub authority: Pubkey,
+    pub name: String,
+    pub description: String,
+    pub image_url: String,
+    pub config: BlinkConfig,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BlinkDeleted {
+    pub blink: Pubkey,
+    pub owner: Pubkey,
+    pub registry: Pubkey,
+    pub index: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BlinkExecuted {
+    pub blink: Pubkey,
+    pub executor: Pubkey,
+    pub blink_type: BlinkType,
+    // None for actions that move no tokens, such as votes
+    pub mint: Option<Pubkey>,
+    pub amount: u64,
+    pub execution_count: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BlinkLeveledUp {
+    pub blink: Pubkey,
+    pub level: u16,
+    pub rarity: BlinkRarity,
+    pub xp: u64,
+}
+
+#[event]
+pub struct BlinkModerated {
+    pub blink: Pubkey,
+    pub moderator: Pubkey,
+    pub status: ModerationStatus,
+    pub reason: u16,
+}
+
+#[event]
+pub struct BlinkModerationCleared {
+    pub blink: Pubkey,
+    pub moderator: Pubkey,
+}
+
+#[event]
+pub struct BlinkModerationAppealed {
+    pub blink: Pubkey,
+    pub owner: Pubkey,
+    pub reason: u16,
+}
+
+#[event]
+pub struct BlinkPauseSet {
+    pub blink: Pubkey,
+    pub authority: Pubkey,
+    pub paused: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BlinkScheduleSet {
+    pub blink: Pubkey,
+    pub authority: Pubkey,
+    pub starts_at: i64,
+    pub ends_at: Option<i64>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BlinkEditorSet {
+    pub blink: Pubkey,
+    pub owner: Pubkey,
+    pub editor: Pubkey,
+    // 0 when the editor was removed
+    pub permissions: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BlinkOwnershipProposed {
+    pub blink: Pubkey,
+    pub owner: Pubkey,
+    // None when a pending transfer was cancelled
+    pub pending_owner: Option<Pubkey>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BlinkOwnershipTransferred {
+    pub blink: Pubkey,
+    pub previous_owner: Pubkey,
+    pub new_owner: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BlinkHookSet {
+    pub blink: Pubkey,
+    pub owner: Pubkey,
+    pub hook: Option<BlinkHook>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BlinkGateSet {
+    pub blink: Pubkey,
+    pub authority: Pubkey,
+    pub gate: Option<TokenGate>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BlinkVaultOpened {
+    pub blink: Pubkey,
+    pub vault: Pubkey,
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BlinkVaultWithdrawn {
+    pub blink: Pubkey,
+    pub vault: Pubkey,
+    pub authority: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BlinkVaultClosed {
+    pub blink: Pubkey,
+    pub vault: Pubkey,
+    pub authority: Pubkey,
+    pub destination: Pubkey,
+    // Balance swept to the destination before the vault was closed
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct HandleRegistered {
+    pub handle_account: Pubkey,
+    pub handle: String,
+    pub blink: Pubkey,
+    pub owner: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct HandleTransferred {
+    pub handle_account: Pubkey,
+    pub handle: String,
+    pub previous_blink: Pubkey,
+    pub blink: Pubkey,
+    pub previous_owner: Pubkey,
+    pub owner: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct HandleReleased {
+    pub handle_account: Pubkey,
+    pub handle: String,
+    pub blink: Pubkey,
+    pub owner: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TemplateCreated {
+    pub template: Pubkey,
+    pub author: Pubkey,
+    pub seed: u64,
+    pub name: String,
+    pub blink_type: BlinkType,
+    pub config: BlinkConfig,
+    pub fee_lamports: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TemplateFeeSet {
+    pub template: Pubkey,
+    pub author: Pubkey,
+    pub fee_lamports: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TemplateClosed {
+    pub template: Pubkey,
+    pub author: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PollOpened {
+    pub poll: Pubkey,
+    pub blink: Pubkey,
+    pub owner: Pubkey,
+    // When set, votes are weighted by the tokens voters lock
+    pub vote_mint: Option<Pubkey>,
+    pub options: Vec<String>,
+    pub closes_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GiftFunded {
+    pub escrow: Pubkey,
+    pub blink: Pubkey,
+    pub sender: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub recipient: Option<Pubkey>,
+    pub expires_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GiftReclaimed {
+    pub escrow: Pubkey,
+    pub blink: Pubkey,
+    pub sender: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProtocolFeeCollected {
+    pub source: FeeSource,
+    pub payer: Pubkey,
+    pub treasury: Pubkey,
+    // None for fees paid in lamports
+    pub mint: Option<Pubkey>,
+    // Gross amount the fee was taken from
+    pub amount: u64,
+    pub fee: u64,
+}
+
+#[event]
+pub struct ProtocolModulePauseSet {
+    pub module: ProtocolModule,
+    pub paused: bool,
+    pub admin: Pubkey,
+}
+
+// Emitted when the config is first initialized and on every update
+#[event]
+pub struct ProtocolConfigUpdated {
+    pub admin: Pubkey,
+    pub treasury: Pubkey,
+    pub fees: ProtocolFees,
+    pub features: ProtocolFeatures,
+}
+
+#[event]
+pub struct ProtocolAdminProposed {
+    pub admin: Pubkey,
+    // None when a pending handover was cancelled
+    pub pending_admin: Option<Pubkey>,
+}
+
+#[event]
+pub struct ProtocolAdminTransferred {
+    pub previous_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
+#[event]
+pub struct NFTCreated {
+    pub nft: Pubkey,
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CollectionCreated {
+    pub collection: Pubkey,
+    pub owner: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct NFTAddedToCollection {
+    pub nft: Pubkey,
+    pub collection: Pubkey,
+    // Default when the NFT wasn't in a collection
+    pub previous_collection: Pubkey,
+    pub owner: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DonationCreated {
+    pub donation: Pubkey,
+    pub donor: Pubkey,
+    pub recipient: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub fee: u64,
+    pub currency: String,
+    pub blink: Option<Pubkey>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PaymentCreated {
+    pub payment: Pubkey,
+    pub payer: Pubkey,
+    pub recipient: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub fee: u64,
+    pub currency: String,
+    pub status: PaymentStatus,
+    pub blink: Option<Pubkey>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SwapCreated {
+    pub swap: Pubkey,
+    pub owner: Pubkey,
+    pub token_a: Pubkey,
+    pub token_b: Pubkey,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub fee: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SwapExecuted {
+    pub swap: Pubkey,
+    pub owner: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub protocol_fee: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MintCreated {
+    pub mint: Pubkey,
+    pub mint_authority: Pubkey,
+    pub metadata: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub decimals: u8,
+    pub timestamp: i64,
+}
@@ -0,0 +1,353 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount, Transfer};
+use crate::blink::{Blink, BlinkConfig, BLINK_SEED};
+use crate::errors::BlinkError;
+use crate::gate::check_token_gate;
+use crate::history::{record_blink_execution, BlinkHistory};
+use crate::gift::{release_vault, GiftEscrow, GiftStatus};
+use crate::hooks::{run_post_action_hook, BlinkHookPayload, HookAction};
+use crate::poll::{lock_vote_tokens, Poll, VoteReceipt, VOTE_RECEIPT_SEED};
+use crate::protocol::{
+    collect_token_fee, FeeSource, ProtocolConfig, ProtocolModule, PROTOCOL_CONFIG_SEED,
+};
+use crate::utils::create_pda_account;
+use crate::vault::check_blink_vault;
+
+// Inputs for every blink action; each type only reads the fields it needs.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct ExecuteBlinkArgs {
+    // Donation amount; payments always charge the configured price
+    pub amount: Option<u64>,
+    // Poll option to vote for
+    pub option: Option<u8>,
+}
+
+// Remaining accounts start with the token gate proof when the blink is gated (see
+// `TokenGate`), then by blink type:
+//   Donation / Payment: [executor_token_account (w), recipient_token_account (w)]
+//   NFT:                [mint (w), executor_token_account (w)]
+//   Poll:               [poll (w), vote_receipt (w), voter_token_account? (w), vote_vault? (w)]
+//   Gift:               [escrow (w), vault (w), sender (w), executor_token_account (w),
+//                        claim_key? (s)], the claim key unless the executor is the recipient
+// followed, for donations, payments and gifts on a blink with a hook, by the hook accounts.
+#[derive(Accounts)]
+pub struct ExecuteBlink<'info> {
+    #[account(mut)]
+    pub blink: Account<'info, Blink>,
+    #[account(mut)]
+    pub executor: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub history: Option<Account<'info, BlinkHistory>>,
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    // Required when a protocol fee is owed on a donation or payment
+    #[account(mut)]
+    pub treasury_token_account: Option<Account<'info, TokenAccount>>,
+}
+
+pub fn execute_blink<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ExecuteBlink<'info>>,
+    args: ExecuteBlinkArgs,
+) -> Result<()> {
+    let accounts = &ctx.accounts;
+
+    accounts.protocol_config.check_not_paused(ProtocolModule::Blinks)?;
+    accounts.blink.check_active(Clock::get()?.unix_timestamp)?;
+    let remaining = check_token_gate(
+        &accounts.blink,
+        accounts.executor.key,
+        ctx.remaining_accounts,
+    )?;
+
+    // Each action returns the mint and amount it moved for the blink's counters, and
+    // the hook action with the number of remaining accounts it consumed
+    let (mint, amount, hook_action) = match &accounts.blink.config {
+        BlinkConfig::Standard => return Err(BlinkError::InvalidBlinkType.into()),
+        BlinkConfig::Donation {
+            recipient, mint, ..
+        } => {
+            let amount = args.amount.ok_or(BlinkError::InvalidBlinkConfig)?;
+            if amount == 0 {
+                return Err(BlinkError::InsufficientFunds.into());
+            }
+            accounts.protocol_config.check_not_paused(ProtocolModule::Donations)?;
+            let source = FeeSource::Donation;
+            transfer_to_recipient(accounts, remaining, source, recipient, mint, amount)?;
+            (Some(*mint), amount, Some((HookAction::Donation, 2)))
+        }
+        BlinkConfig::Payment {
+            recipient,
+            mint,
+            price,
+        } => {
+            accounts.protocol_config.check_not_paused(ProtocolModule::Payments)?;
+            let source = FeeSource::Payment;
+            transfer_to_recipient(accounts, remaining, source, recipient, mint, *price)?;
+            (Some(*mint), *price, Some((HookAction::Payment, 2)))
+        }
+        BlinkConfig::NFT {
+            mint, max_supply, ..
+        } => {
+            accounts.protocol_config.check_not_paused(ProtocolModule::Mints)?;
+            mint_to_executor(accounts, remaining, mint, *max_supply)?;
+            (Some(*mint), 1, None)
+        }
+        BlinkConfig::Poll { .. } => {
+            let option = args.option.ok_or(BlinkError::InvalidPollOption)?;
+            vote(accounts, remaining, option)?;
+            (None, 0, None)
+        }
+        BlinkConfig::Gift { mint, .. } => {
+            let (amount, consumed) = claim(accounts, remaining)?;
+            (Some(*mint), amount, Some((HookAction::GiftClaim, consumed)))
+        }
+    };
+
+    let executor = ctx.accounts.executor.key();
+    record_blink_execution(
+        &mut ctx.accounts.blink,
+        ctx.accounts.history.as_mut(),
+        &executor,
+        mint,
+        amount,
+    )?;
+
+    if let Some((action, consumed)) = hook_action {
+        let payload = BlinkHookPayload {
+            action,
+            blink: ctx.accounts.blink.key(),
+            payer: executor,
+            mint: mint.unwrap_or_default(),
+            amount,
+        };
+        run_post_action_hook(
+            &ctx.accounts.blink,
+            &ctx.accounts.executor.to_account_info(),
+            payload,
+            &remaining[consumed..],
+        )?;
+    }
+
+    Ok(())
+}
+
+fn account_at<'a, 'info>(
+    remaining: &'a [AccountInfo<'info>],
+    index: usize,
+) -> Result<&'a AccountInfo<'info>> {
+    remaining
+        .get(index)
+        .ok_or_else(|| ErrorCode::AccountNotEnoughKeys.into())
+}
+
+fn transfer_to_recipient<'info>(
+    accounts: &ExecuteBlink<'info>,
+    remaining: &'info [AccountInfo<'info>],
+    source: FeeSource,
+    recipient: &Pubkey,
+    mint: &Pubkey,
+    amount: u64,
+) -> Result<()> {
+    let executor_token_account = Account::<TokenAccount>::try_from(account_at(remaining, 0)?)?;
+    let recipient_token_account = Account::<TokenAccount>::try_from(account_at(remaining, 1)?)?;
+
+    if executor_token_account.mint != *mint || recipient_token_account.mint != *mint {
+        return Err(BlinkError::InvalidMint.into());
+    }
+    if recipient_token_account.owner != *recipient {
+        return Err(BlinkError::InvalidRecipient.into());
+    }
+    // A blink configured as its own recipient collects into its vault
+    if *recipient == accounts.blink.key() {
+        if !accounts.protocol_config.features.blink_routing {
+            return Err(BlinkError::FeatureDisabled.into());
+        }
+        check_blink_vault(recipient, &recipient_token_account)?;
+    }
+
+    let fee = collect_token_fee(
+        &accounts.protocol_config,
+        source,
+        amount,
+        &executor_token_account,
+        &accounts.executor.to_account_info(),
+        accounts.treasury_token_account.as_ref(),
+        &accounts.token_program.to_account_info(),
+    )?;
+
+    // Transfer the rest from executor to recipient
+    let cpi_accounts = Transfer {
+        from: executor_token_account.to_account_info(),
+        to: recipient_token_account.to_account_info(),
+        authority: accounts.executor.to_account_info(),
+    };
+    let cpi_program = accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+    token::transfer(cpi_ctx, amount - fee)?;
+
+    Ok(())
+}
+
+fn mint_to_executor<'info>(
+    accounts: &ExecuteBlink<'info>,
+    remaining: &'info [AccountInfo<'info>],
+    mint: &Pubkey,
+    max_supply: u64,
+) -> Result<()> {
+    let blink = &accounts.blink;
+    let mint_account = Account::<Mint>::try_from(account_at(remaining, 0)?)?;
+    let executor_token_account = Account::<TokenAccount>::try_from(account_at(remaining, 1)?)?;
+
+    if mint_account.key() != *mint || executor_token_account.mint != *mint {
+        return Err(BlinkError::InvalidMint.into());
+    }
+    if executor_token_account.owner != accounts.executor.key() {
+        return Err(BlinkError::InvalidTokenAccount.into());
+    }
+    // Only the blink can mint, so the supply counts every token it has handed out
+    if mint_account.supply >= max_supply {
+        return Err(BlinkError::MintSupplyExhausted.into());
+    }
+
+    let index = blink.index.to_le_bytes();
+    let signer_seeds: &[&[&[u8]]] =
+        &[&[BLINK_SEED, blink.registry.as_ref(), &index, &[blink.bump]]];
+
+    // The blink PDA must be the mint authority
+    let cpi_accounts = MintTo {
+        mint: mint_account.to_account_info(),
+        to: executor_token_account.to_account_info(),
+        authority: blink.to_account_info(),
+    };
+    let cpi_program = accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+    token::mint_to(cpi_ctx, 1)?;
+
+    Ok(())
+}
+
+fn vote<'info>(
+    accounts: &ExecuteBlink<'info>,
+    remaining: &'info [AccountInfo<'info>],
+    option: u8,
+) -> Result<()> {
+    let mut poll = Account::<Poll>::try_from(account_at(remaining, 0)?)?;
+    let vote_receipt_info = account_at(remaining, 1)?;
+    let voter_token_account = remaining
+        .get(2)
+        .map(Account::<TokenAccount>::try_from)
+        .transpose()?;
+    let vote_vault = remaining
+        .get(3)
+        .map(Account::<TokenAccount>::try_from)
+        .transpose()?;
+    let voter = &accounts.executor;
+    let clock = Clock::get()?;
+
+    if poll.blink != accounts.blink.key() {
+        return Err(BlinkError::InvalidBlinkConfig.into());
+    }
+
+    let poll_key = poll.key();
+    let (expected_receipt, receipt_bump) = Pubkey::find_program_address(
+        &[VOTE_RECEIPT_SEED, poll_key.as_ref(), voter.key.as_ref()],
+        &crate::ID,
+    );
+    if vote_receipt_info.key() != expected_receipt {
+        return Err(BlinkError::InvalidProgramAddress.into());
+    }
+    if vote_receipt_info.owner == &crate::ID {
+        return Err(BlinkError::AlreadyVoted.into());
+    }
+
+    let weight = lock_vote_tokens(
+        &poll,
+        voter,
+        voter_token_account.as_ref(),
+        vote_vault.as_ref(),
+        &accounts.token_program,
+    )?;
+    poll.record_vote(option, weight, clock.unix_timestamp)?;
+
+    // Create the receipt the way `cast_vote`'s `init_if_needed` would, so lamports sent to
+    // the address ahead of time can't block the vote
+    let receipt_seeds: &[&[&[u8]]] = &[&[
+        VOTE_RECEIPT_SEED,
+        poll_key.as_ref(),
+        voter.key.as_ref(),
+        &[receipt_bump],
+    ]];
+    create_pda_account(
+        vote_receipt_info,
+        VoteReceipt::LEN,
+        &voter.to_account_info(),
+        &accounts.system_program.to_account_info(),
+        receipt_seeds,
+    )?;
+
+    let mut vote_receipt = Account::<VoteReceipt>::try_from_unchecked(vote_receipt_info)?;
+    vote_receipt.poll = poll_key;
+    vote_receipt.voter = voter.key();
+    vote_receipt.option = option;
+    vote_receipt.weight = weight;
+    vote_receipt.voted_at = clock.unix_timestamp;
+    vote_receipt.bump = receipt_bump;
+
+    vote_receipt.exit(&crate::ID)?;
+    poll.exit(&crate::ID)?;
+
+    Ok(())
+}
+
+// Returns the claimed amount and the number of remaining accounts used
+fn claim<'info>(
+    accounts: &ExecuteBlink<'info>,
+    remaining: &'info [AccountInfo<'info>],
+) -> Result<(u64, usize)> {
+    let mut escrow = Account::<GiftEscrow>::try_from(account_at(remaining, 0)?)?;
+    let vault = Account::<TokenAccount>::try_from(account_at(remaining, 1)?)?;
+    let sender = account_at(remaining, 2)?;
+    let claimant_token_account = Account::<TokenAccount>::try_from(account_at(remaining, 3)?)?;
+    let claimant = &accounts.executor;
+    let clock = Clock::get()?;
+
+    if escrow.blink != accounts.blink.key()
+        || escrow.vault != vault.key()
+        || escrow.sender != sender.key()
+    {
+        return Err(BlinkError::InvalidBlinkConfig.into());
+    }
+    if claimant_token_account.mint != escrow.mint {
+        return Err(BlinkError::InvalidMint.into());
+    }
+    if claimant_token_account.owner != claimant.key() {
+        return Err(BlinkError::InvalidTokenAccount.into());
+    }
+
+    let mut consumed = 4;
+    let mut claim_signer = None;
+    if escrow.claim_key.is_some() && !escrow.is_recipient(claimant.key) {
+        let claim_key = account_at(remaining, consumed)?;
+        consumed += 1;
+        if claim_key.is_signer {
+            claim_signer = Some(claim_key.key);
+        }
+    }
+    escrow.check_claim(claimant.key, claim_signer, clock.unix_timestamp)?;
+
+    release_vault(
+        &escrow,
+        &vault,
+        &claimant_token_account.to_account_info(),
+        sender,
+        &accounts.token_program,
+    )?;
+
+    escrow.status = GiftStatus::Claimed;
+    escrow.claimed_by = Some(claimant.key());
+    escrow.exit(&crate::ID)?;
+
+    Ok((escrow.amount, consumed))
+}
@@ -0,0 +1,100 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, TokenAccount};
+use crate::blink::{Blink, ManageBlink, PERMISSION_EDIT_METADATA};
+use crate::errors::BlinkError;
+use crate::events::BlinkGateSet;
+use crate::nft::NFT;
+use crate::protocol::ProtocolModule;
+
+// Holding the executor must prove before any action on a gated blink
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub enum TokenGate {
+    // Proof: [executor token account for `mint`]
+    MinBalance { mint: Pubkey, amount: u64 },
+    // Proof: [NFT account in `collection`, executor token account for its mint]
+    CollectionHolder { collection: Pubkey },
+}
+
+impl TokenGate {
+    pub fn proof_len(&self) -> usize {
+        match self {
+            TokenGate::MinBalance { .. } => 1,
+            TokenGate::CollectionHolder { .. } => 2,
+        }
+    }
+}
+
+// Checks the gate proof at the start of `remaining` and returns the accounts after it.
+// Ungated blinks take no proof accounts.
+pub(crate) fn check_token_gate<'a, 'info>(
+    blink: &Blink,
+    executor: &Pubkey,
+    remaining: &'a [AccountInfo<'info>],
+) -> Result<&'a [AccountInfo<'info>]> {
+    let gate = match &blink.gate {
+        Some(gate) => gate,
+        None => return Ok(remaining),
+    };
+
+    if remaining.len() < gate.proof_len() {
+        return Err(BlinkError::TokenGateNotMet.into());
+    }
+    let (proof, rest) = remaining.split_at(gate.proof_len());
+
+    match gate {
+        TokenGate::MinBalance { mint, amount } => {
+            let token_account = read_token_account(&proof[0])?;
+            if token_account.mint != *mint
+                || token_account.owner != *executor
+                || token_account.amount < *amount
+            {
+                return Err(BlinkError::TokenGateNotMet.into());
+            }
+        }
+        TokenGate::CollectionHolder { collection } => {
+            if proof[0].owner != &crate::ID {
+                return Err(BlinkError::TokenGateNotMet.into());
+            }
+            let nft = NFT::try_deserialize(&mut &proof[0].try_borrow_data()?[..])?;
+            let token_account = read_token_account(&proof[1])?;
+            if nft.collection != *collection
+                || token_account.mint != nft.mint
+                || token_account.owner != *executor
+                || token_account.amount == 0
+            {
+                return Err(BlinkError::TokenGateNotMet.into());
+            }
+        }
+    }
+
+    Ok(rest)
+}
+
+fn read_token_account(info: &AccountInfo) -> Result<TokenAccount> {
+    if info.owner != &token::ID {
+        return Err(BlinkError::InvalidTokenAccount.into());
+    }
+    TokenAccount::try_deserialize(&mut &info.try_borrow_data()?[..])
+}
+
+// Passing None removes the gate
+pub fn set_blink_gate(ctx: Context<ManageBlink>, gate: Option<TokenGate>) -> Result<()> {
+    ctx.accounts.protocol_config.check_not_paused(ProtocolModule::Blinks)?;
+
+    let blink = &mut ctx.accounts.blink;
+    let clock = Clock::get()?;
+
+    blink.check_permission(ctx.accounts.authority.key, PERMISSION_EDIT_METADATA)?;
+
+    blink.gate = gate.clone();
+    blink.updated_at = clock.unix_timestamp;
+
+    emit!(BlinkGateSet {
+        blink: blink.key(),
+        authority: ctx.accounts.authority.key(),
+        gate,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,339 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, CloseAccount, Mint, Token, TokenAccount, Transfer};
+use crate::blink::{Blink, BlinkConfig, BlinkType};
+use crate::errors::BlinkError;
+use crate::events::{GiftFunded, GiftReclaimed};
+use crate::gate::check_token_gate;
+use crate::history::{record_blink_execution, BlinkHistory};
+use crate::hooks::{run_post_action_hook, BlinkHookPayload, HookAction};
+use crate::protocol::{ProtocolConfig, ProtocolModule, PROTOCOL_CONFIG_SEED};
+
+pub const GIFT_ESCROW_SEED: &[u8] = b"gift";
+pub const GIFT_VAULT_SEED: &[u8] = b"gift_vault";
+
+pub fn find_gift_escrow_address(blink: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[GIFT_ESCROW_SEED, blink.as_ref()], &crate::ID)
+}
+
+pub fn find_gift_vault_address(blink: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[GIFT_VAULT_SEED, blink.as_ref()], &crate::ID)
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum GiftStatus {
+    Funded,
+    Claimed,
+    Reclaimed,
+}
+
+// Terms are copied from the blink's Gift config when funded so later config edits
+// can't change who may claim an already escrowed gift.
+#[account]
+pub struct GiftEscrow {
+    pub blink: Pubkey,
+    pub sender: Pubkey,
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub amount: u64,
+    pub recipient: Option<Pubkey>,
+    pub claim_key: Option<Pubkey>,
+    pub expires_at: i64,
+    pub status: GiftStatus,
+    pub claimed_by: Option<Pubkey>,
+    pub funded_at: i64,
+    pub bump: u8,
+}
+
+impl GiftEscrow {
+    pub const LEN: usize = 8 + 32 + 32 + 32 + 32 + 8 + 33 + 33 + 8 + 1 + 33 + 8 + 1;
+
+    // `claim_signer` is the claim key when it signed the transaction
+    pub fn check_claim(
+        &self,
+        claimant: &Pubkey,
+        claim_signer: Option<&Pubkey>,
+        now: i64,
+    ) -> Result<()> {
+        if self.status != GiftStatus::Funded {
+            return Err(BlinkError::GiftAlreadySettled.into());
+        }
+
+        if now >= self.expires_at {
+            return Err(BlinkError::GiftExpired.into());
+        }
+
+        self.authorize_claim(claimant, claim_signer)
+    }
+
+    // A named recipient can always claim; anyone else needs the claim key's signature.
+    // A revealed preimage could be copied into a competing claim, a signature can't.
+    pub fn authorize_claim(&self, claimant: &Pubkey, claim_signer: Option<&Pubkey>) -> Result<()> {
+        if self.is_recipient(claimant) {
+            return Ok(());
+        }
+
+        match (self.claim_key, claim_signer) {
+            (Some(claim_key), Some(signer)) if claim_key == *signer => Ok(()),
+            (Some(_), _) => Err(BlinkError::InvalidGiftSecret.into()),
+            (None, _) => Err(BlinkError::Unauthorized.into()),
+        }
+    }
+
+    pub fn is_recipient(&self, claimant: &Pubkey) -> bool {
+        self.recipient == Some(*claimant)
+    }
+}
+
+#[derive(Accounts)]
+pub struct FundGift<'info> {
+    #[account(
+        has_one = owner @ BlinkError::Unauthorized,
+        constraint = blink.blink_type == BlinkType::Gift @ BlinkError::InvalidBlinkType
+    )]
+    pub blink: Account<'info, Blink>,
+    #[account(
+        init,
+        payer = owner,
+        space = GiftEscrow::LEN,
+        seeds = [GIFT_ESCROW_SEED, blink.key().as_ref()],
+        bump
+    )]
+    pub escrow: Account<'info, GiftEscrow>,
+    #[account(
+        init,
+        payer = owner,
+        seeds = [GIFT_VAULT_SEED, blink.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = escrow
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    pub mint: Account<'info, Mint>,
+    #[account(mut, token::mint = mint, token::authority = owner)]
+    pub owner_token_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+// Remaining accounts: the blink's hook accounts, when it has a hook
+#[derive(Accounts)]
+pub struct ClaimGift<'info> {
+    #[account(mut)]
+    pub blink: Account<'info, Blink>,
+    #[account(mut, has_one = blink, has_one = vault, has_one = sender)]
+    pub escrow: Account<'info, GiftEscrow>,
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+    /// CHECK: Only receives the vault's rent refund; checked against escrow.sender
+    #[account(mut)]
+    pub sender: AccountInfo<'info>,
+    pub claimant: Signer<'info>,
+    #[account(mut, token::mint = escrow.mint, token::authority = claimant)]
+    pub claimant_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    #[account(mut)]
+    pub history: Option<Account<'info, BlinkHistory>>,
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    // Required unless the claimant is the gift's named recipient
+    pub claim_key: Option<Signer<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimGift<'info> {
+    #[account(mut, has_one = vault, has_one = sender @ BlinkError::Unauthorized)]
+    pub escrow: Account<'info, GiftEscrow>,
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub sender: Signer<'info>,
+    #[account(mut, token::mint = escrow.mint, token::authority = sender)]
+    pub sender_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn fund_gift(ctx: Context<FundGift>) -> Result<()> {
+    ctx.accounts.protocol_config.check_not_paused(ProtocolModule::Blinks)?;
+
+    let blink = &ctx.accounts.blink;
+    let escrow = &mut ctx.accounts.escrow;
+    let clock = Clock::get()?;
+
+    blink.check_not_frozen()?;
+
+    let (mint, amount, recipient, claim_key, expires_at) = match &blink.config {
+        BlinkConfig::Gift {
+            mint,
+            amount,
+            recipient,
+            claim_key,
+            expires_at,
+        } => (*mint, *amount, *recipient, *claim_key, *expires_at),
+        _ => return Err(BlinkError::InvalidBlinkConfig.into()),
+    };
+
+    if ctx.accounts.mint.key() != mint {
+        return Err(BlinkError::InvalidMint.into());
+    }
+
+    if expires_at <= clock.unix_timestamp {
+        return Err(BlinkError::GiftExpired.into());
+    }
+
+    escrow.blink = blink.key();
+    escrow.sender = ctx.accounts.owner.key();
+    escrow.mint = mint;
+    escrow.vault = ctx.accounts.vault.key();
+    escrow.amount = amount;
+    escrow.recipient = recipient;
+    escrow.claim_key = claim_key;
+    escrow.expires_at = expires_at;
+    escrow.status = GiftStatus::Funded;
+    escrow.claimed_by = None;
+    escrow.funded_at = clock.unix_timestamp;
+    escrow.bump = ctx.bumps.escrow;
+
+    // Transfer the gift from the sender into the escrow vault
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.owner_token_account.to_account_info(),
+        to: ctx.accounts.vault.to_account_info(),
+        authority: ctx.accounts.owner.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+    token::transfer(cpi_ctx, amount)?;
+
+    emit!(GiftFunded {
+        escrow: escrow.key(),
+        blink: escrow.blink,
+        sender: escrow.sender,
+        mint,
+        amount,
+        recipient,
+        expires_at,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn claim_gift<'info>(ctx: Context<'_, '_, '_, 'info, ClaimGift<'info>>) -> Result<()> {
+    ctx.accounts.protocol_config.check_not_paused(ProtocolModule::Blinks)?;
+
+    let escrow = &mut ctx.accounts.escrow;
+    let claimant = &ctx.accounts.claimant;
+    let clock = Clock::get()?;
+
+    ctx.accounts.blink.check_active(clock.unix_timestamp)?;
+    // Remaining accounts: the token gate proof for a gated blink, then the hook accounts
+    let hook_accounts =
+        check_token_gate(&ctx.accounts.blink, claimant.key, ctx.remaining_accounts)?;
+    let claim_signer = ctx.accounts.claim_key.as_ref().map(|claim_key| claim_key.key);
+    escrow.check_claim(claimant.key, claim_signer, clock.unix_timestamp)?;
+
+    release_vault(
+        escrow,
+        &ctx.accounts.vault,
+        &ctx.accounts.claimant_token_account.to_account_info(),
+        &ctx.accounts.sender,
+        &ctx.accounts.token_program,
+    )?;
+
+    escrow.status = GiftStatus::Claimed;
+    escrow.claimed_by = Some(claimant.key());
+
+    let (mint, amount) = (escrow.mint, escrow.amount);
+    record_blink_execution(
+        &mut ctx.accounts.blink,
+        ctx.accounts.history.as_mut(),
+        claimant.key,
+        Some(mint),
+        amount,
+    )?;
+
+    let payload = BlinkHookPayload {
+        action: HookAction::GiftClaim,
+        blink: ctx.accounts.blink.key(),
+        payer: claimant.key(),
+        mint,
+        amount,
+    };
+    run_post_action_hook(
+        &ctx.accounts.blink,
+        &claimant.to_account_info(),
+        payload,
+        hook_accounts,
+    )?;
+
+    Ok(())
+}
+
+pub fn reclaim_gift(ctx: Context<ReclaimGift>) -> Result<()> {
+    let escrow = &mut ctx.accounts.escrow;
+    let clock = Clock::get()?;
+
+    if escrow.status != GiftStatus::Funded {
+        return Err(BlinkError::GiftAlreadySettled.into());
+    }
+
+    if clock.unix_timestamp < escrow.expires_at {
+        return Err(BlinkError::GiftNotExpired.into());
+    }
+
+    release_vault(
+        escrow,
+        &ctx.accounts.vault,
+        &ctx.accounts.sender_token_account.to_account_info(),
+        &ctx.accounts.sender.to_account_info(),
+        &ctx.accounts.token_program,
+    )?;
+
+    escrow.status = GiftStatus::Reclaimed;
+
+    emit!(GiftReclaimed {
+        escrow: escrow.key(),
+        blink: escrow.blink,
+        sender: escrow.sender,
+        mint: escrow.mint,
+        amount: escrow.amount,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Empties the vault into `destination` and closes it, refunding its rent to the sender
+pub(crate) fn release_vault<'info>(
+    escrow: &Account<'info, GiftEscrow>,
+    vault: &Account<'info, TokenAccount>,
+    destination: &AccountInfo<'info>,
+    sender: &AccountInfo<'info>,
+    token_program: &Program<'info, Token>,
+) -> Result<()> {
+    let signer_seeds: &[&[&[u8]]] = &[&[GIFT_ESCROW_SEED, escrow.blink.as_ref(), &[escrow.bump]]];
+
+    let cpi_accounts = Transfer {
+        from: vault.to_account_info(),
+        to: destination.clone(),
+        authority: escrow.to_account_info(),
+    };
+    let cpi_ctx =
+        CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer_seeds);
+    token::transfer(cpi_ctx, vault.amount)?;
+
+    let cpi_accounts = CloseAccount {
+        account: vault.to_account_info(),
+        destination: sender.clone(),
+        authority: escrow.to_account_info(),
+    };
+    let cpi_ctx =
+        CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer_seeds);
+    token::close_account(cpi_ctx)?;
+
+    Ok(())
+}
@@ -0,0 +1,204 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+use crate::blink::Blink;
+use crate::errors::BlinkError;
+use crate::events::{HandleRegistered, HandleReleased, HandleTransferred};
+use crate::protocol::{ProtocolConfig, ProtocolModule, PROTOCOL_CONFIG_SEED};
+
+pub const HANDLE_SEED: &[u8] = b"handle";
+pub const MIN_HANDLE_LEN: usize = 3;
+pub const MAX_HANDLE_LEN: usize = 32;
+pub const RESERVED_HANDLES: &[&str] = &[
+    "admin", "api", "app", "bark", "blink", "blinks", "help", "login", "new", "settings",
+    "support", "www",
+];
+
+// Maps a unique handle to a blink so share links can be resolved on-chain. Handles are
+// stored in normalized (lowercase) form and seeded by their hash, which fits any input
+// into a seed. A blink has at most one handle, and the handle follows the blink's owner.
+#[account]
+#[derive(InitSpace)]
+pub struct BlinkHandle {
+    #[max_len(MAX_HANDLE_LEN)]
+    pub handle: String,
+    pub blink: Pubkey,
+    pub owner: Pubkey,
+    pub registered_at: i64,
+    pub bump: u8,
+}
+
+impl BlinkHandle {
+    pub fn space_for(handle: &str) -> usize {
+        8 + BlinkHandle::INIT_SPACE - MAX_HANDLE_LEN.saturating_sub(handle.len())
+    }
+}
+
+pub fn normalize_handle(handle: &str) -> String {
+    handle.trim().to_ascii_lowercase()
+}
+
+pub fn find_handle_address(handle: &str) -> (Pubkey, u8) {
+    let handle = normalize_handle(handle);
+    Pubkey::find_program_address(&[HANDLE_SEED, hash(handle.as_bytes()).as_ref()], &crate::ID)
+}
+
+// Handles are 3-32 characters of a-z, 0-9, '-' and '_', starting and ending with a
+// letter or digit. Callers must pass the normalized form.
+pub fn validate_handle(handle: &str) -> Result<()> {
+    let bytes = handle.as_bytes();
+
+    if bytes.len() < MIN_HANDLE_LEN || bytes.len() > MAX_HANDLE_LEN {
+        return Err(BlinkError::InvalidHandle.into());
+    }
+
+    let valid_chars = bytes
+        .iter()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || *c == b'-' || *c == b'_');
+    let valid_ends =
+        bytes[0].is_ascii_alphanumeric() && bytes[bytes.len() - 1].is_ascii_alphanumeric();
+    if !valid_chars || !valid_ends {
+        return Err(BlinkError::InvalidHandle.into());
+    }
+
+    if RESERVED_HANDLES.contains(&handle) {
+        return Err(BlinkError::HandleReserved.into());
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(handle: String)]
+pub struct RegisterHandle<'info> {
+    #[account(mut, has_one = owner)]
+    pub blink: Account<'info, Blink>,
+    #[account(
+        init,
+        payer = owner,
+        space = BlinkHandle::space_for(&handle),
+        seeds = [HANDLE_SEED, hash(handle.as_bytes()).as_ref()],
+        bump
+    )]
+    pub handle_account: Account<'info, BlinkHandle>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+// The new blink's owner must sign so handles can't be pointed at blinks without consent
+#[derive(Accounts)]
+pub struct TransferHandle<'info> {
+    #[account(mut, has_one = owner, has_one = blink)]
+    pub handle_account: Account<'info, BlinkHandle>,
+    #[account(mut)]
+    pub blink: Account<'info, Blink>,
+    pub owner: Signer<'info>,
+    #[account(mut, constraint = new_blink.owner == new_owner.key() @ BlinkError::Unauthorized)]
+    pub new_blink: Account<'info, Blink>,
+    pub new_owner: Signer<'info>,
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseHandle<'info> {
+    #[account(mut, has_one = owner, has_one = blink, close = owner)]
+    pub handle_account: Account<'info, BlinkHandle>,
+    #[account(mut)]
+    pub blink: Account<'info, Blink>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+pub fn register_handle(ctx: Context<RegisterHandle>, handle: String) -> Result<()> {
+    ctx.accounts.protocol_config.check_not_paused(ProtocolModule::Blinks)?;
+
+    let handle_account = &mut ctx.accounts.handle_account;
+    let clock = Clock::get()?;
+
+    let blink = &mut ctx.accounts.blink;
+    blink.check_not_frozen()?;
+    validate_handle(&handle)?;
+
+    if blink.handle.is_some() {
+        return Err(BlinkError::BlinkHasHandle.into());
+    }
+    blink.handle = Some(handle_account.key());
+
+    handle_account.handle = handle;
+    handle_account.blink = blink.key();
+    handle_account.owner = ctx.accounts.owner.key();
+    handle_account.registered_at = clock.unix_timestamp;
+    handle_account.bump = ctx.bumps.handle_account;
+
+    emit!(HandleRegistered {
+        handle_account: handle_account.key(),
+        handle: handle_account.handle.clone(),
+        blink: blink.key(),
+        owner: handle_account.owner,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn transfer_handle(ctx: Context<TransferHandle>) -> Result<()> {
+    ctx.accounts.protocol_config.check_not_paused(ProtocolModule::Blinks)?;
+
+    let handle_account = &mut ctx.accounts.handle_account;
+    let new_blink = &mut ctx.accounts.new_blink;
+
+    new_blink.check_not_frozen()?;
+
+    if new_blink.handle.is_some() {
+        return Err(BlinkError::BlinkHasHandle.into());
+    }
+    ctx.accounts.blink.handle = None;
+    new_blink.handle = Some(handle_account.key());
+
+    let previous_blink = handle_account.blink;
+    let previous_owner = handle_account.owner;
+    handle_account.blink = new_blink.key();
+    handle_account.owner = ctx.accounts.new_owner.key();
+
+    emit!(HandleTransferred {
+        handle_account: handle_account.key(),
+        handle: handle_account.handle.clone(),
+        previous_blink,
+        blink: handle_account.blink,
+        previous_owner,
+        owner: handle_account.owner,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn release_handle(ctx: Context<ReleaseHandle>) -> Result<()> {
+    // The account is closed by its constraints, freeing the handle for anyone to register
+    ctx.accounts.blink.handle = None;
+
+    emit!(HandleReleased {
+        handle_account: ctx.accounts.handle_account.key(),
+        handle: ctx.accounts.handle_account.handle.clone(),
+        blink: ctx.accounts.blink.key(),
+        owner: ctx.accounts.owner.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Checks that `handle_account` is the blink's handle account, if it has one
+pub(crate) fn check_blink_handle(
+    blink: &Blink,
+    handle_account: Option<&Account<BlinkHandle>>,
+) -> Result<()> {
+    if blink.handle != handle_account.map(|handle_account| handle_account.key()) {
+        return Err(BlinkError::InvalidHandleAccount.into());
+    }
+
+    Ok(())
+}
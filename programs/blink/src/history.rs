@@ -0,0 +1,136 @@
+use anchor_lang::prelude::*;
+use crate::blink::Blink;
+use crate::errors::BlinkError;
+use crate::events::{BlinkExecuted, BlinkLeveledUp};
+use crate::protocol::{ProtocolConfig, ProtocolModule, PROTOCOL_CONFIG_SEED};
+
+pub const BLINK_HISTORY_SEED: &[u8] = b"history";
+pub const HISTORY_CAPACITY: usize = 32;
+
+pub fn find_blink_history_address(blink: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[BLINK_HISTORY_SEED, blink.as_ref()], &crate::ID)
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct ExecutionRecord {
+    pub executor: Pubkey,
+    // Pubkey::default() for actions that move no tokens
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+// Ring buffer of the most recent executions; `head` is the next slot to overwrite
+// once `records` has reached HISTORY_CAPACITY.
+#[account]
+pub struct BlinkHistory {
+    pub blink: Pubkey,
+    pub head: u32,
+    pub total_recorded: u64,
+    pub records: Vec<ExecutionRecord>,
+    pub bump: u8,
+}
+
+impl BlinkHistory {
+    pub const LEN: usize = 8 + 32 + 4 + 8 + 4 + HISTORY_CAPACITY * (32 + 32 + 8 + 8) + 1;
+
+    pub fn push(&mut self, record: ExecutionRecord) {
+        if self.records.len() < HISTORY_CAPACITY {
+            self.records.push(record);
+        } else {
+            self.records[self.head as usize] = record;
+        }
+        self.head = ((self.head as usize + 1) % HISTORY_CAPACITY) as u32;
+        self.total_recorded = self.total_recorded.saturating_add(1);
+    }
+
+    // Records from oldest to newest
+    pub fn ordered(&self) -> Vec<ExecutionRecord> {
+        if self.records.len() < HISTORY_CAPACITY {
+            return self.records.clone();
+        }
+        let (newest, oldest) = self.records.split_at(self.head as usize);
+        oldest.iter().chain(newest.iter()).cloned().collect()
+    }
+}
+
+#[derive(Accounts)]
+pub struct OpenBlinkHistory<'info> {
+    #[account(has_one = owner)]
+    pub blink: Account<'info, Blink>,
+    #[account(
+        init,
+        payer = owner,
+        space = BlinkHistory::LEN,
+        seeds = [BLINK_HISTORY_SEED, blink.key().as_ref()],
+        bump
+    )]
+    pub history: Account<'info, BlinkHistory>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+pub fn open_blink_history(ctx: Context<OpenBlinkHistory>) -> Result<()> {
+    ctx.accounts.protocol_config.check_not_paused(ProtocolModule::Blinks)?;
+
+    let history = &mut ctx.accounts.history;
+
+    history.blink = ctx.accounts.blink.key();
+    history.head = 0;
+    history.total_recorded = 0;
+    history.records = Vec::new();
+    history.bump = ctx.bumps.history;
+
+    Ok(())
+}
+
+// Updates the blink's counters and XP and, when the blink has a history account,
+// appends to it
+pub fn record_blink_execution(
+    blink: &mut Account<Blink>,
+    history: Option<&mut Account<BlinkHistory>>,
+    executor: &Pubkey,
+    mint: Option<Pubkey>,
+    amount: u64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+
+    blink.record_execution(mint, amount, clock.unix_timestamp)?;
+
+    emit!(BlinkExecuted {
+        blink: blink.key(),
+        executor: *executor,
+        blink_type: blink.blink_type.clone(),
+        mint,
+        amount,
+        execution_count: blink.execution_count,
+        timestamp: clock.unix_timestamp,
+    });
+
+    let xp = blink.execution_xp(executor, amount);
+    if blink.award_xp(xp) {
+        emit!(BlinkLeveledUp {
+            blink: blink.key(),
+            level: blink.level,
+            rarity: blink.rarity,
+            xp: blink.xp,
+        });
+    }
+
+    if let Some(history) = history {
+        if history.blink != blink.key() {
+            return Err(BlinkError::InvalidBlinkHistory.into());
+        }
+        history.push(ExecutionRecord {
+            executor: *executor,
+            mint: mint.unwrap_or_default(),
+            amount,
+            timestamp: clock.unix_timestamp,
+        });
+    }
+
+    Ok(())
+}
@@ -0,0 +1,239 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::compute_units::sol_remaining_compute_units;
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke;
+use crate::blink::Blink;
+use crate::errors::BlinkError;
+use crate::events::BlinkHookSet;
+use crate::protocol::{ProtocolConfig, ProtocolModule, PROTOCOL_CONFIG_SEED};
+
+pub const HOOK_ALLOWLIST_SEED: &[u8] = b"hook_allowlist";
+pub const MAX_ALLOWED_HOOKS: usize = 32;
+// Hooks implement an instruction with this name; its Anchor discriminator prefixes the payload
+pub const HOOK_INSTRUCTION_NAME: &str = "on_blink_action";
+
+// What happens when a registered hook can't be run: its accounts weren't supplied,
+// there isn't enough compute left for its budget, or it was removed from the allowlist.
+// A hook that is invoked and fails always fails the whole action.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum HookFailureMode {
+    Abort,
+    Skip,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub struct BlinkHook {
+    pub program: Pubkey,
+    // Compute units that must remain before the hook is invoked
+    pub compute_budget: u32,
+    pub failure_mode: HookFailureMode,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum HookAction {
+    Donation,
+    Payment,
+    GiftClaim,
+}
+
+// Serialized after the discriminator as the hook instruction's data
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BlinkHookPayload {
+    pub action: HookAction,
+    pub blink: Pubkey,
+    pub payer: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+// Programs blink owners may register as hooks, managed by the protocol admin
+#[account]
+#[derive(InitSpace)]
+pub struct HookAllowlist {
+    #[max_len(MAX_ALLOWED_HOOKS)]
+    pub programs: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+impl HookAllowlist {
+    pub fn is_allowed(&self, program: &Pubkey) -> bool {
+        self.programs.contains(program)
+    }
+}
+
+pub fn find_hook_allowlist_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[HOOK_ALLOWLIST_SEED], &crate::ID)
+}
+
+pub fn hook_discriminator() -> [u8; 8] {
+    let preimage = format!("global:{}", HOOK_INSTRUCTION_NAME);
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash(preimage.as_bytes()).to_bytes()[..8]);
+    discriminator
+}
+
+#[derive(Accounts)]
+pub struct SetHookProgramAllowed<'info> {
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + HookAllowlist::INIT_SPACE,
+        seeds = [HOOK_ALLOWLIST_SEED],
+        bump
+    )]
+    pub allowlist: Account<'info, HookAllowlist>,
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED],
+        bump = protocol_config.bump,
+        has_one = admin @ BlinkError::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetBlinkHook<'info> {
+    #[account(mut, has_one = owner)]
+    pub blink: Account<'info, Blink>,
+    #[account(seeds = [HOOK_ALLOWLIST_SEED], bump = allowlist.bump)]
+    pub allowlist: Account<'info, HookAllowlist>,
+    pub owner: Signer<'info>,
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+pub fn set_hook_program_allowed(
+    ctx: Context<SetHookProgramAllowed>,
+    program: Pubkey,
+    allowed: bool,
+) -> Result<()> {
+    let allowlist = &mut ctx.accounts.allowlist;
+
+    allowlist.bump = ctx.bumps.allowlist;
+    allowlist.programs.retain(|existing| *existing != program);
+    if allowed {
+        if allowlist.programs.len() >= MAX_ALLOWED_HOOKS {
+            return Err(BlinkError::TooManyHookPrograms.into());
+        }
+        allowlist.programs.push(program);
+    }
+
+    Ok(())
+}
+
+// Passing None removes the hook
+pub fn set_blink_hook(ctx: Context<SetBlinkHook>, hook: Option<BlinkHook>) -> Result<()> {
+    ctx.accounts.protocol_config.check_not_paused(ProtocolModule::Blinks)?;
+
+    let blink = &mut ctx.accounts.blink;
+    let clock = Clock::get()?;
+
+    blink.check_not_frozen()?;
+
+    if let Some(hook) = &hook {
+        if !ctx.accounts.allowlist.is_allowed(&hook.program) {
+            return Err(BlinkError::HookProgramNotAllowed.into());
+        }
+        // A hook that points back at this program could re-enter it
+        if hook.program == crate::ID {
+            return Err(BlinkError::HookProgramNotAllowed.into());
+        }
+    }
+
+    blink.hook = hook.clone();
+    blink.updated_at = clock.unix_timestamp;
+
+    emit!(BlinkHookSet {
+        blink: blink.key(),
+        owner: ctx.accounts.owner.key(),
+        hook,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Invokes the blink's hook, if any, after an action has completed. `accounts` are
+// [hook_allowlist, hook_program, ...accounts forwarded to the hook]; the forwarded
+// accounts never carry signer privileges.
+pub(crate) fn run_post_action_hook<'info>(
+    blink: &Account<'info, Blink>,
+    payer: &AccountInfo<'info>,
+    payload: BlinkHookPayload,
+    accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    let hook = match &blink.hook {
+        Some(hook) => hook,
+        None => return Ok(()),
+    };
+
+    let (allowlist_info, hook_program, forwarded) = match accounts {
+        [allowlist_info, hook_program, forwarded @ ..] if hook_program.key() == hook.program => {
+            (allowlist_info, hook_program, forwarded)
+        }
+        _ => return hook_unavailable(hook, "hook accounts not supplied"),
+    };
+
+    let (expected_allowlist, _) = find_hook_allowlist_address();
+    if allowlist_info.key() != expected_allowlist {
+        return Err(BlinkError::InvalidProgramAddress.into());
+    }
+    if allowlist_info.owner != &crate::ID {
+        return Err(BlinkError::InvalidProgramAddress.into());
+    }
+    let allowlist = HookAllowlist::try_deserialize(&mut &allowlist_info.try_borrow_data()?[..])?;
+    if !allowlist.is_allowed(&hook.program) {
+        return hook_unavailable(hook, "no longer allowed");
+    }
+
+    if sol_remaining_compute_units() < hook.compute_budget as u64 {
+        return hook_unavailable(hook, "not enough compute remaining");
+    }
+
+    // Hooks read the blink as updated by this action
+    blink.exit(&crate::ID)?;
+
+    let mut metas = vec![
+        AccountMeta::new_readonly(blink.key(), false),
+        AccountMeta::new_readonly(payer.key(), false),
+    ];
+    metas.extend(forwarded.iter().map(|account| {
+        if account.is_writable {
+            AccountMeta::new(account.key(), false)
+        } else {
+            AccountMeta::new_readonly(account.key(), false)
+        }
+    }));
+
+    let mut data = hook_discriminator().to_vec();
+    payload.serialize(&mut data)?;
+
+    let instruction = Instruction {
+        program_id: hook.program,
+        accounts: metas,
+        data,
+    };
+
+    let mut infos = vec![blink.to_account_info(), payer.clone()];
+    infos.extend(forwarded.iter().cloned());
+    infos.push(hook_program.clone());
+    invoke(&instruction, &infos)?;
+
+    Ok(())
+}
+
+fn hook_unavailable(hook: &BlinkHook, reason: &str) -> Result<()> {
+    match hook.failure_mode {
+        HookFailureMode::Abort => {
+            msg!("Hook {} unavailable: {}", hook.program, reason);
+            Err(BlinkError::HookUnavailable.into())
+        }
+        HookFailureMode::Skip => {
+            msg!("Skipping hook {}: {}", hook.program, reason);
+            Ok(())
+        }
+    }
+}
@@ -1,24 +1,49 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token};
-use mpl_token_metadata::instruction::create_metadata_accounts_v2;
-
-mod blink;
-mod nft;
-mod collection;
-mod errors;
-mod donations;
-mod payments;
-mod mint;
-mod swap;
+
+pub mod blink;
+pub mod nft;
+pub mod collection;
+pub mod errors;
+pub mod events;
+pub mod donations;
+pub mod payments;
+pub mod mint;
+pub mod swap;
+pub mod poll;
+pub mod gift;
+pub mod vault;
+pub mod execute;
+pub mod history;
+pub mod attributes;
+pub mod hooks;
+pub mod handle;
+pub mod moderation;
+pub mod template;
+pub mod batch;
+pub mod gate;
+pub mod protocol;
+pub mod utils;
 
 use blink::*;
 use nft::*;
 use collection::*;
-use errors::*;
 use donations::*;
 use payments::*;
 use mint::*;
 use swap::*;
+use poll::*;
+use gift::*;
+use vault::*;
+use execute::*;
+use history::*;
+use attributes::*;
+use hooks::*;
+use handle::*;
+use moderation::*;
+use template::*;
+use batch::*;
+use gate::*;
+use protocol::*;
 
 declare_id!("BARK_PROGRAM_ID_HERE");
 
@@ -26,7 +51,129 @@ declare_id!("BARK_PROGRAM_ID_HERE");
 pub mod blink_program {
     use super::*;
 
-    // Existing instructions...
+    pub fn create_blink(
+        ctx: Context<CreateBlink>,
+        name: String,
+        description: String,
+        blink_type: BlinkType,
+        image_url: String,
+        config: BlinkConfig,
+    ) -> Result<()> {
+        blink::create_blink(ctx, name, description, blink_type, image_url, config)
+    }
+
+    pub fn update_blink(
+        ctx: Context<UpdateBlink>,
+        name: String,
+        description: String,
+        image_url: String,
+        config: Option<BlinkConfig>,
+    ) -> Result<()> {
+        blink::update_blink(ctx, name, description, image_url, config)
+    }
+
+    pub fn delete_blink(ctx: Context<DeleteBlink>) -> Result<()> {
+        blink::delete_blink(ctx)
+    }
+
+    pub fn set_blink_paused(ctx: Context<ManageBlink>, paused: bool) -> Result<()> {
+        blink::set_blink_paused(ctx, paused)
+    }
+
+    pub fn set_blink_schedule(
+        ctx: Context<ManageBlink>,
+        starts_at: i64,
+        ends_at: Option<i64>,
+    ) -> Result<()> {
+        blink::set_blink_schedule(ctx, starts_at, ends_at)
+    }
+
+    pub fn transfer_blink_ownership(
+        ctx: Context<TransferBlinkOwnership>,
+        new_owner: Option<Pubkey>,
+    ) -> Result<()> {
+        blink::transfer_blink_ownership(ctx, new_owner)
+    }
+
+    pub fn accept_blink_ownership(ctx: Context<AcceptBlinkOwnership>) -> Result<()> {
+        blink::accept_blink_ownership(ctx)
+    }
+
+    pub fn set_blink_editor(
+        ctx: Context<SetBlinkEditor>,
+        editor: Pubkey,
+        permissions: u8,
+    ) -> Result<()> {
+        blink::set_blink_editor(ctx, editor, permissions)
+    }
+
+    pub fn set_blink_image_hash(
+        ctx: Context<ManageBlink>,
+        image_hash: Option<[u8; 32]>,
+    ) -> Result<()> {
+        blink::set_blink_image_hash(ctx, image_hash)
+    }
+
+    pub fn verify_blink_content(ctx: Context<VerifyBlinkContent>, data: Vec<u8>) -> Result<()> {
+        blink::verify_blink_content(ctx, data)
+    }
+
+    pub fn create_nft(
+        ctx: Context<CreateNFT>,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        nft::create_nft(ctx, name, symbol, uri)
+    }
+
+    pub fn set_nft_uri_hash(ctx: Context<SetNFTUriHash>, uri_hash: Option<[u8; 32]>) -> Result<()> {
+        nft::set_nft_uri_hash(ctx, uri_hash)
+    }
+
+    pub fn verify_nft_content(ctx: Context<VerifyNFTContent>, data: Vec<u8>) -> Result<()> {
+        nft::verify_nft_content(ctx, data)
+    }
+
+    pub fn create_collection(
+        ctx: Context<CreateCollection>,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        collection::create_collection(ctx, name, symbol, uri)
+    }
+
+    pub fn add_nft_to_collection(ctx: Context<AddNFTToCollection>) -> Result<()> {
+        collection::add_nft_to_collection(ctx)
+    }
+
+    pub fn create_donation<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateDonation<'info>>,
+        amount: u64,
+        currency: String,
+        message: String,
+    ) -> Result<()> {
+        donations::create_donation(ctx, amount, currency, message)
+    }
+
+    pub fn create_payment<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreatePayment<'info>>,
+        amount: u64,
+        currency: String,
+        description: String,
+    ) -> Result<()> {
+        payments::create_payment(ctx, amount, currency, description)
+    }
+
+    pub fn create_mint(
+        ctx: Context<CreateMint>,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        mint::create_mint(ctx, name, symbol, uri)
+    }
 
     pub fn create_swap(
         ctx: Context<CreateSwap>,
@@ -40,4 +187,254 @@ pub mod blink_program {
     pub fn execute_swap(ctx: Context<ExecuteSwap>) -> Result<()> {
         swap::execute_swap(ctx)
     }
-}
\ No newline at end of file
+
+    pub fn open_poll(ctx: Context<OpenPoll>, closes_at: i64) -> Result<()> {
+        poll::open_poll(ctx, closes_at)
+    }
+
+    pub fn cast_vote(ctx: Context<CastVote>, option: u8) -> Result<()> {
+        poll::cast_vote(ctx, option)
+    }
+
+    pub fn withdraw_vote_tokens(ctx: Context<WithdrawVoteTokens>) -> Result<()> {
+        poll::withdraw_vote_tokens(ctx)
+    }
+
+    pub fn get_poll_results(ctx: Context<GetPollResults>) -> Result<PollResults> {
+        poll::get_poll_results(ctx)
+    }
+
+    pub fn fund_gift(ctx: Context<FundGift>) -> Result<()> {
+        gift::fund_gift(ctx)
+    }
+
+    pub fn claim_gift<'info>(ctx: Context<'_, '_, '_, 'info, ClaimGift<'info>>) -> Result<()> {
+        gift::claim_gift(ctx)
+    }
+
+    pub fn reclaim_gift(ctx: Context<ReclaimGift>) -> Result<()> {
+        gift::reclaim_gift(ctx)
+    }
+
+    pub fn open_blink_vault(ctx: Context<OpenBlinkVault>) -> Result<()> {
+        vault::open_blink_vault(ctx)
+    }
+
+    pub fn withdraw_from_blink_vault(
+        ctx: Context<WithdrawFromBlinkVault>,
+        amount: u64,
+    ) -> Result<()> {
+        vault::withdraw_from_blink_vault(ctx, amount)
+    }
+
+    pub fn close_blink_vault(ctx: Context<CloseBlinkVault>) -> Result<()> {
+        vault::close_blink_vault(ctx)
+    }
+
+    pub fn execute_blink<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExecuteBlink<'info>>,
+        args: ExecuteBlinkArgs,
+    ) -> Result<()> {
+        execute::execute_blink(ctx, args)
+    }
+
+    pub fn open_blink_history(ctx: Context<OpenBlinkHistory>) -> Result<()> {
+        history::open_blink_history(ctx)
+    }
+
+    pub fn add_blink_attribute(
+        ctx: Context<ManageBlinkAttributes>,
+        trait_type: String,
+        value: String,
+        numeric_value: Option<u64>,
+    ) -> Result<()> {
+        attributes::add_blink_attribute(ctx, trait_type, value, numeric_value)
+    }
+
+    pub fn update_blink_attribute(
+        ctx: Context<ManageBlinkAttributes>,
+        trait_type: String,
+        value: String,
+        numeric_value: Option<u64>,
+    ) -> Result<()> {
+        attributes::update_blink_attribute(ctx, trait_type, value, numeric_value)
+    }
+
+    pub fn remove_blink_attribute(
+        ctx: Context<ManageBlinkAttributes>,
+        trait_type: String,
+    ) -> Result<()> {
+        attributes::remove_blink_attribute(ctx, trait_type)
+    }
+
+    pub fn add_blink_ability(
+        ctx: Context<ManageBlinkAttributes>,
+        name: String,
+        description: String,
+        cooldown: u32,
+        power_cost: u32,
+    ) -> Result<()> {
+        attributes::add_blink_ability(ctx, name, description, cooldown, power_cost)
+    }
+
+    pub fn update_blink_ability(
+        ctx: Context<ManageBlinkAttributes>,
+        name: String,
+        description: String,
+        cooldown: u32,
+        power_cost: u32,
+    ) -> Result<()> {
+        attributes::update_blink_ability(ctx, name, description, cooldown, power_cost)
+    }
+
+    pub fn remove_blink_ability(ctx: Context<ManageBlinkAttributes>, name: String) -> Result<()> {
+        attributes::remove_blink_ability(ctx, name)
+    }
+
+    pub fn set_hook_program_allowed(
+        ctx: Context<SetHookProgramAllowed>,
+        program: Pubkey,
+        allowed: bool,
+    ) -> Result<()> {
+        hooks::set_hook_program_allowed(ctx, program, allowed)
+    }
+
+    pub fn set_blink_hook(ctx: Context<SetBlinkHook>, hook: Option<BlinkHook>) -> Result<()> {
+        hooks::set_blink_hook(ctx, hook)
+    }
+
+    pub fn register_handle(ctx: Context<RegisterHandle>, handle: String) -> Result<()> {
+        handle::register_handle(ctx, handle)
+    }
+
+    pub fn transfer_handle(ctx: Context<TransferHandle>) -> Result<()> {
+        handle::transfer_handle(ctx)
+    }
+
+    pub fn release_handle(ctx: Context<ReleaseHandle>) -> Result<()> {
+        handle::release_handle(ctx)
+    }
+
+    pub fn set_moderator(
+        ctx: Context<SetModerator>,
+        moderator: Pubkey,
+        enabled: bool,
+    ) -> Result<()> {
+        moderation::set_moderator(ctx, moderator, enabled)
+    }
+
+    pub fn moderate_blink(
+        ctx: Context<ModerateBlink>,
+        status: ModerationStatus,
+        reason: u16,
+    ) -> Result<()> {
+        moderation::moderate_blink(ctx, status, reason)
+    }
+
+    pub fn clear_moderation(ctx: Context<ModerateBlink>) -> Result<()> {
+        moderation::clear_moderation(ctx)
+    }
+
+    pub fn appeal_moderation(ctx: Context<AppealModeration>) -> Result<()> {
+        moderation::appeal_moderation(ctx)
+    }
+
+    pub fn create_blink_template(
+        ctx: Context<CreateBlinkTemplate>,
+        seed: u64,
+        name: String,
+        description: String,
+        image_url: String,
+        config: BlinkConfig,
+        fee_lamports: u64,
+    ) -> Result<()> {
+        template::create_blink_template(
+            ctx,
+            seed,
+            name,
+            description,
+            image_url,
+            config,
+            fee_lamports,
+        )
+    }
+
+    pub fn set_template_fee(ctx: Context<SetTemplateFee>, fee_lamports: u64) -> Result<()> {
+        template::set_template_fee(ctx, fee_lamports)
+    }
+
+    pub fn close_blink_template(ctx: Context<CloseBlinkTemplate>) -> Result<()> {
+        template::close_blink_template(ctx)
+    }
+
+    pub fn create_blink_from_template(
+        ctx: Context<CreateBlinkFromTemplate>,
+        name: Option<String>,
+        config: Option<BlinkConfig>,
+        max_fee_lamports: u64,
+    ) -> Result<()> {
+        template::create_blink_from_template(ctx, name, config, max_fee_lamports)
+    }
+
+    pub fn batch_create_blinks<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchCreateBlinks<'info>>,
+        items: Vec<BlinkInput>,
+    ) -> Result<()> {
+        batch::batch_create_blinks(ctx, items)
+    }
+
+    pub fn batch_update_blinks<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchUpdateBlinks<'info>>,
+        items: Vec<BlinkUpdate>,
+    ) -> Result<()> {
+        batch::batch_update_blinks(ctx, items)
+    }
+
+    pub fn batch_delete_blinks<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchDeleteBlinks<'info>>,
+    ) -> Result<()> {
+        batch::batch_delete_blinks(ctx)
+    }
+
+    pub fn set_blink_gate(ctx: Context<ManageBlink>, gate: Option<TokenGate>) -> Result<()> {
+        gate::set_blink_gate(ctx, gate)
+    }
+
+    pub fn initialize_protocol_config(
+        ctx: Context<InitializeProtocolConfig>,
+        admin: Pubkey,
+        treasury: Pubkey,
+        fees: ProtocolFees,
+        features: ProtocolFeatures,
+    ) -> Result<()> {
+        protocol::initialize_protocol_config(ctx, admin, treasury, fees, features)
+    }
+
+    pub fn update_protocol_config(
+        ctx: Context<UpdateProtocolConfig>,
+        treasury: Pubkey,
+        fees: ProtocolFees,
+        features: ProtocolFeatures,
+    ) -> Result<()> {
+        protocol::update_protocol_config(ctx, treasury, fees, features)
+    }
+
+    pub fn set_module_paused(
+        ctx: Context<UpdateProtocolConfig>,
+        module: ProtocolModule,
+        paused: bool,
+    ) -> Result<()> {
+        protocol::set_module_paused(ctx, module, paused)
+    }
+
+    pub fn propose_protocol_admin(
+        ctx: Context<UpdateProtocolConfig>,
+        new_admin: Option<Pubkey>,
+    ) -> Result<()> {
+        protocol::propose_protocol_admin(ctx, new_admin)
+    }
+
+    pub fn accept_protocol_admin(ctx: Context<AcceptProtocolAdmin>) -> Result<()> {
+        protocol::accept_protocol_admin(ctx)
+    }
+}
@@ -1,6 +1,14 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount};
-use mpl_token_metadata::instruction::create_metadata_accounts_v2;
+use anchor_lang::system_program::{self, Transfer};
+use anchor_spl::token::Token;
+use mpl_token_metadata::instructions::{
+    CreateMetadataAccountV3, CreateMetadataAccountV3InstructionArgs,
+};
+use mpl_token_metadata::types::DataV2;
+use crate::errors::BlinkError;
+use crate::events::{MintCreated, ProtocolFeeCollected};
+use crate::nft::validate_token_metadata;
+use crate::protocol::{FeeSource, ProtocolConfig, ProtocolModule, PROTOCOL_CONFIG_SEED};
 
 #[derive(Accounts)]
 pub struct CreateMint<'info> {
@@ -17,6 +25,11 @@ pub struct CreateMint<'info> {
     pub metadata: AccountInfo<'info>,
     /// CHECK: This is not dangerous because we don't read or write from this account
     pub token_metadata_program: AccountInfo<'info>,
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    /// CHECK: Receives the mint fee; must be the protocol treasury
+    #[account(mut, address = protocol_config.treasury @ BlinkError::InvalidTreasuryAccount)]
+    pub treasury: AccountInfo<'info>,
 }
 
 pub fn create_mint(
@@ -25,6 +38,8 @@ pub fn create_mint(
     symbol: String,
     uri: String,
 ) -> Result<()> {
+    ctx.accounts.protocol_config.check_not_paused(ProtocolModule::Mints)?;
+
     let mint = &ctx.accounts.mint;
     let mint_authority = &ctx.accounts.mint_authority;
     let metadata = &ctx.accounts.metadata;
@@ -33,6 +48,27 @@ pub fn create_mint(
     let system_program = &ctx.accounts.system_program;
     let rent = &ctx.accounts.rent;
 
+    validate_token_metadata(&name, &symbol, &uri)?;
+
+    let fee = ctx.accounts.protocol_config.mint_fee();
+    if fee > 0 {
+        let cpi_accounts = Transfer {
+            from: mint_authority.to_account_info(),
+            to: ctx.accounts.treasury.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(system_program.to_account_info(), cpi_accounts);
+        system_program::transfer(cpi_ctx, fee)?;
+
+        emit!(ProtocolFeeCollected {
+            source: FeeSource::Mint,
+            payer: mint_authority.key(),
+            treasury: ctx.accounts.treasury.key(),
+            mint: None,
+            amount: fee,
+            fee,
+        });
+    }
+
     // Create mint account
     let cpi_accounts = anchor_spl::token::InitializeMint {
         mint: mint.to_account_info(),
@@ -43,23 +79,28 @@ pub fn create_mint(
     anchor_spl::token::initialize_mint(cpi_ctx, 0, mint_authority.key, Some(mint_authority.key))?;
 
     // Create metadata account
-    let create_metadata_ix = create_metadata_accounts_v2(
-        token_metadata_program.key(),
-        metadata.key(),
-        mint.key(),
-        mint_authority.key(),
-        mint_authority.key(),
-        mint_authority.key(),
-        name,
-        symbol,
-        uri,
-        None,
-        0,
-        true,
-        false,
-        None,
-        None,
-    );
+    let create_metadata_ix = CreateMetadataAccountV3 {
+        metadata: metadata.key(),
+        mint: mint.key(),
+        mint_authority: mint_authority.key(),
+        payer: mint_authority.key(),
+        update_authority: (mint_authority.key(), true),
+        system_program: system_program.key(),
+        rent: Some(rent.key()),
+    }
+    .instruction(CreateMetadataAccountV3InstructionArgs {
+        data: DataV2 {
+            name: name.clone(),
+            symbol: symbol.clone(),
+            uri: uri.clone(),
+            seller_fee_basis_points: 0,
+            creators: None,
+            collection: None,
+            uses: None,
+        },
+        is_mutable: false,
+        collection_details: None,
+    });
 
     anchor_lang::solana_program::program::invoke(
         &create_metadata_ix,
@@ -74,5 +115,16 @@ pub fn create_mint(
         ],
     )?;
 
+    emit!(MintCreated {
+        mint: mint.key(),
+        mint_authority: mint_authority.key(),
+        metadata: metadata.key(),
+        name,
+        symbol,
+        uri,
+        decimals: 0,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,165 @@
+use anchor_lang::prelude::*;
+use crate::blink::Blink;
+use crate::errors::BlinkError;
+use crate::events::{BlinkModerated, BlinkModerationAppealed, BlinkModerationCleared};
+use crate::protocol::{ProtocolConfig, PROTOCOL_CONFIG_SEED};
+
+pub const MODERATORS_SEED: &[u8] = b"moderators";
+pub const MAX_MODERATORS: usize = 16;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum ModerationStatus {
+    // Visible warning; the blink keeps working
+    Flagged,
+    // Every action and edit is blocked until a moderator clears it
+    Frozen,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub struct Moderation {
+    pub status: ModerationStatus,
+    // Protocol-defined code, e.g. scam or impersonation
+    pub reason: u16,
+    pub moderator: Pubkey,
+    pub moderated_at: i64,
+    pub appealed: bool,
+}
+
+// Accounts allowed to moderate blinks, managed by the protocol admin
+#[account]
+#[derive(InitSpace)]
+pub struct Moderators {
+    #[max_len(MAX_MODERATORS)]
+    pub moderators: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+impl Moderators {
+    pub fn is_moderator(&self, account: &Pubkey) -> bool {
+        self.moderators.contains(account)
+    }
+}
+
+pub fn find_moderators_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[MODERATORS_SEED], &crate::ID)
+}
+
+#[derive(Accounts)]
+pub struct SetModerator<'info> {
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + Moderators::INIT_SPACE,
+        seeds = [MODERATORS_SEED],
+        bump
+    )]
+    pub moderators: Account<'info, Moderators>,
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED],
+        bump = protocol_config.bump,
+        has_one = admin @ BlinkError::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ModerateBlink<'info> {
+    #[account(mut)]
+    pub blink: Account<'info, Blink>,
+    #[account(
+        seeds = [MODERATORS_SEED],
+        bump = moderators.bump,
+        constraint = moderators.is_moderator(moderator.key) @ BlinkError::Unauthorized
+    )]
+    pub moderators: Account<'info, Moderators>,
+    pub moderator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AppealModeration<'info> {
+    #[account(mut, has_one = owner)]
+    pub blink: Account<'info, Blink>,
+    pub owner: Signer<'info>,
+}
+
+pub fn set_moderator(ctx: Context<SetModerator>, moderator: Pubkey, enabled: bool) -> Result<()> {
+    let moderators = &mut ctx.accounts.moderators;
+
+    moderators.bump = ctx.bumps.moderators;
+    moderators.moderators.retain(|existing| *existing != moderator);
+    if enabled {
+        if moderators.moderators.len() >= MAX_MODERATORS {
+            return Err(BlinkError::TooManyModerators.into());
+        }
+        moderators.moderators.push(moderator);
+    }
+
+    Ok(())
+}
+
+// Flags or freezes the blink; moderating it again replaces the previous decision
+// and dismisses any pending appeal.
+pub fn moderate_blink(
+    ctx: Context<ModerateBlink>,
+    status: ModerationStatus,
+    reason: u16,
+) -> Result<()> {
+    let blink = &mut ctx.accounts.blink;
+    let moderator = &ctx.accounts.moderator;
+    let clock = Clock::get()?;
+
+    blink.moderation = Some(Moderation {
+        status,
+        reason,
+        moderator: moderator.key(),
+        moderated_at: clock.unix_timestamp,
+        appealed: false,
+    });
+
+    emit!(BlinkModerated {
+        blink: blink.key(),
+        moderator: moderator.key(),
+        status,
+        reason,
+    });
+
+    Ok(())
+}
+
+pub fn clear_moderation(ctx: Context<ModerateBlink>) -> Result<()> {
+    let blink = &mut ctx.accounts.blink;
+
+    if blink.moderation.is_none() {
+        return Err(BlinkError::NotModerated.into());
+    }
+    blink.moderation = None;
+
+    emit!(BlinkModerationCleared {
+        blink: blink.key(),
+        moderator: ctx.accounts.moderator.key(),
+    });
+
+    Ok(())
+}
+
+pub fn appeal_moderation(ctx: Context<AppealModeration>) -> Result<()> {
+    let blink = &mut ctx.accounts.blink;
+
+    let moderation = blink.moderation.as_mut().ok_or(BlinkError::NotModerated)?;
+    if moderation.appealed {
+        return Err(BlinkError::AppealAlreadyFiled.into());
+    }
+    moderation.appealed = true;
+    let reason = moderation.reason;
+
+    emit!(BlinkModerationAppealed {
+        blink: blink.key(),
+        owner: ctx.accounts.owner.key(),
+        reason,
+    });
+
+    Ok(())
+}
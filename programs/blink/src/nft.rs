@@ -1,21 +1,66 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token};
-use mpl_token_metadata::instruction::create_metadata_accounts_v2;
+use anchor_spl::token::Token;
+use mpl_token_metadata::instructions::{
+    CreateMetadataAccountV3, CreateMetadataAccountV3InstructionArgs,
+};
+use mpl_token_metadata::types::DataV2;
+use crate::errors::BlinkError;
+use crate::events::NFTCreated;
+use crate::protocol::{ProtocolConfig, ProtocolModule, PROTOCOL_CONFIG_SEED};
+use crate::utils::{validate_uri, verify_content_hash};
+
+// Limits match the Metaplex metadata program
+pub const MAX_NFT_NAME_LEN: usize = 32;
+pub const MAX_SYMBOL_LEN: usize = 10;
+pub const MAX_URI_LEN: usize = 200;
 
 #[account]
+#[derive(InitSpace)]
 pub struct NFT {
     pub owner: Pubkey,
     pub mint: Pubkey,
+    #[max_len(MAX_NFT_NAME_LEN)]
     pub name: String,
+    #[max_len(MAX_SYMBOL_LEN)]
     pub symbol: String,
+    #[max_len(MAX_URI_LEN)]
     pub uri: String,
+    // SHA-256 of the metadata JSON at `uri`
+    pub uri_hash: Option<[u8; 32]>,
     pub collection: Pubkey,
     pub created_at: i64,
 }
 
+impl NFT {
+    pub fn space_for(name: &str, symbol: &str, uri: &str) -> usize {
+        8 + NFT::INIT_SPACE
+            - MAX_NFT_NAME_LEN.saturating_sub(name.len())
+            - MAX_SYMBOL_LEN.saturating_sub(symbol.len())
+            - MAX_URI_LEN.saturating_sub(uri.len())
+    }
+}
+
+pub fn validate_token_metadata(name: &str, symbol: &str, uri: &str) -> Result<()> {
+    if name.len() > MAX_NFT_NAME_LEN {
+        return Err(BlinkError::NameTooLong.into());
+    }
+
+    if symbol.len() > MAX_SYMBOL_LEN {
+        return Err(BlinkError::SymbolTooLong.into());
+    }
+
+    if uri.len() > MAX_URI_LEN {
+        return Err(BlinkError::UrlTooLong.into());
+    }
+    validate_uri(uri)?;
+
+    Ok(())
+}
+
 #[derive(Accounts)]
+#[instruction(name: String, symbol: String, uri: String)]
 pub struct CreateNFT<'info> {
-    #[account(init, payer = owner, space = 8 + 32 + 32 + 4 + 50 + 4 + 10 + 4 + 200 + 32 + 8)]
+    #[account(init, payer = owner, space = NFT::space_for(&name, &symbol, &uri))]
     pub nft: Account<'info, NFT>,
     #[account(mut)]
     pub owner: Signer<'info>,
@@ -26,11 +71,26 @@ pub struct CreateNFT<'info> {
     pub metadata: AccountInfo<'info>,
     #[account(mut)]
     pub mint: Signer<'info>,
+    /// CHECK: This is not dangerous because we don't read or write from this account
     #[account(mut)]
     pub token_account: AccountInfo<'info>,
     /// CHECK: This is not dangerous because we don't read or write from this account
     pub metadata_program: AccountInfo<'info>,
     pub rent: Sysvar<'info, Rent>,
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+#[derive(Accounts)]
+pub struct SetNFTUriHash<'info> {
+    #[account(mut, has_one = owner)]
+    pub nft: Account<'info, NFT>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyNFTContent<'info> {
+    pub nft: Account<'info, NFT>,
 }
 
 pub fn create_nft(
@@ -39,17 +99,13 @@ pub fn create_nft(
     symbol: String,
     uri: String,
 ) -> Result<()> {
+    ctx.accounts.protocol_config.check_not_paused(ProtocolModule::Nfts)?;
+
     let nft = &mut ctx.accounts.nft;
     let owner = &ctx.accounts.owner;
     let clock = Clock::get()?;
 
-    if name.len() > 32 {
-        return Err(BlinkError::NameTooLong.into());
-    }
-
-    if symbol.len() > 10 {
-        return Err(BlinkError::SymbolTooLong.into());
-    }
+    validate_token_metadata(&name, &symbol, &uri)?;
 
     nft.owner = *owner.key;
     nft.mint = ctx.accounts.mint.key();
@@ -60,23 +116,28 @@ pub fn create_nft(
     nft.created_at = clock.unix_timestamp;
 
     // Create metadata account
-    let create_metadata_ix = create_metadata_accounts_v2(
-        ctx.accounts.metadata_program.key(),
-        ctx.accounts.metadata.key(),
-        ctx.accounts.mint.key(),
-        ctx.accounts.owner.key(),
-        ctx.accounts.owner.key(),
-        ctx.accounts.owner.key(),
-        name,
-        symbol,
-        uri,
-        None,
-        0,
-        true,
-        true,
-        None,
-        None,
-    );
+    let create_metadata_ix = CreateMetadataAccountV3 {
+        metadata: ctx.accounts.metadata.key(),
+        mint: ctx.accounts.mint.key(),
+        mint_authority: ctx.accounts.owner.key(),
+        payer: ctx.accounts.owner.key(),
+        update_authority: (ctx.accounts.owner.key(), true),
+        system_program: ctx.accounts.system_program.key(),
+        rent: Some(ctx.accounts.rent.key()),
+    }
+    .instruction(CreateMetadataAccountV3InstructionArgs {
+        data: DataV2 {
+            name,
+            symbol,
+            uri,
+            seller_fee_basis_points: 0,
+            creators: None,
+            collection: None,
+            uses: None,
+        },
+        is_mutable: true,
+        collection_details: None,
+    });
 
     anchor_lang::solana_program::program::invoke(
         &create_metadata_ix,
@@ -91,5 +152,26 @@ pub fn create_nft(
         ],
     )?;
 
+    emit!(NFTCreated {
+        nft: nft.key(),
+        mint: nft.mint,
+        owner: nft.owner,
+        name: nft.name.clone(),
+        symbol: nft.symbol.clone(),
+        uri: nft.uri.clone(),
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn set_nft_uri_hash(ctx: Context<SetNFTUriHash>, uri_hash: Option<[u8; 32]>) -> Result<()> {
+    ctx.accounts.nft.uri_hash = uri_hash;
+
     Ok(())
-}
\ No newline at end of file
+}
+
+// Only practical for small payloads since `data` must fit in a transaction
+pub fn verify_nft_content(ctx: Context<VerifyNFTContent>, data: Vec<u8>) -> Result<()> {
+    verify_content_hash(ctx.accounts.nft.uri_hash, &data)
+}
@@ -1,11 +1,23 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::blink::{Blink, BlinkConfig};
+use crate::errors::BlinkError;
+use crate::events::PaymentCreated;
+use crate::gate::check_token_gate;
+use crate::history::{record_blink_execution, BlinkHistory};
+use crate::hooks::{run_post_action_hook, BlinkHookPayload, HookAction};
+use crate::protocol::{
+    collect_token_fee, FeeSource, ProtocolConfig, ProtocolModule, PROTOCOL_CONFIG_SEED,
+};
+use crate::vault::check_blink_vault;
 
 #[account]
 pub struct Payment {
     pub payer: Pubkey,
     pub recipient: Pubkey,
     pub amount: u64,
+    // Protocol fee taken out of `amount`
+    pub fee: u64,
     pub currency: String,
     pub description: String,
     pub status: PaymentStatus,
@@ -20,9 +32,11 @@ pub enum PaymentStatus {
     Refunded,
 }
 
+// Remaining accounts: the token gate proof when the blink is gated, then the blink's
+// hook accounts when it has a hook
 #[derive(Accounts)]
 pub struct CreatePayment<'info> {
-    #[account(init, payer = payer, space = 8 + 32 + 32 + 8 + 4 + 10 + 4 + 200 + 1 + 8)]
+    #[account(init, payer = payer, space = 8 + 32 + 32 + 8 + 8 + 4 + 10 + 4 + 200 + 1 + 8)]
     pub payment: Account<'info, Payment>,
     #[account(mut)]
     pub payer: Signer<'info>,
@@ -34,14 +48,26 @@ pub struct CreatePayment<'info> {
     pub recipient_token_account: Account<'info, TokenAccount>,
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    // Required when a protocol fee is owed
+    #[account(mut)]
+    pub treasury_token_account: Option<Account<'info, TokenAccount>>,
+    // When set, the payment is routed to this blink's vault instead of a wallet
+    #[account(mut)]
+    pub blink: Option<Account<'info, Blink>>,
+    #[account(mut)]
+    pub history: Option<Account<'info, BlinkHistory>>,
 }
 
-pub fn create_payment(
-    ctx: Context<CreatePayment>,
+pub fn create_payment<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreatePayment<'info>>,
     amount: u64,
     currency: String,
     description: String,
 ) -> Result<()> {
+    ctx.accounts.protocol_config.check_not_paused(ProtocolModule::Payments)?;
+
     let payment = &mut ctx.accounts.payment;
     let clock = Clock::get()?;
 
@@ -53,6 +79,35 @@ pub fn create_payment(
         return Err(BlinkError::DescriptionTooLong.into());
     }
 
+    let mut hook_accounts = ctx.remaining_accounts;
+    if let Some(blink) = &ctx.accounts.blink {
+        if !ctx.accounts.protocol_config.features.blink_routing {
+            return Err(BlinkError::FeatureDisabled.into());
+        }
+        ctx.accounts.protocol_config.check_not_paused(ProtocolModule::Blinks)?;
+        blink.check_active(clock.unix_timestamp)?;
+        if amount == 0 {
+            return Err(BlinkError::InsufficientFunds.into());
+        }
+        hook_accounts = check_token_gate(blink, ctx.accounts.payer.key, hook_accounts)?;
+        if ctx.accounts.recipient.key() != blink.key() {
+            return Err(BlinkError::InvalidRecipient.into());
+        }
+        check_blink_vault(&blink.key(), &ctx.accounts.recipient_token_account)?;
+        // A blink only takes payments at its configured price and mint
+        match &blink.config {
+            BlinkConfig::Payment { mint, price, .. } => {
+                if ctx.accounts.recipient_token_account.mint != *mint {
+                    return Err(BlinkError::InvalidMint.into());
+                }
+                if amount != *price {
+                    return Err(BlinkError::PriceMismatch.into());
+                }
+            }
+            _ => return Err(BlinkError::InvalidBlinkType.into()),
+        }
+    }
+
     payment.payer = ctx.accounts.payer.key();
     payment.recipient = ctx.accounts.recipient.key();
     payment.amount = amount;
@@ -61,7 +116,18 @@ pub fn create_payment(
     payment.status = PaymentStatus::Pending;
     payment.timestamp = clock.unix_timestamp;
 
-    // Transfer tokens from payer to recipient
+    let fee = collect_token_fee(
+        &ctx.accounts.protocol_config,
+        FeeSource::Payment,
+        amount,
+        &ctx.accounts.payer_token_account,
+        &ctx.accounts.payer.to_account_info(),
+        ctx.accounts.treasury_token_account.as_ref(),
+        &ctx.accounts.token_program.to_account_info(),
+    )?;
+    payment.fee = fee;
+
+    // Transfer the rest from payer to recipient
     let cpi_accounts = Transfer {
         from: ctx.accounts.payer_token_account.to_account_info(),
         to: ctx.accounts.recipient_token_account.to_account_info(),
@@ -69,9 +135,47 @@ pub fn create_payment(
     };
     let cpi_program = ctx.accounts.token_program.to_account_info();
     let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-    token::transfer(cpi_ctx, amount)?;
+    token::transfer(cpi_ctx, amount - fee)?;
 
     payment.status = PaymentStatus::Completed;
 
+    emit!(PaymentCreated {
+        payment: payment.key(),
+        payer: payment.payer,
+        recipient: payment.recipient,
+        mint: ctx.accounts.recipient_token_account.mint,
+        amount,
+        fee,
+        currency: payment.currency.clone(),
+        status: payment.status.clone(),
+        blink: ctx.accounts.blink.as_ref().map(|blink| blink.key()),
+        timestamp: clock.unix_timestamp,
+    });
+
+    if let Some(blink) = ctx.accounts.blink.as_mut() {
+        let mint = ctx.accounts.recipient_token_account.mint;
+        record_blink_execution(
+            blink,
+            ctx.accounts.history.as_mut(),
+            ctx.accounts.payer.key,
+            Some(mint),
+            amount,
+        )?;
+
+        let payload = BlinkHookPayload {
+            action: HookAction::Payment,
+            blink: blink.key(),
+            payer: ctx.accounts.payer.key(),
+            mint,
+            amount,
+        };
+        run_post_action_hook(
+            blink,
+            &ctx.accounts.payer.to_account_info(),
+            payload,
+            hook_accounts,
+        )?;
+    }
+
     Ok(())
 }
\ No newline at end of file
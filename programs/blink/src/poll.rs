@@ -0,0 +1,355 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use crate::blink::{Blink, BlinkConfig, BlinkType, MAX_POLL_OPTIONS, MAX_POLL_OPTION_LEN};
+use crate::errors::BlinkError;
+use crate::events::PollOpened;
+use crate::gate::check_token_gate;
+use crate::history::{record_blink_execution, BlinkHistory};
+use crate::protocol::{ProtocolConfig, ProtocolModule, PROTOCOL_CONFIG_SEED};
+
+pub const POLL_SEED: &[u8] = b"poll";
+pub const VOTE_RECEIPT_SEED: &[u8] = b"vote";
+pub const POLL_VAULT_SEED: &[u8] = b"poll_vault";
+
+pub fn find_poll_address(blink: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[POLL_SEED, blink.as_ref()], &crate::ID)
+}
+
+pub fn find_poll_vault_address(poll: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[POLL_VAULT_SEED, poll.as_ref()], &crate::ID)
+}
+
+pub fn find_vote_receipt_address(poll: &Pubkey, voter: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[VOTE_RECEIPT_SEED, poll.as_ref(), voter.as_ref()],
+        &crate::ID,
+    )
+}
+
+// Options are copied from the blink's Poll config when opened so later config edits
+// can't relabel or reorder the options votes were cast for.
+#[account]
+pub struct Poll {
+    pub blink: Pubkey,
+    // When set, each vote is weighted by the tokens the voter locks in the poll vault
+    pub vote_mint: Option<Pubkey>,
+    pub options: Vec<String>,
+    pub tallies: Vec<u64>,
+    pub voter_count: u64,
+    pub opened_at: i64,
+    pub closes_at: i64,
+    pub bump: u8,
+}
+
+impl Poll {
+    pub const LEN: usize = 8 + 32 + 33 + (4 + MAX_POLL_OPTIONS * (4 + MAX_POLL_OPTION_LEN))
+        + (4 + MAX_POLL_OPTIONS * 8) + 8 + 8 + 8 + 1;
+
+    pub fn is_closed(&self, now: i64) -> bool {
+        now >= self.closes_at
+    }
+
+    pub fn record_vote(&mut self, option: u8, weight: u64, now: i64) -> Result<()> {
+        if self.is_closed(now) {
+            return Err(BlinkError::PollClosed.into());
+        }
+
+        let tally = self
+            .tallies
+            .get_mut(option as usize)
+            .ok_or(BlinkError::InvalidPollOption)?;
+        *tally = tally.checked_add(weight).ok_or(BlinkError::MathOverflow)?;
+        self.voter_count = self
+            .voter_count
+            .checked_add(1)
+            .ok_or(BlinkError::MathOverflow)?;
+
+        Ok(())
+    }
+}
+
+// One receipt per (poll, voter); its existence is what prevents double voting.
+#[account]
+pub struct VoteReceipt {
+    pub poll: Pubkey,
+    pub voter: Pubkey,
+    pub option: u8,
+    pub weight: u64,
+    pub voted_at: i64,
+    pub bump: u8,
+}
+
+impl VoteReceipt {
+    pub const LEN: usize = 8 + 32 + 32 + 1 + 8 + 8 + 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PollResults {
+    pub options: Vec<String>,
+    pub tallies: Vec<u64>,
+    pub voter_count: u64,
+    pub closed: bool,
+}
+
+#[derive(Accounts)]
+pub struct OpenPoll<'info> {
+    #[account(
+        has_one = owner,
+        constraint = blink.blink_type == BlinkType::Poll @ BlinkError::InvalidBlinkType
+    )]
+    pub blink: Account<'info, Blink>,
+    #[account(
+        init,
+        payer = owner,
+        space = Poll::LEN,
+        seeds = [POLL_SEED, blink.key().as_ref()],
+        bump
+    )]
+    pub poll: Account<'info, Poll>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    // Passed together for token-weighted polls; the vault holds the tokens voters lock
+    pub vote_mint: Option<Account<'info, Mint>>,
+    #[account(
+        init,
+        payer = owner,
+        seeds = [POLL_VAULT_SEED, poll.key().as_ref()],
+        bump,
+        token::mint = vote_mint,
+        token::authority = poll
+    )]
+    pub vote_vault: Option<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct CastVote<'info> {
+    #[account(mut)]
+    pub blink: Account<'info, Blink>,
+    #[account(mut, has_one = blink, seeds = [POLL_SEED, blink.key().as_ref()], bump = poll.bump)]
+    pub poll: Account<'info, Poll>,
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = VoteReceipt::LEN,
+        seeds = [VOTE_RECEIPT_SEED, poll.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub vote_receipt: Account<'info, VoteReceipt>,
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    // Required only for token-weighted polls
+    #[account(mut)]
+    pub voter_token_account: Option<Account<'info, TokenAccount>>,
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub history: Option<Account<'info, BlinkHistory>>,
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    // Required only for token-weighted polls
+    #[account(mut, seeds = [POLL_VAULT_SEED, poll.key().as_ref()], bump)]
+    pub vote_vault: Option<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+}
+
+// Returns a token-weighted vote's locked tokens once the poll has closed
+#[derive(Accounts)]
+pub struct WithdrawVoteTokens<'info> {
+    #[account(seeds = [POLL_SEED, poll.blink.as_ref()], bump = poll.bump)]
+    pub poll: Account<'info, Poll>,
+    #[account(
+        mut,
+        close = voter,
+        has_one = poll,
+        has_one = voter,
+        seeds = [VOTE_RECEIPT_SEED, poll.key().as_ref(), voter.key().as_ref()],
+        bump = vote_receipt.bump
+    )]
+    pub vote_receipt: Account<'info, VoteReceipt>,
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    #[account(mut)]
+    pub voter_token_account: Account<'info, TokenAccount>,
+    #[account(mut, seeds = [POLL_VAULT_SEED, poll.key().as_ref()], bump)]
+    pub vote_vault: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct GetPollResults<'info> {
+    #[account(seeds = [POLL_SEED, poll.blink.as_ref()], bump = poll.bump)]
+    pub poll: Account<'info, Poll>,
+}
+
+pub fn open_poll(ctx: Context<OpenPoll>, closes_at: i64) -> Result<()> {
+    ctx.accounts.protocol_config.check_not_paused(ProtocolModule::Blinks)?;
+
+    let blink = &ctx.accounts.blink;
+    let poll = &mut ctx.accounts.poll;
+    let clock = Clock::get()?;
+
+    blink.check_not_frozen()?;
+
+    let options = match &blink.config {
+        BlinkConfig::Poll { options } => options.clone(),
+        _ => return Err(BlinkError::InvalidBlinkConfig.into()),
+    };
+    let vote_mint = ctx.accounts.vote_mint.as_ref().map(|mint| mint.key());
+    if vote_mint.is_some() && ctx.accounts.vote_vault.is_none() {
+        return Err(BlinkError::InvalidTokenAccount.into());
+    }
+
+    if closes_at <= clock.unix_timestamp {
+        return Err(BlinkError::InvalidPollCloseTime.into());
+    }
+
+    poll.blink = blink.key();
+    poll.vote_mint = vote_mint;
+    poll.tallies = vec![0; options.len()];
+    poll.options = options;
+    poll.voter_count = 0;
+    poll.opened_at = clock.unix_timestamp;
+    poll.closes_at = closes_at;
+    poll.bump = ctx.bumps.poll;
+
+    emit!(PollOpened {
+        poll: poll.key(),
+        blink: poll.blink,
+        owner: ctx.accounts.owner.key(),
+        vote_mint,
+        options: poll.options.clone(),
+        closes_at,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn cast_vote(ctx: Context<CastVote>, option: u8) -> Result<()> {
+    ctx.accounts.protocol_config.check_not_paused(ProtocolModule::Blinks)?;
+
+    let poll = &mut ctx.accounts.poll;
+    let vote_receipt = &mut ctx.accounts.vote_receipt;
+    let voter = &ctx.accounts.voter;
+    let clock = Clock::get()?;
+
+    ctx.accounts.blink.check_active(clock.unix_timestamp)?;
+    // Remaining accounts: the token gate proof for a gated blink
+    check_token_gate(&ctx.accounts.blink, voter.key, ctx.remaining_accounts)?;
+
+    if vote_receipt.voter != Pubkey::default() {
+        return Err(BlinkError::AlreadyVoted.into());
+    }
+
+    let weight = lock_vote_tokens(
+        poll,
+        voter,
+        ctx.accounts.voter_token_account.as_ref(),
+        ctx.accounts.vote_vault.as_ref(),
+        &ctx.accounts.token_program,
+    )?;
+    poll.record_vote(option, weight, clock.unix_timestamp)?;
+
+    vote_receipt.poll = poll.key();
+    vote_receipt.voter = voter.key();
+    vote_receipt.option = option;
+    vote_receipt.weight = weight;
+    vote_receipt.voted_at = clock.unix_timestamp;
+    vote_receipt.bump = ctx.bumps.vote_receipt;
+
+    record_blink_execution(
+        &mut ctx.accounts.blink,
+        ctx.accounts.history.as_mut(),
+        voter.key,
+        None,
+        0,
+    )?;
+
+    Ok(())
+}
+
+pub fn get_poll_results(ctx: Context<GetPollResults>) -> Result<PollResults> {
+    let poll = &ctx.accounts.poll;
+    let clock = Clock::get()?;
+
+    Ok(PollResults {
+        options: poll.options.clone(),
+        tallies: poll.tallies.clone(),
+        voter_count: poll.voter_count,
+        closed: poll.is_closed(clock.unix_timestamp),
+    })
+}
+
+// Token-weighted votes lock the voter's whole balance in the poll vault until the poll
+// closes, so the same tokens can't be moved to another wallet and counted again. Other
+// polls weigh every vote as one.
+pub fn lock_vote_tokens<'info>(
+    poll: &Account<'info, Poll>,
+    voter: &Signer<'info>,
+    voter_token_account: Option<&Account<'info, TokenAccount>>,
+    vote_vault: Option<&Account<'info, TokenAccount>>,
+    token_program: &Program<'info, Token>,
+) -> Result<u64> {
+    let vote_mint = match poll.vote_mint {
+        Some(vote_mint) => vote_mint,
+        None => return Ok(1),
+    };
+
+    let token_account = voter_token_account.ok_or(BlinkError::InvalidTokenAccount)?;
+    if token_account.mint != vote_mint {
+        return Err(BlinkError::InvalidMint.into());
+    }
+    if token_account.owner != voter.key() {
+        return Err(BlinkError::InvalidTokenAccount.into());
+    }
+    if token_account.amount == 0 {
+        return Err(BlinkError::InsufficientFunds.into());
+    }
+    let vote_vault = vote_vault.ok_or(BlinkError::InvalidTokenAccount)?;
+    if vote_vault.key() != find_poll_vault_address(&poll.key()).0 {
+        return Err(BlinkError::InvalidProgramAddress.into());
+    }
+
+    let weight = token_account.amount;
+    let cpi_accounts = Transfer {
+        from: token_account.to_account_info(),
+        to: vote_vault.to_account_info(),
+        authority: voter.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, weight)?;
+
+    Ok(weight)
+}
+
+pub fn withdraw_vote_tokens(ctx: Context<WithdrawVoteTokens>) -> Result<()> {
+    let poll = &ctx.accounts.poll;
+    let vote_receipt = &ctx.accounts.vote_receipt;
+    let clock = Clock::get()?;
+
+    if !poll.is_closed(clock.unix_timestamp) {
+        return Err(BlinkError::PollStillOpen.into());
+    }
+    if poll.vote_mint != Some(ctx.accounts.voter_token_account.mint) {
+        return Err(BlinkError::InvalidMint.into());
+    }
+
+    let seeds: &[&[&[u8]]] = &[&[POLL_SEED, poll.blink.as_ref(), &[poll.bump]]];
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.vote_vault.to_account_info(),
+        to: ctx.accounts.voter_token_account.to_account_info(),
+        authority: poll.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        seeds,
+    );
+    token::transfer(cpi_ctx, vote_receipt.weight)?;
+
+    Ok(())
+}
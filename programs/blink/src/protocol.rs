@@ -0,0 +1,337 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, TokenAccount, Transfer};
+use crate::errors::BlinkError;
+use crate::events::{
+    ProtocolAdminProposed, ProtocolAdminTransferred, ProtocolConfigUpdated, ProtocolFeeCollected,
+    ProtocolModulePauseSet,
+};
+use crate::program::BlinkProgram;
+
+pub const PROTOCOL_CONFIG_SEED: &[u8] = b"protocol_config";
+pub const BPS_DENOMINATOR: u16 = 10_000;
+// Highest fees the admin can configure for each module
+pub const MAX_DONATION_FEE_BPS: u16 = 500;
+pub const MAX_PAYMENT_FEE_BPS: u16 = 500;
+pub const MAX_SWAP_FEE_BPS: u16 = 100;
+pub const MAX_MINT_FEE_LAMPORTS: u64 = 100_000_000;
+
+// Modules the admin can pause in an emergency. Blinks covers creating, updating and
+// executing blinks, including their polls, gifts, vaults, attributes, hooks, gates,
+// handles and routed donations and payments. Deleting, withdrawing and reclaiming stay
+// available so owners can always recover funds and close their accounts.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ProtocolModule {
+    Blinks,
+    Nfts,
+    Collections,
+    Donations,
+    Payments,
+    Swaps,
+    Mints,
+}
+
+impl ProtocolModule {
+    fn flag(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum FeeSource {
+    Donation,
+    Payment,
+    Swap,
+    Mint,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, PartialEq, Eq, InitSpace)]
+pub struct ProtocolFees {
+    pub donation_bps: u16,
+    pub payment_bps: u16,
+    pub swap_bps: u16,
+    // Mints move no tokens, so they pay a flat fee in lamports instead
+    pub mint_fee_lamports: u64,
+}
+
+impl ProtocolFees {
+    pub fn validate(&self) -> Result<()> {
+        let all = [self.donation_bps, self.payment_bps, self.swap_bps];
+        if all.iter().any(|bps| *bps > BPS_DENOMINATOR) {
+            return Err(BlinkError::InvalidFeeBasisPoints.into());
+        }
+        if self.donation_bps > MAX_DONATION_FEE_BPS
+            || self.payment_bps > MAX_PAYMENT_FEE_BPS
+            || self.swap_bps > MAX_SWAP_FEE_BPS
+            || self.mint_fee_lamports > MAX_MINT_FEE_LAMPORTS
+        {
+            return Err(BlinkError::FeeTooHigh.into());
+        }
+
+        Ok(())
+    }
+
+    // Mints are charged in lamports and have no token fee
+    pub fn bps_for(&self, source: FeeSource) -> u16 {
+        match source {
+            FeeSource::Donation => self.donation_bps,
+            FeeSource::Payment => self.payment_bps,
+            FeeSource::Swap => self.swap_bps,
+            FeeSource::Mint => 0,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, PartialEq, Eq, InitSpace)]
+pub struct ProtocolFeatures {
+    // Donations and payments may be routed into a blink's vault
+    pub blink_routing: bool,
+    // Protocol fees are collected; when off every flow transfers the full amount
+    pub fee_collection: bool,
+}
+
+// Global settings shared by every module. Created once by the program's upgrade
+// authority, then managed by `admin`.
+#[account]
+#[derive(InitSpace)]
+pub struct ProtocolConfig {
+    pub admin: Pubkey,
+    // Set by the admin and cleared once accepted, so a mistyped key can't lock the protocol
+    pub pending_admin: Option<Pubkey>,
+    // Wallet whose token accounts receive protocol fees
+    pub treasury: Pubkey,
+    pub fees: ProtocolFees,
+    pub features: ProtocolFeatures,
+    // Bit set of paused `ProtocolModule`s
+    pub paused_modules: u8,
+    pub bump: u8,
+}
+
+impl ProtocolConfig {
+    // Fee owed on `amount`, rounded down; nothing while fee collection is switched off
+    pub fn token_fee(&self, source: FeeSource, amount: u64) -> u64 {
+        if !self.features.fee_collection {
+            return 0;
+        }
+        let bps = self.fees.bps_for(source) as u128;
+        (amount as u128 * bps / BPS_DENOMINATOR as u128) as u64
+    }
+
+    pub fn is_paused(&self, module: ProtocolModule) -> bool {
+        self.paused_modules & module.flag() != 0
+    }
+
+    pub fn check_not_paused(&self, module: ProtocolModule) -> Result<()> {
+        if self.is_paused(module) {
+            return Err(BlinkError::ModulePaused.into());
+        }
+
+        Ok(())
+    }
+
+    pub fn mint_fee(&self) -> u64 {
+        if !self.features.fee_collection {
+            return 0;
+        }
+        self.fees.mint_fee_lamports
+    }
+}
+
+pub fn find_protocol_config_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PROTOCOL_CONFIG_SEED], &crate::ID)
+}
+
+#[derive(Accounts)]
+pub struct InitializeProtocolConfig<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ProtocolConfig::INIT_SPACE,
+        seeds = [PROTOCOL_CONFIG_SEED],
+        bump
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, BlinkProgram>,
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(authority.key())
+            @ BlinkError::Unauthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateProtocolConfig<'info> {
+    #[account(
+        mut,
+        seeds = [PROTOCOL_CONFIG_SEED],
+        bump = protocol_config.bump,
+        has_one = admin @ BlinkError::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptProtocolAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [PROTOCOL_CONFIG_SEED],
+        bump = protocol_config.bump,
+        constraint = protocol_config.pending_admin == Some(new_admin.key())
+            @ BlinkError::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    pub new_admin: Signer<'info>,
+}
+
+pub fn initialize_protocol_config(
+    ctx: Context<InitializeProtocolConfig>,
+    admin: Pubkey,
+    treasury: Pubkey,
+    fees: ProtocolFees,
+    features: ProtocolFeatures,
+) -> Result<()> {
+    let protocol_config = &mut ctx.accounts.protocol_config;
+
+    fees.validate()?;
+
+    protocol_config.admin = admin;
+    protocol_config.pending_admin = None;
+    protocol_config.treasury = treasury;
+    protocol_config.fees = fees;
+    protocol_config.features = features;
+    protocol_config.paused_modules = 0;
+    protocol_config.bump = ctx.bumps.protocol_config;
+
+    emit!(ProtocolConfigUpdated {
+        admin,
+        treasury,
+        fees: protocol_config.fees.clone(),
+        features: protocol_config.features.clone(),
+    });
+
+    Ok(())
+}
+
+pub fn update_protocol_config(
+    ctx: Context<UpdateProtocolConfig>,
+    treasury: Pubkey,
+    fees: ProtocolFees,
+    features: ProtocolFeatures,
+) -> Result<()> {
+    let protocol_config = &mut ctx.accounts.protocol_config;
+
+    fees.validate()?;
+
+    protocol_config.treasury = treasury;
+    protocol_config.fees = fees;
+    protocol_config.features = features;
+
+    emit!(ProtocolConfigUpdated {
+        admin: protocol_config.admin,
+        treasury,
+        fees: protocol_config.fees.clone(),
+        features: protocol_config.features.clone(),
+    });
+
+    Ok(())
+}
+
+pub fn set_module_paused(
+    ctx: Context<UpdateProtocolConfig>,
+    module: ProtocolModule,
+    paused: bool,
+) -> Result<()> {
+    let protocol_config = &mut ctx.accounts.protocol_config;
+
+    if paused {
+        protocol_config.paused_modules |= module.flag();
+    } else {
+        protocol_config.paused_modules &= !module.flag();
+    }
+
+    emit!(ProtocolModulePauseSet {
+        module,
+        paused,
+        admin: ctx.accounts.admin.key(),
+    });
+
+    Ok(())
+}
+
+// Passing None cancels a pending handover
+pub fn propose_protocol_admin(
+    ctx: Context<UpdateProtocolConfig>,
+    new_admin: Option<Pubkey>,
+) -> Result<()> {
+    ctx.accounts.protocol_config.pending_admin = new_admin;
+
+    emit!(ProtocolAdminProposed {
+        admin: ctx.accounts.admin.key(),
+        pending_admin: new_admin,
+    });
+
+    Ok(())
+}
+
+pub fn accept_protocol_admin(ctx: Context<AcceptProtocolAdmin>) -> Result<()> {
+    let protocol_config = &mut ctx.accounts.protocol_config;
+
+    let previous_admin = protocol_config.admin;
+    protocol_config.admin = ctx.accounts.new_admin.key();
+    protocol_config.pending_admin = None;
+
+    emit!(ProtocolAdminTransferred {
+        previous_admin,
+        new_admin: protocol_config.admin,
+    });
+
+    Ok(())
+}
+
+// Moves the protocol fee on `amount` from `from` to the treasury's token account for the
+// same mint and returns it. The treasury account is only required when a fee is owed.
+pub(crate) fn collect_token_fee<'info>(
+    config: &ProtocolConfig,
+    source: FeeSource,
+    amount: u64,
+    from: &Account<'info, TokenAccount>,
+    authority: &AccountInfo<'info>,
+    treasury_token_account: Option<&Account<'info, TokenAccount>>,
+    token_program: &AccountInfo<'info>,
+) -> Result<u64> {
+    let fee = config.token_fee(source, amount);
+    if fee == 0 {
+        return Ok(0);
+    }
+
+    let treasury_token_account =
+        treasury_token_account.ok_or(BlinkError::InvalidTreasuryAccount)?;
+    if treasury_token_account.owner != config.treasury
+        || treasury_token_account.mint != from.mint
+    {
+        return Err(BlinkError::InvalidTreasuryAccount.into());
+    }
+
+    let cpi_accounts = Transfer {
+        from: from.to_account_info(),
+        to: treasury_token_account.to_account_info(),
+        authority: authority.clone(),
+    };
+    let cpi_ctx = CpiContext::new(token_program.clone(), cpi_accounts);
+    token::transfer(cpi_ctx, fee)?;
+
+    emit!(ProtocolFeeCollected {
+        source,
+        payer: authority.key(),
+        treasury: config.treasury,
+        mint: Some(from.mint),
+        amount,
+        fee,
+    });
+
+    Ok(fee)
+}
@@ -1,7 +1,13 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::errors::BlinkError;
+use crate::events::{SwapCreated, SwapExecuted};
+use crate::protocol::{
+    collect_token_fee, FeeSource, ProtocolConfig, ProtocolModule, PROTOCOL_CONFIG_SEED,
+};
 
 #[account]
+#[derive(InitSpace)]
 pub struct Swap {
     pub owner: Pubkey,
     pub token_a: Pubkey,
@@ -9,13 +15,15 @@ pub struct Swap {
     pub amount_a: u64,
     pub amount_b: u64,
     pub fee: u64,
+    // Protocol fee taken out of `amount_a` on execution
+    pub protocol_fee: u64,
     pub created_at: i64,
     pub executed_at: Option<i64>,
 }
 
 #[derive(Accounts)]
 pub struct CreateSwap<'info> {
-    #[account(init, payer = owner, space = 8 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8)]
+    #[account(init, payer = owner, space = 8 + Swap::INIT_SPACE)]
     pub swap: Account<'info, Swap>,
     #[account(mut)]
     pub owner: Signer<'info>,
@@ -23,6 +31,8 @@ pub struct CreateSwap<'info> {
     pub token_b: Account<'info, TokenAccount>,
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
 }
 
 #[derive(Accounts)]
@@ -39,6 +49,11 @@ pub struct ExecuteSwap<'info> {
     #[account(mut)]
     pub token_b_destination: Account<'info, TokenAccount>,
     pub token_program: Program<'info, Token>,
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    // Token A account of the treasury; required when a protocol fee is owed
+    #[account(mut)]
+    pub treasury_token_account: Option<Account<'info, TokenAccount>>,
 }
 
 pub fn create_swap(
@@ -47,6 +62,8 @@ pub fn create_swap(
     amount_b: u64,
     fee: u64,
 ) -> Result<()> {
+    ctx.accounts.protocol_config.check_not_paused(ProtocolModule::Swaps)?;
+
     let swap = &mut ctx.accounts.swap;
     let clock = Clock::get()?;
 
@@ -56,17 +73,41 @@ pub fn create_swap(
     swap.amount_a = amount_a;
     swap.amount_b = amount_b;
     swap.fee = fee;
+    swap.protocol_fee = 0;
     swap.created_at = clock.unix_timestamp;
     swap.executed_at = None;
 
+    emit!(SwapCreated {
+        swap: swap.key(),
+        owner: swap.owner,
+        token_a: swap.token_a,
+        token_b: swap.token_b,
+        amount_a,
+        amount_b,
+        fee,
+        timestamp: clock.unix_timestamp,
+    });
+
     Ok(())
 }
 
 pub fn execute_swap(ctx: Context<ExecuteSwap>) -> Result<()> {
+    ctx.accounts.protocol_config.check_not_paused(ProtocolModule::Swaps)?;
+
     let swap = &mut ctx.accounts.swap;
     let clock = Clock::get()?;
 
-    // Transfer token A from source to destination
+    let protocol_fee = collect_token_fee(
+        &ctx.accounts.protocol_config,
+        FeeSource::Swap,
+        swap.amount_a,
+        &ctx.accounts.token_a_source,
+        &ctx.accounts.owner.to_account_info(),
+        ctx.accounts.treasury_token_account.as_ref(),
+        &ctx.accounts.token_program.to_account_info(),
+    )?;
+
+    // Transfer the rest of token A from source to destination
     let cpi_accounts_a = Transfer {
         from: ctx.accounts.token_a_source.to_account_info(),
         to: ctx.accounts.token_a_destination.to_account_info(),
@@ -74,7 +115,7 @@ pub fn execute_swap(ctx: Context<ExecuteSwap>) -> Result<()> {
     };
     let cpi_program_a = ctx.accounts.token_program.to_account_info();
     let cpi_ctx_a = CpiContext::new(cpi_program_a, cpi_accounts_a);
-    token::transfer(cpi_ctx_a, swap.amount_a)?;
+    token::transfer(cpi_ctx_a, swap.amount_a - protocol_fee)?;
 
     // Transfer token B from source to destination
     let cpi_accounts_b = Transfer {
@@ -87,8 +128,20 @@ pub fn execute_swap(ctx: Context<ExecuteSwap>) -> Result<()> {
     token::transfer(cpi_ctx_b, swap.amount_b)?;
 
     // Update swap state
+    swap.protocol_fee = protocol_fee;
     swap.executed_at = Some(clock.unix_timestamp);
 
+    emit!(SwapExecuted {
+        swap: swap.key(),
+        owner: swap.owner,
+        mint_a: ctx.accounts.token_a_source.mint,
+        mint_b: ctx.accounts.token_b_source.mint,
+        amount_a: swap.amount_a,
+        amount_b: swap.amount_b,
+        protocol_fee,
+        timestamp: clock.unix_timestamp,
+    });
+
     Ok(())
 }
 
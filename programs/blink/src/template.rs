@@ -0,0 +1,259 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+use crate::blink::{
+    register_blink, Blink, BlinkConfig, BlinkType, OwnerRegistry, BLINK_SEED, MAX_DESCRIPTION_LEN,
+    MAX_IMAGE_URL_LEN, MAX_NAME_LEN, OWNER_REGISTRY_SEED,
+};
+use crate::errors::BlinkError;
+use crate::events::{TemplateClosed, TemplateCreated, TemplateFeeSet};
+use crate::protocol::{ProtocolConfig, ProtocolModule, PROTOCOL_CONFIG_SEED};
+
+pub const TEMPLATE_SEED: &[u8] = b"template";
+
+// Reusable metadata and config that anyone can instantiate as a new blink
+#[account]
+#[derive(InitSpace)]
+pub struct BlinkTemplate {
+    pub author: Pubkey,
+    pub seed: u64,
+    #[max_len(MAX_NAME_LEN)]
+    pub name: String,
+    #[max_len(MAX_DESCRIPTION_LEN)]
+    pub description: String,
+    #[max_len(MAX_IMAGE_URL_LEN)]
+    pub image_url: String,
+    pub blink_type: BlinkType,
+    pub config: BlinkConfig,
+    // Paid to the author each time someone else creates a blink from the template
+    pub fee_lamports: u64,
+    pub use_count: u64,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl BlinkTemplate {
+    pub fn space_for(
+        name: &str,
+        description: &str,
+        image_url: &str,
+        config: &BlinkConfig,
+    ) -> usize {
+        8 + BlinkTemplate::INIT_SPACE
+            - MAX_NAME_LEN.saturating_sub(name.len())
+            - MAX_DESCRIPTION_LEN.saturating_sub(description.len())
+            - MAX_IMAGE_URL_LEN.saturating_sub(image_url.len())
+            - BlinkConfig::INIT_SPACE.saturating_sub(config.space())
+    }
+}
+
+pub fn find_template_address(author: &Pubkey, seed: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[TEMPLATE_SEED, author.as_ref(), &seed.to_le_bytes()],
+        &crate::ID,
+    )
+}
+
+#[derive(Accounts)]
+#[instruction(
+    seed: u64,
+    name: String,
+    description: String,
+    image_url: String,
+    config: BlinkConfig
+)]
+pub struct CreateBlinkTemplate<'info> {
+    #[account(
+        init,
+        payer = author,
+        space = BlinkTemplate::space_for(&name, &description, &image_url, &config),
+        seeds = [TEMPLATE_SEED, author.key().as_ref(), &seed.to_le_bytes()],
+        bump
+    )]
+    pub template: Account<'info, BlinkTemplate>,
+    #[account(mut)]
+    pub author: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+#[derive(Accounts)]
+pub struct SetTemplateFee<'info> {
+    #[account(mut, has_one = author)]
+    pub template: Account<'info, BlinkTemplate>,
+    pub author: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseBlinkTemplate<'info> {
+    #[account(mut, has_one = author, close = author)]
+    pub template: Account<'info, BlinkTemplate>,
+    #[account(mut)]
+    pub author: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(name: Option<String>, config: Option<BlinkConfig>)]
+pub struct CreateBlinkFromTemplate<'info> {
+    #[account(mut)]
+    pub template: Account<'info, BlinkTemplate>,
+    /// CHECK: Receives the template fee; must be the template author
+    #[account(mut, address = template.author @ BlinkError::Unauthorized)]
+    pub author: AccountInfo<'info>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + OwnerRegistry::INIT_SPACE,
+        seeds = [OWNER_REGISTRY_SEED, owner.key().as_ref()],
+        bump
+    )]
+    pub registry: Account<'info, OwnerRegistry>,
+    #[account(
+        init,
+        payer = owner,
+        space = Blink::space_for(
+            name.as_deref().unwrap_or(&template.name),
+            &template.description,
+            &template.image_url,
+            config.as_ref().unwrap_or(&template.config)
+        ),
+        seeds = [BLINK_SEED, registry.key().as_ref(), &registry.blink_count.to_le_bytes()],
+        bump
+    )]
+    pub blink: Account<'info, Blink>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+pub fn create_blink_template(
+    ctx: Context<CreateBlinkTemplate>,
+    seed: u64,
+    name: String,
+    description: String,
+    image_url: String,
+    config: BlinkConfig,
+    fee_lamports: u64,
+) -> Result<()> {
+    ctx.accounts.protocol_config.check_not_paused(ProtocolModule::Blinks)?;
+
+    let template = &mut ctx.accounts.template;
+    let clock = Clock::get()?;
+
+    Blink::validate_metadata(&name, &description, &image_url)?;
+    config.validate(clock.unix_timestamp)?;
+
+    template.author = ctx.accounts.author.key();
+    template.seed = seed;
+    template.name = name;
+    template.description = description;
+    template.image_url = image_url;
+    template.blink_type = config.blink_type();
+    template.config = config;
+    template.fee_lamports = fee_lamports;
+    template.use_count = 0;
+    template.created_at = clock.unix_timestamp;
+    template.bump = ctx.bumps.template;
+
+    emit!(TemplateCreated {
+        template: template.key(),
+        author: template.author,
+        seed,
+        name: template.name.clone(),
+        blink_type: template.blink_type.clone(),
+        config: template.config.clone(),
+        fee_lamports,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn set_template_fee(ctx: Context<SetTemplateFee>, fee_lamports: u64) -> Result<()> {
+    ctx.accounts.template.fee_lamports = fee_lamports;
+
+    emit!(TemplateFeeSet {
+        template: ctx.accounts.template.key(),
+        author: ctx.accounts.author.key(),
+        fee_lamports,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn close_blink_template(ctx: Context<CloseBlinkTemplate>) -> Result<()> {
+    // The account is closed by its constraints. Blinks created from the template keep
+    // their own copy and its address as their origin.
+    emit!(TemplateClosed {
+        template: ctx.accounts.template.key(),
+        author: ctx.accounts.author.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// `name` and `config` override the template's values; the config must keep the
+// template's blink type. `max_fee_lamports` caps the fee so the author can't raise it
+// after the transaction was signed.
+pub fn create_blink_from_template(
+    ctx: Context<CreateBlinkFromTemplate>,
+    name: Option<String>,
+    config: Option<BlinkConfig>,
+    max_fee_lamports: u64,
+) -> Result<()> {
+    ctx.accounts.protocol_config.check_not_paused(ProtocolModule::Blinks)?;
+
+    let template = &mut ctx.accounts.template;
+    let blink = &mut ctx.accounts.blink;
+    let owner = &ctx.accounts.owner;
+    let clock = Clock::get()?;
+
+    let name = name.unwrap_or_else(|| template.name.clone());
+    let config = config.unwrap_or_else(|| template.config.clone());
+
+    Blink::validate_metadata(&name, &template.description, &template.image_url)?;
+    if config.blink_type() != template.blink_type {
+        return Err(BlinkError::InvalidBlinkType.into());
+    }
+    config.validate(clock.unix_timestamp)?;
+
+    // Authors don't pay themselves
+    if template.fee_lamports > 0 && owner.key() != template.author {
+        if template.fee_lamports > max_fee_lamports {
+            return Err(BlinkError::FeeTooHigh.into());
+        }
+
+        let cpi_accounts = Transfer {
+            from: owner.to_account_info(),
+            to: ctx.accounts.author.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.system_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        system_program::transfer(cpi_ctx, template.fee_lamports)?;
+    }
+
+    blink.name = name;
+    blink.description = template.description.clone();
+    blink.image_url = template.image_url.clone();
+    blink.blink_type = template.blink_type.clone();
+    blink.config = config;
+    blink.template = Some(template.key());
+
+    template.use_count = template
+        .use_count
+        .checked_add(1)
+        .ok_or(BlinkError::MathOverflow)?;
+
+    register_blink(
+        &mut ctx.accounts.registry,
+        ctx.bumps.registry,
+        blink,
+        ctx.bumps.blink,
+        owner.key,
+        clock.unix_timestamp,
+    )
+}
@@ -0,0 +1,112 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::system_program::{self, Allocate, Assign, Transfer};
+use crate::errors::BlinkError;
+
+pub const ALLOWED_URI_SCHEMES: &[&str] = &["https://", "ipfs://", "ar://"];
+
+// Empty URIs are allowed; anything else must use a content-addressed or TLS scheme
+pub fn validate_uri(uri: &str) -> Result<()> {
+    if !uri.is_empty() && !ALLOWED_URI_SCHEMES.iter().any(|scheme| uri.starts_with(scheme)) {
+        return Err(BlinkError::InvalidUriScheme.into());
+    }
+
+    Ok(())
+}
+
+// Checks `data` against a stored SHA-256 content hash
+pub fn verify_content_hash(expected: Option<[u8; 32]>, data: &[u8]) -> Result<()> {
+    let expected = expected.ok_or(BlinkError::ContentHashNotSet)?;
+    if hash(data).to_bytes() != expected {
+        return Err(BlinkError::ContentHashMismatch.into());
+    }
+
+    Ok(())
+}
+
+// Creates a program-owned PDA the way Anchor's `init` does. `create_account` fails once
+// anyone has sent lamports to the address, so this tops up rent, allocates and assigns.
+pub fn create_pda_account<'info>(
+    account: &AccountInfo<'info>,
+    space: usize,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let required = Rent::get()?.minimum_balance(space);
+    let current = account.lamports();
+    if required > current {
+        let cpi_accounts = Transfer {
+            from: payer.clone(),
+            to: account.clone(),
+        };
+        let cpi_ctx = CpiContext::new(system_program.clone(), cpi_accounts);
+        system_program::transfer(cpi_ctx, required - current)?;
+    }
+
+    let cpi_accounts = Allocate {
+        account_to_allocate: account.clone(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(system_program.clone(), cpi_accounts, signer_seeds);
+    system_program::allocate(cpi_ctx, space as u64)?;
+
+    let cpi_accounts = Assign {
+        account_to_assign: account.clone(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(system_program.clone(), cpi_accounts, signer_seeds);
+    system_program::assign(cpi_ctx, &crate::ID)?;
+
+    Ok(())
+}
+
+// Closes a program-owned account the way Anchor's `close` constraint does, moving all
+// of its lamports to `refund_to`
+pub fn close_account<'info>(
+    account: &AccountInfo<'info>,
+    refund_to: &AccountInfo<'info>,
+) -> Result<()> {
+    let lamports = account.lamports();
+    **account.try_borrow_mut_lamports()? -= lamports;
+    **refund_to.try_borrow_mut_lamports()? += lamports;
+
+    account.assign(&system_program::ID);
+    account.realloc(0, false)?;
+
+    Ok(())
+}
+
+// Reallocates a program-owned account to `new_len`, charging `payer` for any extra rent
+// and returning rent that is no longer needed to `refund_to`.
+pub fn resize_account<'info>(
+    account: &AccountInfo<'info>,
+    new_len: usize,
+    payer: &AccountInfo<'info>,
+    refund_to: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    if account.data_len() == new_len {
+        return Ok(());
+    }
+
+    let required = Rent::get()?.minimum_balance(new_len);
+    let current = account.lamports();
+
+    if required > current {
+        // Top up rent from the payer
+        let cpi_accounts = Transfer {
+            from: payer.clone(),
+            to: account.clone(),
+        };
+        let cpi_ctx = CpiContext::new(system_program.clone(), cpi_accounts);
+        system_program::transfer(cpi_ctx, required - current)?;
+    } else if current > required {
+        // The program owns the account, so excess rent can be moved directly
+        let refund = current - required;
+        **account.try_borrow_mut_lamports()? -= refund;
+        **refund_to.try_borrow_mut_lamports()? += refund;
+    }
+
+    account.realloc(new_len, false)?;
+
+    Ok(())
+}
@@ -0,0 +1,191 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, CloseAccount, Mint, Token, TokenAccount, Transfer};
+use crate::blink::{Blink, BLINK_SEED, PERMISSION_WITHDRAW};
+use crate::errors::BlinkError;
+use crate::events::{BlinkVaultClosed, BlinkVaultOpened, BlinkVaultWithdrawn};
+use crate::protocol::{ProtocolConfig, ProtocolModule, PROTOCOL_CONFIG_SEED};
+
+pub const BLINK_VAULT_SEED: &[u8] = b"blink_vault";
+
+pub fn find_blink_vault_address(blink: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[BLINK_VAULT_SEED, blink.as_ref(), mint.as_ref()],
+        &crate::ID,
+    )
+}
+
+// Donations and payments may only be routed to a blink through its own vault for that mint
+pub fn check_blink_vault(blink: &Pubkey, vault: &Account<TokenAccount>) -> Result<()> {
+    let (expected, _) = find_blink_vault_address(blink, &vault.mint);
+    if vault.key() != expected || vault.owner != *blink {
+        return Err(BlinkError::InvalidTokenAccount.into());
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct OpenBlinkVault<'info> {
+    #[account(mut)]
+    pub blink: Account<'info, Blink>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [BLINK_VAULT_SEED, blink.key().as_ref(), mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = blink
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    pub mint: Account<'info, Mint>,
+    // The owner or an editor allowed to withdraw, since an open vault blocks deletion
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawFromBlinkVault<'info> {
+    pub blink: Account<'info, Blink>,
+    #[account(
+        mut,
+        seeds = [BLINK_VAULT_SEED, blink.key().as_ref(), vault.mint.as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = vault.mint)]
+    pub destination: Account<'info, TokenAccount>,
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CloseBlinkVault<'info> {
+    #[account(mut)]
+    pub blink: Account<'info, Blink>,
+    #[account(
+        mut,
+        seeds = [BLINK_VAULT_SEED, blink.key().as_ref(), vault.mint.as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    // Receives whatever balance is left in the vault
+    #[account(mut, token::mint = vault.mint)]
+    pub destination: Account<'info, TokenAccount>,
+    pub authority: Signer<'info>,
+    /// CHECK: Receives the vault's rent; must be the blink owner
+    #[account(mut, address = blink.owner @ BlinkError::Unauthorized)]
+    pub owner: AccountInfo<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn open_blink_vault(ctx: Context<OpenBlinkVault>) -> Result<()> {
+    ctx.accounts.protocol_config.check_not_paused(ProtocolModule::Blinks)?;
+
+    // The vault token account is created by the account constraints
+    let blink = &mut ctx.accounts.blink;
+    blink.check_permission(ctx.accounts.payer.key, PERMISSION_WITHDRAW)?;
+    blink.open_vaults = blink
+        .open_vaults
+        .checked_add(1)
+        .ok_or(BlinkError::MathOverflow)?;
+
+    emit!(BlinkVaultOpened {
+        blink: blink.key(),
+        vault: ctx.accounts.vault.key(),
+        mint: ctx.accounts.mint.key(),
+        authority: ctx.accounts.payer.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn withdraw_from_blink_vault(ctx: Context<WithdrawFromBlinkVault>, amount: u64) -> Result<()> {
+    let blink = &ctx.accounts.blink;
+
+    blink.check_permission(ctx.accounts.authority.key, PERMISSION_WITHDRAW)?;
+
+    if amount > ctx.accounts.vault.amount {
+        return Err(BlinkError::InsufficientFunds.into());
+    }
+
+    let index = blink.index.to_le_bytes();
+    let signer_seeds: &[&[&[u8]]] =
+        &[&[BLINK_SEED, blink.registry.as_ref(), &index, &[blink.bump]]];
+
+    // Transfer tokens from the blink vault to the withdrawer's destination
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.vault.to_account_info(),
+        to: ctx.accounts.destination.to_account_info(),
+        authority: blink.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+    token::transfer(cpi_ctx, amount)?;
+
+    emit!(BlinkVaultWithdrawn {
+        blink: blink.key(),
+        vault: ctx.accounts.vault.key(),
+        authority: ctx.accounts.authority.key(),
+        destination: ctx.accounts.destination.key(),
+        amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Drains the vault into `destination` and closes it; a blink's vaults must all be closed
+// before the blink can be deleted
+pub fn close_blink_vault(ctx: Context<CloseBlinkVault>) -> Result<()> {
+    let blink = &ctx.accounts.blink;
+
+    // The owner can still close vaults on a frozen blink, so it can go on to delete the
+    // blink and recover its rent
+    if *ctx.accounts.authority.key != blink.owner {
+        blink.check_permission(ctx.accounts.authority.key, PERMISSION_WITHDRAW)?;
+    }
+
+    let index = blink.index.to_le_bytes();
+    let signer_seeds: &[&[&[u8]]] =
+        &[&[BLINK_SEED, blink.registry.as_ref(), &index, &[blink.bump]]];
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+
+    let remaining = ctx.accounts.vault.amount;
+    if remaining > 0 {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+            authority: blink.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts, signer_seeds);
+        token::transfer(cpi_ctx, remaining)?;
+    }
+
+    let cpi_accounts = CloseAccount {
+        account: ctx.accounts.vault.to_account_info(),
+        destination: ctx.accounts.owner.to_account_info(),
+        authority: blink.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+    token::close_account(cpi_ctx)?;
+
+    let blink = &mut ctx.accounts.blink;
+    blink.open_vaults = blink.open_vaults.saturating_sub(1);
+
+    emit!(BlinkVaultClosed {
+        blink: blink.key(),
+        vault: ctx.accounts.vault.key(),
+        authority: ctx.accounts.authority.key(),
+        destination: ctx.accounts.destination.key(),
+        amount: remaining,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
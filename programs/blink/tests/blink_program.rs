@@ -0,0 +1,3953 @@
+use anchor_lang::{
+    AccountSerialize, AnchorDeserialize, Discriminator, InstructionData, Space, ToAccountMetas,
+};
+use base64::prelude::{Engine, BASE64_STANDARD};
+use bark_blinks::attributes::{find_blink_attributes_address, BlinkAttributes};
+use bark_blinks::batch::{BlinkInput, BlinkUpdate, BATCH_ITEM_LABEL};
+use bark_blinks::blink::{
+    find_blink_address, find_owner_registry_address, Blink, BlinkConfig, BlinkRarity, BlinkType,
+    OwnerRegistry, MAX_TRANSFERRED_BLINKS, PERMISSION_PAUSE, XP_LEVEL_BASE, XP_PER_EXECUTION,
+};
+use bark_blinks::collection::Collection;
+use bark_blinks::donations::Donation;
+use bark_blinks::errors::BlinkError;
+use bark_blinks::events::{
+    BlinkCreated, BlinkEditorSet, BlinkExecuted, BlinkGateSet, BlinkHookSet, BlinkOwnershipProposed,
+    BlinkOwnershipTransferred, BlinkPauseSet, BlinkScheduleSet, BlinkVaultClosed, BlinkVaultOpened,
+    BlinkVaultWithdrawn, DonationCreated, GiftFunded, GiftReclaimed, HandleRegistered,
+    HandleReleased, HandleTransferred, PollOpened, ProtocolAdminProposed, ProtocolAdminTransferred,
+    ProtocolConfigUpdated, TemplateClosed, TemplateCreated, TemplateFeeSet,
+};
+use bark_blinks::execute::ExecuteBlinkArgs;
+use bark_blinks::gate::TokenGate;
+use bark_blinks::gift::{GiftEscrow, GiftStatus, GIFT_ESCROW_SEED, GIFT_VAULT_SEED};
+use bark_blinks::handle::{find_handle_address, BlinkHandle};
+use bark_blinks::history::{BlinkHistory, ExecutionRecord, HISTORY_CAPACITY};
+use bark_blinks::hooks::{find_hook_allowlist_address, BlinkHook, HookFailureMode};
+use bark_blinks::moderation::{find_moderators_address, ModerationStatus, Moderators};
+use bark_blinks::nft::NFT;
+use bark_blinks::payments::{Payment, PaymentStatus};
+use bark_blinks::poll::{
+    find_poll_address, find_poll_vault_address, find_vote_receipt_address, Poll, VoteReceipt,
+    POLL_SEED, VOTE_RECEIPT_SEED,
+};
+use bark_blinks::protocol::{
+    find_protocol_config_address, ProtocolConfig, ProtocolFeatures, ProtocolFees, ProtocolModule,
+    BPS_DENOMINATOR, MAX_DONATION_FEE_BPS,
+};
+use bark_blinks::swap::Swap;
+use bark_blinks::template::{find_template_address, BlinkTemplate};
+use bark_blinks::vault::find_blink_vault_address;
+use solana_program_runtime::invoke_context::BuiltinFunctionWithContext;
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    account_info::AccountInfo,
+    bpf_loader_upgradeable,
+    clock::Clock,
+    entrypoint::ProgramResult,
+    hash::hash,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction, system_program, sysvar,
+    transaction::{Transaction, TransactionError},
+};
+
+mod common;
+
+use common::{
+    assert_blink_error, create_mint_account, create_token_account, fetch, get_account, process,
+    process_with_logs, program_test, program_test_with_admin, program_test_with_upgrade_authority,
+    token_balance, try_process, try_process_with_logs,
+};
+
+// `emit!` logs each event as base64 after "Program data: "
+fn events<T: AnchorDeserialize + Discriminator>(logs: &[String]) -> Vec<T> {
+    logs.iter()
+        .filter_map(|log| log.strip_prefix("Program data: "))
+        .filter_map(|data| BASE64_STANDARD.decode(data).ok())
+        .filter(|data| data.starts_with(&T::DISCRIMINATOR))
+        .map(|data| T::deserialize(&mut &data[8..]).unwrap())
+        .collect()
+}
+
+fn metadata_address(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"metadata", mpl_token_metadata::ID.as_ref(), mint.as_ref()],
+        &mpl_token_metadata::ID,
+    )
+    .0
+}
+
+async fn create_blink(
+    context: &mut ProgramTestContext,
+    blink_type: BlinkType,
+    config: BlinkConfig,
+) -> Pubkey {
+    let owner = context.payer.pubkey();
+    let (registry, _) = find_owner_registry_address(&owner);
+    let index = match context.banks_client.get_account(registry).await.unwrap() {
+        Some(_) => fetch::<OwnerRegistry>(context, registry).await.blink_count,
+        None => 0,
+    };
+    let (blink, _) = find_blink_address(&registry, index);
+
+    let create_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::CreateBlink {
+            registry,
+            blink,
+            owner,
+            system_program: system_program::id(),
+            protocol_config: find_protocol_config_address().0,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::CreateBlink {
+            name: "Test Blink".to_string(),
+            description: "This is a test blink".to_string(),
+            blink_type,
+            image_url: "https://example.com/image.png".to_string(),
+            config,
+        }
+        .data(),
+    };
+    process(context, &[create_ix], &[]).await;
+    blink
+}
+
+// Funds a gift blink from `owner_token_account`, returning its escrow and vault
+async fn fund_gift(
+    context: &mut ProgramTestContext,
+    blink: Pubkey,
+    mint: &Keypair,
+    owner_token_account: Pubkey,
+) -> (Pubkey, Pubkey) {
+    let (escrow, _) =
+        Pubkey::find_program_address(&[GIFT_ESCROW_SEED, blink.as_ref()], &bark_blinks::id());
+    let (vault, _) =
+        Pubkey::find_program_address(&[GIFT_VAULT_SEED, blink.as_ref()], &bark_blinks::id());
+
+    let fund_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::FundGift {
+            blink,
+            escrow,
+            vault,
+            mint: mint.pubkey(),
+            owner_token_account,
+            owner: context.payer.pubkey(),
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            rent: sysvar::rent::id(),
+            protocol_config: find_protocol_config_address().0,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::FundGift {}.data(),
+    };
+    let logs = process_with_logs(context, &[fund_ix], &[]).await;
+
+    let funded = events::<GiftFunded>(&logs);
+    assert_eq!(funded.len(), 1);
+    assert_eq!(funded[0].escrow, escrow);
+    assert_eq!(funded[0].blink, blink);
+    assert_eq!(funded[0].mint, mint.pubkey());
+
+    (escrow, vault)
+}
+
+// The moderator list is normally managed by the upgrade authority, which program-test
+// doesn't provide, so it is seeded directly.
+fn program_test_with_moderator(moderator: Pubkey) -> ProgramTest {
+    let (moderators, bump) = find_moderators_address();
+    let mut data = Vec::new();
+    Moderators {
+        moderators: vec![moderator],
+        bump,
+    }
+    .try_serialize(&mut data)
+    .unwrap();
+    let mut program_test = program_test();
+    program_test.add_account(
+        moderators,
+        Account {
+            lamports: 1_000_000_000,
+            data,
+            owner: bark_blinks::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    program_test
+}
+
+#[tokio::test]
+async fn blink_lifecycle() {
+    let mut context = program_test().start_with_context().await;
+    let owner = context.payer.pubkey();
+    let (registry, _) = find_owner_registry_address(&owner);
+    let (blink, _) = find_blink_address(&registry, 0);
+
+    let create_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::CreateBlink {
+            registry,
+            blink,
+            owner,
+            system_program: system_program::id(),
+            protocol_config: find_protocol_config_address().0,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::CreateBlink {
+            name: "Test Blink".to_string(),
+            description: "This is a test blink".to_string(),
+            blink_type: BlinkType::Standard,
+            image_url: "https://example.com/image.png".to_string(),
+            config: BlinkConfig::Standard,
+        }
+        .data(),
+    };
+    process(&mut context, &[create_ix], &[]).await;
+
+    let account: Blink = fetch(&mut context, blink).await;
+    assert_eq!(account.owner, owner);
+    assert_eq!(account.registry, registry);
+    assert_eq!(account.index, 0);
+    assert_eq!(account.name, "Test Blink");
+    assert!(account.blink_type == BlinkType::Standard);
+
+    let registry_account: OwnerRegistry = fetch(&mut context, registry).await;
+    assert_eq!(registry_account.blink_count, 1);
+    assert_eq!(registry_account.blink_addresses(&registry), vec![blink]);
+
+    // The account is sized to its contents rather than the maximum
+    let raw = context.banks_client.get_account(blink).await.unwrap().unwrap();
+    assert_eq!(raw.data.len(), account.space());
+    assert!(raw.data.len() < 8 + Blink::INIT_SPACE);
+
+    let update_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::UpdateBlink {
+            blink,
+            authority: owner,
+            owner,
+            system_program: system_program::id(),
+            protocol_config: find_protocol_config_address().0,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::UpdateBlink {
+            name: "Updated Blink".to_string(),
+            description: "This is an updated blink".to_string(),
+            image_url: "https://example.com/new-image.png".to_string(),
+            config: None,
+        }
+        .data(),
+    };
+    process(&mut context, &[update_ix], &[]).await;
+
+    let account: Blink = fetch(&mut context, blink).await;
+    assert_eq!(account.name, "Updated Blink");
+    assert_eq!(account.image_url, "https://example.com/new-image.png");
+
+    let grown = context.banks_client.get_account(blink).await.unwrap().unwrap();
+    assert_eq!(grown.data.len(), account.space());
+    assert!(grown.data.len() > raw.data.len());
+
+    // Shrinking the metadata returns the excess rent to the owner
+    let shrink_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::UpdateBlink {
+            blink,
+            authority: owner,
+            owner,
+            system_program: system_program::id(),
+            protocol_config: find_protocol_config_address().0,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::UpdateBlink {
+            name: "B".to_string(),
+            description: String::new(),
+            image_url: String::new(),
+            config: None,
+        }
+        .data(),
+    };
+    process(&mut context, &[shrink_ix], &[]).await;
+
+    let account: Blink = fetch(&mut context, blink).await;
+    let shrunk = context.banks_client.get_account(blink).await.unwrap().unwrap();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    assert_eq!(shrunk.data.len(), account.space());
+    assert_eq!(shrunk.lamports, rent.minimum_balance(shrunk.data.len()));
+
+    let delete_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::DeleteBlink {
+            blink,
+            registry,
+            owner,
+            handle_account: None,
+            attributes: find_blink_attributes_address(&blink).0,
+            creator_registry: None,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::DeleteBlink {}.data(),
+    };
+    process(&mut context, &[delete_ix], &[]).await;
+
+    let closed = context.banks_client.get_account(blink).await.unwrap();
+    assert!(closed.is_none());
+
+    let registry_account: OwnerRegistry = fetch(&mut context, registry).await;
+    assert_eq!(registry_account.blink_count, 1);
+    assert_eq!(registry_account.active_count, 0);
+}
+
+#[tokio::test]
+async fn nft_and_collection() {
+    let mut context = program_test().start_with_context().await;
+    let owner = context.payer.pubkey();
+
+    let collection = Keypair::new();
+    let create_collection_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::CreateCollection {
+            collection: collection.pubkey(),
+            owner,
+            system_program: system_program::id(),
+            protocol_config: find_protocol_config_address().0,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::CreateCollection {
+            name: "Test Collection".to_string(),
+            symbol: "TCOL".to_string(),
+            uri: "https://example.com/collection.json".to_string(),
+        }
+        .data(),
+    };
+    process(&mut context, &[create_collection_ix], &[&collection]).await;
+
+    let account: Collection = fetch(&mut context, collection.pubkey()).await;
+    assert_eq!(account.owner, owner);
+    assert_eq!(account.symbol, "TCOL");
+
+    let mint = Keypair::new();
+    create_mint_account(&mut context, &mint).await;
+    let token_account = create_token_account(&mut context, &mint.pubkey(), &owner, 1).await;
+
+    let nft = Keypair::new();
+    let create_nft_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::CreateNFT {
+            nft: nft.pubkey(),
+            owner,
+            system_program: system_program::id(),
+            token_program: spl_token::id(),
+            metadata: metadata_address(&mint.pubkey()),
+            mint: mint.pubkey(),
+            token_account,
+            metadata_program: mpl_token_metadata::ID,
+            rent: sysvar::rent::id(),
+            protocol_config: find_protocol_config_address().0,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::CreateNft {
+            name: "Test NFT".to_string(),
+            symbol: "TNFT".to_string(),
+            uri: "https://example.com/nft-metadata.json".to_string(),
+        }
+        .data(),
+    };
+    process(&mut context, &[create_nft_ix], &[&nft, &mint]).await;
+
+    let account: NFT = fetch(&mut context, nft.pubkey()).await;
+    assert_eq!(account.mint, mint.pubkey());
+    assert_eq!(account.collection, Pubkey::default());
+
+    let add_ix = |collection: Pubkey, collection_owner: Pubkey| Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::AddNFTToCollection {
+            nft: nft.pubkey(),
+            collection,
+            owner: collection_owner,
+            nft_owner: collection_owner,
+            protocol_config: find_protocol_config_address().0,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::AddNftToCollection {}.data(),
+    };
+    process(&mut context, &[add_ix(collection.pubkey(), owner)], &[]).await;
+
+    let account: NFT = fetch(&mut context, nft.pubkey()).await;
+    assert_eq!(account.collection, collection.pubkey());
+
+    // Another collection's owner can't pull the NFT out of this one without its owner
+    let stranger = Keypair::new();
+    let stranger_collection = Keypair::new();
+    let fund_ix = system_instruction::transfer(&owner, &stranger.pubkey(), 1_000_000_000);
+    let create_collection_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::CreateCollection {
+            collection: stranger_collection.pubkey(),
+            owner: stranger.pubkey(),
+            system_program: system_program::id(),
+            protocol_config: find_protocol_config_address().0,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::CreateCollection {
+            name: "Other Collection".to_string(),
+            symbol: "OCOL".to_string(),
+            uri: "https://example.com/other.json".to_string(),
+        }
+        .data(),
+    };
+    let signers = [&stranger, &stranger_collection];
+    process(&mut context, &[fund_ix, create_collection_ix], &signers).await;
+
+    let steal_ix = add_ix(stranger_collection.pubkey(), stranger.pubkey());
+    let result = try_process(&mut context, &[steal_ix], &[&stranger]).await;
+    assert_blink_error(result, BlinkError::Unauthorized);
+
+    let account: NFT = fetch(&mut context, nft.pubkey()).await;
+    assert_eq!(account.collection, collection.pubkey());
+}
+
+#[tokio::test]
+async fn donation_and_payment() {
+    let mut context = program_test().start_with_context().await;
+    let payer = context.payer.pubkey();
+    let recipient = Pubkey::new_unique();
+
+    let mint = Keypair::new();
+    create_mint_account(&mut context, &mint).await;
+    let payer_token_account =
+        create_token_account(&mut context, &mint.pubkey(), &payer, 1_000).await;
+    let recipient_token_account =
+        create_token_account(&mut context, &mint.pubkey(), &recipient, 0).await;
+
+    let donation = Keypair::new();
+    let donation_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::CreateDonation {
+            donation: donation.pubkey(),
+            donor: payer,
+            recipient,
+            donor_token_account: payer_token_account,
+            recipient_token_account,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            protocol_config: find_protocol_config_address().0,
+            treasury_token_account: None,
+            blink: None,
+            history: None,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::CreateDonation {
+            amount: 100,
+            currency: "BARK".to_string(),
+            message: "Test donation".to_string(),
+        }
+        .data(),
+    };
+    process(&mut context, &[donation_ix], &[&donation]).await;
+
+    let account: Donation = fetch(&mut context, donation.pubkey()).await;
+    assert_eq!(account.donor, payer);
+    assert_eq!(account.amount, 100);
+    assert_eq!(
+        token_balance(&mut context, recipient_token_account).await,
+        100
+    );
+
+    let payment = Keypair::new();
+    let payment_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::CreatePayment {
+            payment: payment.pubkey(),
+            payer,
+            recipient,
+            payer_token_account,
+            recipient_token_account,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            protocol_config: find_protocol_config_address().0,
+            treasury_token_account: None,
+            blink: None,
+            history: None,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::CreatePayment {
+            amount: 250,
+            currency: "BARK".to_string(),
+            description: "Test payment".to_string(),
+        }
+        .data(),
+    };
+    process(&mut context, &[payment_ix], &[&payment]).await;
+
+    let account: Payment = fetch(&mut context, payment.pubkey()).await;
+    assert_eq!(account.amount, 250);
+    assert!(account.status == PaymentStatus::Completed);
+    assert_eq!(
+        token_balance(&mut context, recipient_token_account).await,
+        350
+    );
+}
+
+#[tokio::test]
+async fn mint_creation() {
+    let treasury = Pubkey::new_unique();
+    let mut context = program_test_with_admin(treasury).start_with_context().await;
+    let mint_authority = context.payer.pubkey();
+    let rent = context.banks_client.get_rent().await.unwrap();
+
+    let mint = Keypair::new();
+    let allocate_ix = system_instruction::create_account(
+        &mint_authority,
+        &mint.pubkey(),
+        rent.minimum_balance(spl_token::state::Mint::LEN),
+        spl_token::state::Mint::LEN as u64,
+        &spl_token::id(),
+    );
+    let create_mint_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::CreateMint {
+            mint_authority,
+            mint: mint.pubkey(),
+            token_program: spl_token::id(),
+            rent: sysvar::rent::id(),
+            system_program: system_program::id(),
+            metadata: metadata_address(&mint.pubkey()),
+            token_metadata_program: mpl_token_metadata::ID,
+            protocol_config: find_protocol_config_address().0,
+            treasury,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::CreateMint {
+            name: "Test Mint".to_string(),
+            symbol: "TMNT".to_string(),
+            uri: "https://example.com/mint.json".to_string(),
+        }
+        .data(),
+    };
+    process(&mut context, &[allocate_ix, create_mint_ix], &[&mint]).await;
+
+    let account = context
+        .banks_client
+        .get_account(mint.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let state = spl_token::state::Mint::unpack(&account.data).unwrap();
+    assert!(state.is_initialized);
+    assert_eq!(state.decimals, 0);
+}
+
+#[tokio::test]
+async fn swap_lifecycle() {
+    let mut context = program_test().start_with_context().await;
+    let owner = context.payer.pubkey();
+    let counterparty = Pubkey::new_unique();
+
+    let mint_a = Keypair::new();
+    let mint_b = Keypair::new();
+    create_mint_account(&mut context, &mint_a).await;
+    create_mint_account(&mut context, &mint_b).await;
+    let token_a_source = create_token_account(&mut context, &mint_a.pubkey(), &owner, 500).await;
+    let token_a_destination =
+        create_token_account(&mut context, &mint_a.pubkey(), &counterparty, 0).await;
+    let token_b_source = create_token_account(&mut context, &mint_b.pubkey(), &owner, 500).await;
+    let token_b_destination =
+        create_token_account(&mut context, &mint_b.pubkey(), &counterparty, 0).await;
+
+    let swap = Keypair::new();
+    let create_swap_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::CreateSwap {
+            swap: swap.pubkey(),
+            owner,
+            token_a: token_a_source,
+            token_b: token_b_source,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            protocol_config: find_protocol_config_address().0,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::CreateSwap {
+            amount_a: 50,
+            amount_b: 100,
+            fee: 1,
+        }
+        .data(),
+    };
+    process(&mut context, &[create_swap_ix], &[&swap]).await;
+
+    let account: Swap = fetch(&mut context, swap.pubkey()).await;
+    assert_eq!(account.owner, owner);
+    assert!(account.executed_at.is_none());
+
+    let execute_swap_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::ExecuteSwap {
+            swap: swap.pubkey(),
+            owner,
+            token_a_source,
+            token_a_destination,
+            token_b_source,
+            token_b_destination,
+            token_program: spl_token::id(),
+            protocol_config: find_protocol_config_address().0,
+            treasury_token_account: None,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::ExecuteSwap {}.data(),
+    };
+    process(&mut context, &[execute_swap_ix], &[]).await;
+
+    let account: Swap = fetch(&mut context, swap.pubkey()).await;
+    assert!(account.executed_at.is_some());
+    assert_eq!(token_balance(&mut context, token_a_destination).await, 50);
+    assert_eq!(token_balance(&mut context, token_b_destination).await, 100);
+}
+
+#[tokio::test]
+async fn poll_voting() {
+    let mut context = program_test().start_with_context().await;
+    let owner = context.payer.pubkey();
+    let blink = create_blink(
+        &mut context,
+        BlinkType::Poll,
+        BlinkConfig::Poll {
+            options: vec!["Yes".to_string(), "No".to_string()],
+        },
+    )
+    .await;
+    let (poll, _) = Pubkey::find_program_address(&[POLL_SEED, blink.as_ref()], &bark_blinks::id());
+
+    let open_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::OpenPoll {
+            blink,
+            poll,
+            owner,
+            system_program: system_program::id(),
+            protocol_config: find_protocol_config_address().0,
+            vote_mint: None,
+            vote_vault: None,
+            token_program: spl_token::id(),
+            rent: sysvar::rent::id(),
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::OpenPoll { closes_at: i64::MAX }.data(),
+    };
+    let logs = process_with_logs(&mut context, &[open_ix], &[]).await;
+
+    let opened = events::<PollOpened>(&logs);
+    assert_eq!(opened.len(), 1);
+    assert_eq!(opened[0].poll, poll);
+    assert_eq!(opened[0].blink, blink);
+    assert_eq!(opened[0].vote_mint, None);
+    assert_eq!(opened[0].options, vec!["Yes".to_string(), "No".to_string()]);
+    assert_eq!(opened[0].closes_at, i64::MAX);
+
+    let (vote_receipt, _) = Pubkey::find_program_address(
+        &[VOTE_RECEIPT_SEED, poll.as_ref(), owner.as_ref()],
+        &bark_blinks::id(),
+    );
+    let vote_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::CastVote {
+            blink,
+            poll,
+            vote_receipt,
+            voter: owner,
+            voter_token_account: None,
+            system_program: system_program::id(),
+            history: None,
+            protocol_config: find_protocol_config_address().0,
+            vote_vault: None,
+            token_program: spl_token::id(),
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::CastVote { option: 1 }.data(),
+    };
+    process(&mut context, std::slice::from_ref(&vote_ix), &[]).await;
+
+    let account: Poll = fetch(&mut context, poll).await;
+    assert_eq!(account.tallies, vec![0, 1]);
+    assert_eq!(account.voter_count, 1);
+
+    // A second vote from the same wallet is rejected by the existing receipt
+    context.last_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let transaction = Transaction::new_signed_with_payer(
+        &[vote_ix],
+        Some(&owner),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    assert!(context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .is_err());
+
+    // Editing the blink's options doesn't relabel an open poll
+    let update_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::UpdateBlink {
+            blink,
+            authority: owner,
+            owner,
+            system_program: system_program::id(),
+            protocol_config: find_protocol_config_address().0,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::UpdateBlink {
+            name: "Test Blink".to_string(),
+            description: "This is a test blink".to_string(),
+            image_url: "https://example.com/image.png".to_string(),
+            config: Some(BlinkConfig::Poll {
+                options: vec!["No".to_string(), "Yes".to_string(), "Maybe".to_string()],
+            }),
+        }
+        .data(),
+    };
+    process(&mut context, &[update_ix], &[]).await;
+
+    let account: Poll = fetch(&mut context, poll).await;
+    assert_eq!(account.options, vec!["Yes".to_string(), "No".to_string()]);
+    assert_eq!(account.tallies, vec![0, 1]);
+}
+
+#[tokio::test]
+async fn token_weighted_poll_locks_votes() {
+    let mut context = program_test().start_with_context().await;
+    let owner = context.payer.pubkey();
+    let other_voter = Keypair::new();
+
+    let mint = Keypair::new();
+    create_mint_account(&mut context, &mint).await;
+    let owner_token_account = create_token_account(&mut context, &mint.pubkey(), &owner, 100).await;
+    let other_token_account =
+        create_token_account(&mut context, &mint.pubkey(), &other_voter.pubkey(), 0).await;
+    let fund_ix = system_instruction::transfer(&owner, &other_voter.pubkey(), 1_000_000_000);
+    process(&mut context, &[fund_ix], &[]).await;
+
+    let blink = create_blink(
+        &mut context,
+        BlinkType::Poll,
+        BlinkConfig::Poll {
+            options: vec!["Yes".to_string(), "No".to_string()],
+        },
+    )
+    .await;
+    let (poll, _) = find_poll_address(&blink);
+    let (vote_vault, _) = find_poll_vault_address(&poll);
+    let mut clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    let closes_at = clock.unix_timestamp + 3_600;
+
+    let open_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::OpenPoll {
+            blink,
+            poll,
+            owner,
+            system_program: system_program::id(),
+            protocol_config: find_protocol_config_address().0,
+            vote_mint: Some(mint.pubkey()),
+            vote_vault: Some(vote_vault),
+            token_program: spl_token::id(),
+            rent: sysvar::rent::id(),
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::OpenPoll { closes_at }.data(),
+    };
+    process(&mut context, &[open_ix], &[]).await;
+
+    let vote_ix = |voter: Pubkey, voter_token_account: Pubkey| Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::CastVote {
+            blink,
+            poll,
+            vote_receipt: find_vote_receipt_address(&poll, &voter).0,
+            voter,
+            voter_token_account: Some(voter_token_account),
+            system_program: system_program::id(),
+            history: None,
+            protocol_config: find_protocol_config_address().0,
+            vote_vault: Some(vote_vault),
+            token_program: spl_token::id(),
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::CastVote { option: 0 }.data(),
+    };
+    process(&mut context, &[vote_ix(owner, owner_token_account)], &[]).await;
+
+    let account: Poll = fetch(&mut context, poll).await;
+    assert_eq!(account.tallies, vec![100, 0]);
+    assert_eq!(token_balance(&mut context, vote_vault).await, 100);
+    assert_eq!(token_balance(&mut context, owner_token_account).await, 0);
+
+    // The voted tokens stay locked, so there is nothing to pass on and vote with again
+    let other_vote_ix = vote_ix(other_voter.pubkey(), other_token_account);
+    let result = try_process(&mut context, &[other_vote_ix], &[&other_voter]).await;
+    assert_blink_error(result, BlinkError::InsufficientFunds);
+
+    let withdraw_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::WithdrawVoteTokens {
+            poll,
+            vote_receipt: find_vote_receipt_address(&poll, &owner).0,
+            voter: owner,
+            voter_token_account: owner_token_account,
+            vote_vault,
+            token_program: spl_token::id(),
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::WithdrawVoteTokens {}.data(),
+    };
+    let result = try_process(&mut context, std::slice::from_ref(&withdraw_ix), &[]).await;
+    assert_blink_error(result, BlinkError::PollStillOpen);
+
+    clock.unix_timestamp = closes_at;
+    context.set_sysvar(&clock);
+    // A fresh blockhash keeps the retry from matching the failed transaction
+    context.last_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    process(&mut context, &[withdraw_ix], &[]).await;
+
+    assert_eq!(token_balance(&mut context, owner_token_account).await, 100);
+    assert_eq!(token_balance(&mut context, vote_vault).await, 0);
+    let (vote_receipt, _) = find_vote_receipt_address(&poll, &owner);
+    assert!(context.banks_client.get_account(vote_receipt).await.unwrap().is_none());
+    let account: Poll = fetch(&mut context, poll).await;
+    assert_eq!(account.tallies, vec![100, 0]);
+}
+
+#[tokio::test]
+async fn gift_claimed_with_claim_key() {
+    let mut context = program_test().start_with_context().await;
+    let owner = context.payer.pubkey();
+
+    let mint = Keypair::new();
+    create_mint_account(&mut context, &mint).await;
+    let owner_token_account =
+        create_token_account(&mut context, &mint.pubkey(), &owner, 1_000).await;
+
+    let claim_key = Keypair::new();
+    let blink = create_blink(
+        &mut context,
+        BlinkType::Gift,
+        BlinkConfig::Gift {
+            mint: mint.pubkey(),
+            amount: 400,
+            recipient: None,
+            claim_key: Some(claim_key.pubkey()),
+            expires_at: i64::MAX,
+        },
+    )
+    .await;
+    let (escrow, vault) = fund_gift(&mut context, blink, &mint, owner_token_account).await;
+    assert_eq!(token_balance(&mut context, vault).await, 400);
+
+    let claimant = Keypair::new();
+    let claimant_token_account =
+        create_token_account(&mut context, &mint.pubkey(), &claimant.pubkey(), 0).await;
+    let claim_ix = |claim_key: Option<Pubkey>| Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::ClaimGift {
+            blink,
+            escrow,
+            vault,
+            sender: owner,
+            claimant: claimant.pubkey(),
+            claimant_token_account,
+            token_program: spl_token::id(),
+            history: None,
+            protocol_config: find_protocol_config_address().0,
+            claim_key,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::ClaimGift {}.data(),
+    };
+
+    // Only the gift's own claim key can sign for the claim
+    let result = try_process(&mut context, &[claim_ix(None)], &[&claimant]).await;
+    assert_blink_error(result, BlinkError::InvalidGiftSecret);
+    let impostor = Keypair::new();
+    let result = try_process(
+        &mut context,
+        &[claim_ix(Some(impostor.pubkey()))],
+        &[&claimant, &impostor],
+    )
+    .await;
+    assert_blink_error(result, BlinkError::InvalidGiftSecret);
+
+    process(
+        &mut context,
+        &[claim_ix(Some(claim_key.pubkey()))],
+        &[&claimant, &claim_key],
+    )
+    .await;
+
+    let account: GiftEscrow = fetch(&mut context, escrow).await;
+    assert!(account.status == GiftStatus::Claimed);
+    assert_eq!(account.claimed_by, Some(claimant.pubkey()));
+    assert_eq!(
+        token_balance(&mut context, claimant_token_account).await,
+        400
+    );
+}
+
+#[tokio::test]
+async fn gift_claimed_by_recipient() {
+    let mut context = program_test().start_with_context().await;
+    let owner = context.payer.pubkey();
+
+    let mint = Keypair::new();
+    create_mint_account(&mut context, &mint).await;
+    let owner_token_account =
+        create_token_account(&mut context, &mint.pubkey(), &owner, 1_000).await;
+
+    let recipient = Keypair::new();
+    let blink = create_blink(
+        &mut context,
+        BlinkType::Gift,
+        BlinkConfig::Gift {
+            mint: mint.pubkey(),
+            amount: 400,
+            recipient: Some(recipient.pubkey()),
+            claim_key: None,
+            expires_at: i64::MAX,
+        },
+    )
+    .await;
+    let (escrow, vault) = fund_gift(&mut context, blink, &mint, owner_token_account).await;
+
+    let claim_ix = |claimant: Pubkey, claimant_token_account: Pubkey| Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::ClaimGift {
+            blink,
+            escrow,
+            vault,
+            sender: owner,
+            claimant,
+            claimant_token_account,
+            token_program: spl_token::id(),
+            history: None,
+            protocol_config: find_protocol_config_address().0,
+            claim_key: None,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::ClaimGift {}.data(),
+    };
+
+    let stranger = Keypair::new();
+    let stranger_token_account =
+        create_token_account(&mut context, &mint.pubkey(), &stranger.pubkey(), 0).await;
+    let result = try_process(
+        &mut context,
+        &[claim_ix(stranger.pubkey(), stranger_token_account)],
+        &[&stranger],
+    )
+    .await;
+    assert_blink_error(result, BlinkError::Unauthorized);
+
+    // The named recipient needs no claim key
+    let recipient_token_account =
+        create_token_account(&mut context, &mint.pubkey(), &recipient.pubkey(), 0).await;
+    process(
+        &mut context,
+        &[claim_ix(recipient.pubkey(), recipient_token_account)],
+        &[&recipient],
+    )
+    .await;
+
+    let account: GiftEscrow = fetch(&mut context, escrow).await;
+    assert!(account.status == GiftStatus::Claimed);
+    assert_eq!(account.claimed_by, Some(recipient.pubkey()));
+    assert_eq!(
+        token_balance(&mut context, recipient_token_account).await,
+        400
+    );
+}
+
+#[tokio::test]
+async fn expired_gift_reclaimed_by_sender() {
+    let mut context = program_test().start_with_context().await;
+    let owner = context.payer.pubkey();
+
+    let mint = Keypair::new();
+    create_mint_account(&mut context, &mint).await;
+    let owner_token_account =
+        create_token_account(&mut context, &mint.pubkey(), &owner, 1_000).await;
+
+    let mut clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    let expires_at = clock.unix_timestamp + 3_600;
+    let recipient = Keypair::new();
+    let blink = create_blink(
+        &mut context,
+        BlinkType::Gift,
+        BlinkConfig::Gift {
+            mint: mint.pubkey(),
+            amount: 400,
+            recipient: Some(recipient.pubkey()),
+            claim_key: None,
+            expires_at,
+        },
+    )
+    .await;
+    let (escrow, vault) = fund_gift(&mut context, blink, &mint, owner_token_account).await;
+    assert_eq!(token_balance(&mut context, owner_token_account).await, 600);
+
+    let reclaim_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::ReclaimGift {
+            escrow,
+            vault,
+            sender: owner,
+            sender_token_account: owner_token_account,
+            token_program: spl_token::id(),
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::ReclaimGift {}.data(),
+    };
+    let result = try_process(&mut context, std::slice::from_ref(&reclaim_ix), &[]).await;
+    assert_blink_error(result, BlinkError::GiftNotExpired);
+
+    clock.unix_timestamp = expires_at;
+    context.set_sysvar(&clock);
+
+    // Once expired the recipient can no longer claim
+    let recipient_token_account =
+        create_token_account(&mut context, &mint.pubkey(), &recipient.pubkey(), 0).await;
+    let claim_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::ClaimGift {
+            blink,
+            escrow,
+            vault,
+            sender: owner,
+            claimant: recipient.pubkey(),
+            claimant_token_account: recipient_token_account,
+            token_program: spl_token::id(),
+            history: None,
+            protocol_config: find_protocol_config_address().0,
+            claim_key: None,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::ClaimGift {}.data(),
+    };
+    let result = try_process(&mut context, &[claim_ix], &[&recipient]).await;
+    assert_blink_error(result, BlinkError::GiftExpired);
+
+    // A fresh blockhash keeps the retry from matching the failed transaction
+    context.last_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let logs = process_with_logs(&mut context, &[reclaim_ix], &[]).await;
+
+    let account: GiftEscrow = fetch(&mut context, escrow).await;
+    assert!(account.status == GiftStatus::Reclaimed);
+    assert_eq!(account.claimed_by, None);
+    assert_eq!(token_balance(&mut context, owner_token_account).await, 1_000);
+    assert!(context.banks_client.get_account(vault).await.unwrap().is_none());
+
+    let reclaimed = events::<GiftReclaimed>(&logs);
+    assert_eq!(reclaimed.len(), 1);
+    assert_eq!(reclaimed[0].escrow, escrow);
+    assert_eq!(reclaimed[0].sender, owner);
+    assert_eq!(reclaimed[0].amount, 400);
+}
+
+#[tokio::test]
+async fn donation_to_blink_vault() {
+    let mut context = program_test().start_with_context().await;
+    let owner = context.payer.pubkey();
+
+    let mint = Keypair::new();
+    create_mint_account(&mut context, &mint).await;
+    let owner_token_account =
+        create_token_account(&mut context, &mint.pubkey(), &owner, 1_000).await;
+
+    let blink = create_blink(
+        &mut context,
+        BlinkType::Donation,
+        BlinkConfig::Donation {
+            recipient: Pubkey::new_unique(),
+            mint: mint.pubkey(),
+            suggested_amounts: vec![10, 50],
+        },
+    )
+    .await;
+    let (vault, _) = find_blink_vault_address(&blink, &mint.pubkey());
+
+    let open_vault_ix = |payer: Pubkey| Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::OpenBlinkVault {
+            blink,
+            vault,
+            mint: mint.pubkey(),
+            payer,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            rent: sysvar::rent::id(),
+            protocol_config: find_protocol_config_address().0,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::OpenBlinkVault {}.data(),
+    };
+
+    // An open vault blocks deletion, so a stranger can't open one on someone else's blink
+    let stranger = Keypair::new();
+    let fund_ix = system_instruction::transfer(&owner, &stranger.pubkey(), 1_000_000_000);
+    process(&mut context, &[fund_ix], &[]).await;
+    let result =
+        try_process(&mut context, &[open_vault_ix(stranger.pubkey())], &[&stranger]).await;
+    assert_blink_error(result, BlinkError::Unauthorized);
+
+    let logs = process_with_logs(&mut context, &[open_vault_ix(owner)], &[]).await;
+    let opened = events::<BlinkVaultOpened>(&logs);
+    assert_eq!(opened.len(), 1);
+    assert_eq!(opened[0].blink, blink);
+    assert_eq!(opened[0].vault, vault);
+    assert_eq!(opened[0].mint, mint.pubkey());
+    assert_eq!(opened[0].authority, owner);
+
+    let donation = Keypair::new();
+    let donation_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::CreateDonation {
+            donation: donation.pubkey(),
+            donor: owner,
+            recipient: blink,
+            donor_token_account: owner_token_account,
+            recipient_token_account: vault,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            protocol_config: find_protocol_config_address().0,
+            treasury_token_account: None,
+            blink: Some(blink),
+            history: None,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::CreateDonation {
+            amount: 50,
+            currency: "BARK".to_string(),
+            message: "Tip".to_string(),
+        }
+        .data(),
+    };
+    process(&mut context, &[donation_ix], &[&donation]).await;
+    assert_eq!(token_balance(&mut context, vault).await, 50);
+
+    let withdraw_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::WithdrawFromBlinkVault {
+            blink,
+            vault,
+            destination: owner_token_account,
+            authority: owner,
+            token_program: spl_token::id(),
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::WithdrawFromBlinkVault { amount: 30 }.data(),
+    };
+    let logs = process_with_logs(&mut context, &[withdraw_ix], &[]).await;
+    assert_eq!(token_balance(&mut context, vault).await, 20);
+    assert_eq!(token_balance(&mut context, owner_token_account).await, 980);
+    let withdrawn = events::<BlinkVaultWithdrawn>(&logs);
+    assert_eq!(withdrawn.len(), 1);
+    assert_eq!(withdrawn[0].destination, owner_token_account);
+    assert_eq!(withdrawn[0].amount, 30);
+
+    // The blink can't be deleted while its vault still exists
+    let (registry, _) = find_owner_registry_address(&owner);
+    let delete_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::DeleteBlink {
+            blink,
+            registry,
+            owner,
+            handle_account: None,
+            attributes: find_blink_attributes_address(&blink).0,
+            creator_registry: None,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::DeleteBlink {}.data(),
+    };
+    let result = try_process(&mut context, std::slice::from_ref(&delete_ix), &[]).await;
+    assert_blink_error(result, BlinkError::BlinkVaultsOpen);
+
+    let close_vault_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::CloseBlinkVault {
+            blink,
+            vault,
+            destination: owner_token_account,
+            authority: owner,
+            owner,
+            token_program: spl_token::id(),
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::CloseBlinkVault {}.data(),
+    };
+    let logs = process_with_logs(&mut context, &[close_vault_ix], &[]).await;
+    assert!(context.banks_client.get_account(vault).await.unwrap().is_none());
+    assert_eq!(token_balance(&mut context, owner_token_account).await, 1_000);
+    let closed = events::<BlinkVaultClosed>(&logs);
+    assert_eq!(closed.len(), 1);
+    assert_eq!(closed[0].vault, vault);
+    assert_eq!(closed[0].authority, owner);
+    assert_eq!(closed[0].amount, 20);
+    let account: Blink = fetch(&mut context, blink).await;
+    assert_eq!(account.open_vaults, 0);
+
+    // A fresh blockhash keeps the retry from matching the failed transaction
+    context.last_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    process(&mut context, &[delete_ix], &[]).await;
+    assert!(context.banks_client.get_account(blink).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn blink_routed_donations_match_blink_config() {
+    let mut context = program_test().start_with_context().await;
+    let owner = context.payer.pubkey();
+
+    let mint = Keypair::new();
+    create_mint_account(&mut context, &mint).await;
+    let other_mint = Keypair::new();
+    create_mint_account(&mut context, &other_mint).await;
+    let donation_blink = create_blink(
+        &mut context,
+        BlinkType::Donation,
+        BlinkConfig::Donation {
+            recipient: owner,
+            mint: mint.pubkey(),
+            suggested_amounts: vec![],
+        },
+    )
+    .await;
+    let standard_blink =
+        create_blink(&mut context, BlinkType::Standard, BlinkConfig::Standard).await;
+
+    let open_vault_ix = |blink: Pubkey, mint: Pubkey| Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::OpenBlinkVault {
+            blink,
+            vault: find_blink_vault_address(&blink, &mint).0,
+            mint,
+            payer: owner,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            rent: sysvar::rent::id(),
+            protocol_config: find_protocol_config_address().0,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::OpenBlinkVault {}.data(),
+    };
+    let open_vaults = [
+        open_vault_ix(donation_blink, other_mint.pubkey()),
+        open_vault_ix(standard_blink, mint.pubkey()),
+    ];
+    process(&mut context, &open_vaults, &[]).await;
+
+    let donation = Keypair::new();
+    let donation_ix = |blink: Pubkey, mint: Pubkey, donor_token_account: Pubkey| Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::CreateDonation {
+            donation: donation.pubkey(),
+            donor: owner,
+            recipient: blink,
+            donor_token_account,
+            recipient_token_account: find_blink_vault_address(&blink, &mint).0,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            protocol_config: find_protocol_config_address().0,
+            treasury_token_account: None,
+            blink: Some(blink),
+            history: None,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::CreateDonation {
+            amount: 10,
+            currency: "BARK".to_string(),
+            message: String::new(),
+        }
+        .data(),
+    };
+
+    // A donation blink only takes its configured mint
+    let other_token_account =
+        create_token_account(&mut context, &other_mint.pubkey(), &owner, 10).await;
+    let ix = donation_ix(donation_blink, other_mint.pubkey(), other_token_account);
+    let result = try_process(&mut context, &[ix], &[&donation]).await;
+    assert_blink_error(result, BlinkError::InvalidMint);
+
+    // Other blink types don't take donations at all
+    let owner_token_account = create_token_account(&mut context, &mint.pubkey(), &owner, 10).await;
+    let ix = donation_ix(standard_blink, mint.pubkey(), owner_token_account);
+    let result = try_process(&mut context, &[ix], &[&donation]).await;
+    assert_blink_error(result, BlinkError::InvalidBlinkType);
+
+    let account: Blink = fetch(&mut context, standard_blink).await;
+    assert_eq!(account.execution_count, 0);
+    assert_eq!(account.xp, 0);
+}
+
+#[tokio::test]
+async fn volume_tracking_never_blocks_donations() {
+    let mut context = program_test().start_with_context().await;
+    let owner = context.payer.pubkey();
+    let donation_config = |mint: Pubkey| BlinkConfig::Donation {
+        recipient: owner,
+        mint,
+        suggested_amounts: vec![],
+    };
+    let blink = create_blink(
+        &mut context,
+        BlinkType::Donation,
+        donation_config(Pubkey::new_unique()),
+    )
+    .await;
+
+    // Dust in four throwaway mints fills every slot, then a real donation evicts the
+    // smallest and more dust goes untracked. The owner switches the blink's mint before
+    // each donation since it only takes donations in its configured mint.
+    let amounts = [1, 2, 1, 1, 500, 1];
+    let mut mints = Vec::new();
+    for amount in amounts {
+        let mint = Keypair::new();
+        create_mint_account(&mut context, &mint).await;
+        let donor_token_account =
+            create_token_account(&mut context, &mint.pubkey(), &owner, amount).await;
+        let (vault, _) = find_blink_vault_address(&blink, &mint.pubkey());
+        let update_ix = Instruction {
+            program_id: bark_blinks::id(),
+            accounts: bark_blinks::accounts::UpdateBlink {
+                blink,
+                authority: owner,
+                owner,
+                system_program: system_program::id(),
+                protocol_config: find_protocol_config_address().0,
+            }
+            .to_account_metas(None),
+            data: bark_blinks::instruction::UpdateBlink {
+                name: "Test Blink".to_string(),
+                description: "This is a test blink".to_string(),
+                image_url: "https://example.com/image.png".to_string(),
+                config: Some(donation_config(mint.pubkey())),
+            }
+            .data(),
+        };
+        let open_vault_ix = Instruction {
+            program_id: bark_blinks::id(),
+            accounts: bark_blinks::accounts::OpenBlinkVault {
+                blink,
+                vault,
+                mint: mint.pubkey(),
+                payer: owner,
+                token_program: spl_token::id(),
+                system_program: system_program::id(),
+                rent: sysvar::rent::id(),
+                protocol_config: find_protocol_config_address().0,
+            }
+            .to_account_metas(None),
+            data: bark_blinks::instruction::OpenBlinkVault {}.data(),
+        };
+        let donation = Keypair::new();
+        let donation_ix = Instruction {
+            program_id: bark_blinks::id(),
+            accounts: bark_blinks::accounts::CreateDonation {
+                donation: donation.pubkey(),
+                donor: owner,
+                recipient: blink,
+                donor_token_account,
+                recipient_token_account: vault,
+                token_program: spl_token::id(),
+                system_program: system_program::id(),
+                protocol_config: find_protocol_config_address().0,
+                treasury_token_account: None,
+                blink: Some(blink),
+                history: None,
+            }
+            .to_account_metas(None),
+            data: bark_blinks::instruction::CreateDonation {
+                amount,
+                currency: "BARK".to_string(),
+                message: String::new(),
+            }
+            .data(),
+        };
+        let instructions = [update_ix, open_vault_ix, donation_ix];
+        process(&mut context, &instructions, &[&donation]).await;
+        mints.push(mint.pubkey());
+    }
+
+    let account: Blink = fetch(&mut context, blink).await;
+    assert_eq!(account.execution_count, amounts.len() as u64);
+    let tracked: Vec<(Pubkey, u64)> = account
+        .volumes
+        .iter()
+        .map(|volume| (volume.mint, volume.total))
+        .collect();
+    assert_eq!(
+        tracked,
+        vec![(mints[4], 500), (mints[1], 2), (mints[2], 1), (mints[3], 1)]
+    );
+}
+
+#[test]
+fn history_ring_buffer_wraps() {
+    let mut history = BlinkHistory {
+        blink: Pubkey::new_unique(),
+        head: 0,
+        total_recorded: 0,
+        records: Vec::new(),
+        bump: 0,
+    };
+    let record = |amount: u64| ExecutionRecord {
+        executor: Pubkey::default(),
+        mint: Pubkey::default(),
+        amount,
+        timestamp: amount as i64,
+    };
+
+    for amount in 0..HISTORY_CAPACITY as u64 {
+        history.push(record(amount));
+    }
+    assert_eq!(history.head, 0);
+    let amounts: Vec<u64> = history.ordered().iter().map(|record| record.amount).collect();
+    assert_eq!(amounts, (0..HISTORY_CAPACITY as u64).collect::<Vec<_>>());
+
+    // Past capacity the oldest records are overwritten in place
+    let extra = 5;
+    for amount in HISTORY_CAPACITY as u64..HISTORY_CAPACITY as u64 + extra {
+        history.push(record(amount));
+    }
+    assert_eq!(history.records.len(), HISTORY_CAPACITY);
+    assert_eq!(history.head, extra as u32);
+    assert_eq!(history.total_recorded, HISTORY_CAPACITY as u64 + extra);
+    assert_eq!(history.records[0].amount, HISTORY_CAPACITY as u64);
+    let amounts: Vec<u64> = history.ordered().iter().map(|record| record.amount).collect();
+    assert_eq!(
+        amounts,
+        (extra..HISTORY_CAPACITY as u64 + extra).collect::<Vec<_>>()
+    );
+}
+
+#[tokio::test]
+async fn execute_payment_blink() {
+    let mut context = program_test().start_with_context().await;
+    let owner = context.payer.pubkey();
+    let merchant = Pubkey::new_unique();
+
+    let mint = Keypair::new();
+    create_mint_account(&mut context, &mint).await;
+    let owner_token_account =
+        create_token_account(&mut context, &mint.pubkey(), &owner, 1_000).await;
+    let merchant_token_account =
+        create_token_account(&mut context, &mint.pubkey(), &merchant, 0).await;
+
+    let blink = create_blink(
+        &mut context,
+        BlinkType::Payment,
+        BlinkConfig::Payment {
+            recipient: merchant,
+            mint: mint.pubkey(),
+            price: 75,
+        },
+    )
+    .await;
+
+    let mut accounts = bark_blinks::accounts::ExecuteBlink {
+        blink,
+        executor: owner,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        history: None,
+        protocol_config: find_protocol_config_address().0,
+        treasury_token_account: None,
+    }
+    .to_account_metas(None);
+    accounts.push(AccountMeta::new(owner_token_account, false));
+    accounts.push(AccountMeta::new(merchant_token_account, false));
+    let execute_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts,
+        data: bark_blinks::instruction::ExecuteBlink {
+            args: ExecuteBlinkArgs::default(),
+        }
+        .data(),
+    };
+    process(&mut context, &[execute_ix], &[]).await;
+
+    assert_eq!(token_balance(&mut context, merchant_token_account).await, 75);
+    assert_eq!(token_balance(&mut context, owner_token_account).await, 925);
+
+    let account: Blink = fetch(&mut context, blink).await;
+    assert_eq!(account.execution_count, 1);
+    assert_eq!(account.volumes.len(), 1);
+    assert_eq!(account.volumes[0].mint, mint.pubkey());
+    assert_eq!(account.volumes[0].total, 75);
+    // Owners earn no XP from their own blinks
+    assert_eq!(account.xp, 0);
+    assert_eq!(account.level, 1);
+    assert!(account.rarity == BlinkRarity::Common);
+}
+
+#[tokio::test]
+async fn blink_routed_payments_level_up_blink() {
+    let mut context = program_test().start_with_context().await;
+    let owner = context.payer.pubkey();
+    let customer = Keypair::new();
+    let (protocol_config, _) = find_protocol_config_address();
+    // Ten digits earns the most XP a single execution can
+    let price = 10u64.pow(XP_PER_EXECUTION as u32);
+
+    let mint = Keypair::new();
+    create_mint_account(&mut context, &mint).await;
+    let owner_token_account =
+        create_token_account(&mut context, &mint.pubkey(), &owner, 1_000).await;
+    let customer_token_account =
+        create_token_account(&mut context, &mint.pubkey(), &customer.pubkey(), price * 10).await;
+    let fund_ix = system_instruction::transfer(&owner, &customer.pubkey(), 1_000_000_000);
+    process(&mut context, &[fund_ix], &[]).await;
+
+    // Both blinks collect into their own vaults
+    let (registry, _) = find_owner_registry_address(&owner);
+    let (donation_blink, _) = find_blink_address(&registry, 0);
+    let (payment_blink, _) = find_blink_address(&registry, 1);
+    create_blink(
+        &mut context,
+        BlinkType::Donation,
+        BlinkConfig::Donation {
+            recipient: donation_blink,
+            mint: mint.pubkey(),
+            suggested_amounts: vec![],
+        },
+    )
+    .await;
+    create_blink(
+        &mut context,
+        BlinkType::Payment,
+        BlinkConfig::Payment {
+            recipient: payment_blink,
+            mint: mint.pubkey(),
+            price,
+        },
+    )
+    .await;
+    let (donation_vault, _) = find_blink_vault_address(&donation_blink, &mint.pubkey());
+    let (payment_vault, _) = find_blink_vault_address(&payment_blink, &mint.pubkey());
+    let open_vault_ix = |blink: Pubkey, vault: Pubkey| Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::OpenBlinkVault {
+            blink,
+            vault,
+            mint: mint.pubkey(),
+            payer: owner,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            rent: sysvar::rent::id(),
+            protocol_config,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::OpenBlinkVault {}.data(),
+    };
+    process(
+        &mut context,
+        &[
+            open_vault_ix(donation_blink, donation_vault),
+            open_vault_ix(payment_blink, payment_vault),
+        ],
+        &[],
+    )
+    .await;
+
+    let donation = Keypair::new();
+    let donation_ix = |amount: u64| Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::CreateDonation {
+            donation: donation.pubkey(),
+            donor: owner,
+            recipient: donation_blink,
+            donor_token_account: owner_token_account,
+            recipient_token_account: donation_vault,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            protocol_config,
+            treasury_token_account: None,
+            blink: Some(donation_blink),
+            history: None,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::CreateDonation {
+            amount,
+            currency: "BARK".to_string(),
+            message: "Tip".to_string(),
+        }
+        .data(),
+    };
+    let result = try_process(&mut context, &[donation_ix(0)], &[&donation]).await;
+    assert_blink_error(result, BlinkError::InsufficientFunds);
+
+    // Donating dust to your own blink earns nothing
+    process(&mut context, &[donation_ix(1)], &[&donation]).await;
+    let account: Blink = fetch(&mut context, donation_blink).await;
+    assert_eq!(account.execution_count, 1);
+    assert_eq!(account.xp, 0);
+    assert_eq!(account.level, 1);
+
+    let payment_ix = |payment: &Keypair, amount: u64| Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::CreatePayment {
+            payment: payment.pubkey(),
+            payer: customer.pubkey(),
+            recipient: payment_blink,
+            payer_token_account: customer_token_account,
+            recipient_token_account: payment_vault,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            protocol_config,
+            treasury_token_account: None,
+            blink: Some(payment_blink),
+            history: None,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::CreatePayment {
+            amount,
+            currency: "BARK".to_string(),
+            description: "Order".to_string(),
+        }
+        .data(),
+    };
+    let payment = Keypair::new();
+    let signers = [&customer, &payment];
+    let result = try_process(&mut context, &[payment_ix(&payment, 0)], &signers).await;
+    assert_blink_error(result, BlinkError::InsufficientFunds);
+    let result = try_process(&mut context, &[payment_ix(&payment, price - 1)], &signers).await;
+    assert_blink_error(result, BlinkError::PriceMismatch);
+
+    // Reaching level 2 takes XP_LEVEL_BASE XP
+    let executions = XP_LEVEL_BASE / XP_PER_EXECUTION;
+    for execution in 1..=executions {
+        let payment = Keypair::new();
+        let signers = [&customer, &payment];
+        process(&mut context, &[payment_ix(&payment, price)], &signers).await;
+
+        let account: Blink = fetch(&mut context, payment_blink).await;
+        assert_eq!(account.xp, execution * XP_PER_EXECUTION);
+        let level = if execution < executions { 1 } else { 2 };
+        assert_eq!(account.level, level);
+    }
+    assert_eq!(token_balance(&mut context, payment_vault).await, price * executions);
+
+    let account: Blink = fetch(&mut context, payment_blink).await;
+    assert_eq!(account.execution_count, executions);
+    assert!(account.rarity == BlinkRarity::Common);
+}
+
+#[tokio::test]
+async fn blink_editors_and_ownership_transfer() {
+    let mut context = program_test().start_with_context().await;
+    let owner = context.payer.pubkey();
+    let blink = create_blink(&mut context, BlinkType::Standard, BlinkConfig::Standard).await;
+    let editor = Keypair::new();
+    let new_owner = Keypair::new();
+
+    let set_editor_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::SetBlinkEditor {
+            blink,
+            owner,
+            protocol_config: find_protocol_config_address().0,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::SetBlinkEditor {
+            editor: editor.pubkey(),
+            permissions: PERMISSION_PAUSE,
+        }
+        .data(),
+    };
+    let pause_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::ManageBlink {
+            blink,
+            authority: editor.pubkey(),
+            protocol_config: find_protocol_config_address().0,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::SetBlinkPaused { paused: true }.data(),
+    };
+    let logs = process_with_logs(&mut context, &[set_editor_ix, pause_ix], &[&editor]).await;
+
+    let account: Blink = fetch(&mut context, blink).await;
+    assert!(account.paused);
+
+    let editor_set = events::<BlinkEditorSet>(&logs);
+    assert_eq!(editor_set.len(), 1);
+    assert_eq!(editor_set[0].blink, blink);
+    assert_eq!(editor_set[0].owner, owner);
+    assert_eq!(editor_set[0].editor, editor.pubkey());
+    assert_eq!(editor_set[0].permissions, PERMISSION_PAUSE);
+    let pause_set = events::<BlinkPauseSet>(&logs);
+    assert_eq!(pause_set.len(), 1);
+    assert_eq!(pause_set[0].authority, editor.pubkey());
+    assert!(pause_set[0].paused);
+
+    let propose_ix = |blink_owner: Pubkey, new_owner: Pubkey| Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::TransferBlinkOwnership {
+            blink,
+            owner: blink_owner,
+            protocol_config: find_protocol_config_address().0,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::TransferBlinkOwnership {
+            new_owner: Some(new_owner),
+        }
+        .data(),
+    };
+    let accept_ix = |blink_owner: Pubkey, new_owner: Pubkey| Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::AcceptBlinkOwnership {
+            blink,
+            new_owner,
+            protocol_config: find_protocol_config_address().0,
+            handle_account: None,
+            previous_registry: find_owner_registry_address(&blink_owner).0,
+            new_registry: find_owner_registry_address(&new_owner).0,
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::AcceptBlinkOwnership {}.data(),
+    };
+    // The new owner pays for their registry
+    let fund_ix = system_instruction::transfer(&owner, &new_owner.pubkey(), 1_000_000_000);
+    let logs = process_with_logs(
+        &mut context,
+        &[
+            fund_ix,
+            propose_ix(owner, new_owner.pubkey()),
+            accept_ix(owner, new_owner.pubkey()),
+        ],
+        &[&new_owner],
+    )
+    .await;
+
+    let account: Blink = fetch(&mut context, blink).await;
+    assert_eq!(account.owner, new_owner.pubkey());
+    assert!(account.pending_owner.is_none());
+    assert!(account.editors.is_empty());
+
+    let proposed = events::<BlinkOwnershipProposed>(&logs);
+    assert_eq!(proposed.len(), 1);
+    assert_eq!(proposed[0].blink, blink);
+    assert_eq!(proposed[0].owner, owner);
+    assert_eq!(proposed[0].pending_owner, Some(new_owner.pubkey()));
+    let transferred = events::<BlinkOwnershipTransferred>(&logs);
+    assert_eq!(transferred.len(), 1);
+    assert_eq!(transferred[0].previous_owner, owner);
+    assert_eq!(transferred[0].new_owner, new_owner.pubkey());
+
+    // The blink moves to the new owner's registry while keeping its address
+    let (registry, _) = find_owner_registry_address(&owner);
+    let (new_registry, _) = find_owner_registry_address(&new_owner.pubkey());
+    let registry_account: OwnerRegistry = fetch(&mut context, registry).await;
+    assert!(registry_account.blink_addresses(&registry).is_empty());
+    assert_eq!(registry_account.active_count, 0);
+    let registry_account: OwnerRegistry = fetch(&mut context, new_registry).await;
+    assert_eq!(registry_account.owner, new_owner.pubkey());
+    assert_eq!(registry_account.blink_addresses(&new_registry), vec![blink]);
+    assert_eq!(registry_account.active_count, 1);
+
+    let self_transfer_ix = propose_ix(new_owner.pubkey(), new_owner.pubkey());
+    let result = try_process(&mut context, &[self_transfer_ix], &[&new_owner]).await;
+    assert_blink_error(result, BlinkError::InvalidRecipient);
+
+    // Handing it back restores the creator's registry
+    process(
+        &mut context,
+        &[
+            propose_ix(new_owner.pubkey(), owner),
+            accept_ix(new_owner.pubkey(), owner),
+        ],
+        &[&new_owner],
+    )
+    .await;
+
+    let registry_account: OwnerRegistry = fetch(&mut context, registry).await;
+    assert_eq!(registry_account.blink_addresses(&registry), vec![blink]);
+    assert!(registry_account.transferred.is_empty());
+    assert_eq!(registry_account.active_count, 1);
+    let registry_account: OwnerRegistry = fetch(&mut context, new_registry).await;
+    assert!(registry_account.blink_addresses(&new_registry).is_empty());
+    assert_eq!(registry_account.active_count, 0);
+}
+
+#[tokio::test]
+async fn registries_release_blinks_deleted_after_transfer() {
+    let mut context = program_test().start_with_context().await;
+    let owner = context.payer.pubkey();
+    let first_owner = Keypair::new();
+    let second_owner = Keypair::new();
+    let (registry, _) = find_owner_registry_address(&owner);
+    let (first_registry, _) = find_owner_registry_address(&first_owner.pubkey());
+    let (second_registry, _) = find_owner_registry_address(&second_owner.pubkey());
+    let fund_ixs = [
+        system_instruction::transfer(&owner, &first_owner.pubkey(), 1_000_000_000),
+        system_instruction::transfer(&owner, &second_owner.pubkey(), 1_000_000_000),
+    ];
+    process(&mut context, &fund_ixs, &[]).await;
+
+    let propose_ix = |blink: Pubkey, blink_owner: Pubkey, new_owner: Pubkey| Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::TransferBlinkOwnership {
+            blink,
+            owner: blink_owner,
+            protocol_config: find_protocol_config_address().0,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::TransferBlinkOwnership {
+            new_owner: Some(new_owner),
+        }
+        .data(),
+    };
+    let accept_ix = |blink: Pubkey, blink_owner: Pubkey, new_owner: Pubkey| Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::AcceptBlinkOwnership {
+            blink,
+            new_owner,
+            protocol_config: find_protocol_config_address().0,
+            handle_account: None,
+            previous_registry: find_owner_registry_address(&blink_owner).0,
+            new_registry: find_owner_registry_address(&new_owner).0,
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::AcceptBlinkOwnership {}.data(),
+    };
+    let delete_ix = |blink: Pubkey, creator_registry: Option<Pubkey>| Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::DeleteBlink {
+            blink,
+            registry: second_registry,
+            owner: second_owner.pubkey(),
+            handle_account: None,
+            attributes: find_blink_attributes_address(&blink).0,
+            creator_registry,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::DeleteBlink {}.data(),
+    };
+
+    // Each blink is handed on twice and deleted by its last owner. Entries are dropped as
+    // blinks leave, so neither list ever fills, however many blinks pass through.
+    let (first, second) = (first_owner.pubkey(), second_owner.pubkey());
+    for index in 0..=MAX_TRANSFERRED_BLINKS {
+        // Enough slots pass over this many transactions for the first blockhash to expire
+        context.last_blockhash = context.banks_client.get_latest_blockhash().await.unwrap();
+        let blink = create_blink(&mut context, BlinkType::Standard, BlinkConfig::Standard).await;
+        let hand_on_ixs = [
+            propose_ix(blink, owner, first),
+            accept_ix(blink, owner, first),
+            propose_ix(blink, first, second),
+            accept_ix(blink, first, second),
+        ];
+        process(&mut context, &hand_on_ixs, &[&first_owner, &second_owner]).await;
+
+        if index == 0 {
+            // The creator's registry must be passed so it stops listing the blink
+            let result =
+                try_process(&mut context, &[delete_ix(blink, None)], &[&second_owner]).await;
+            assert_blink_error(result, BlinkError::InvalidRegistryAccount);
+        }
+        process(&mut context, &[delete_ix(blink, Some(registry))], &[&second_owner]).await;
+    }
+
+    let registry_account: OwnerRegistry = fetch(&mut context, registry).await;
+    assert!(registry_account.transferred.is_empty());
+    assert_eq!(registry_account.blink_count, MAX_TRANSFERRED_BLINKS as u64 + 1);
+    assert_eq!(registry_account.active_count, 0);
+    for registry in [first_registry, second_registry] {
+        let registry_account: OwnerRegistry = fetch(&mut context, registry).await;
+        assert!(registry_account.received.is_empty());
+        assert_eq!(registry_account.active_count, 0);
+    }
+}
+
+#[tokio::test]
+async fn blink_attributes_and_abilities() {
+    let mut context = program_test().start_with_context().await;
+    let owner = context.payer.pubkey();
+    let blink = create_blink(&mut context, BlinkType::Standard, BlinkConfig::Standard).await;
+    let (attributes, _) = find_blink_attributes_address(&blink);
+    let accounts = bark_blinks::accounts::ManageBlinkAttributes {
+        blink,
+        attributes,
+        authority: owner,
+        owner,
+        system_program: system_program::id(),
+        protocol_config: find_protocol_config_address().0,
+    };
+
+    let add_attribute_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: accounts.to_account_metas(None),
+        data: bark_blinks::instruction::AddBlinkAttribute {
+            trait_type: "Strength".to_string(),
+            value: "High".to_string(),
+            numeric_value: Some(80),
+        }
+        .data(),
+    };
+    let add_ability_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: accounts.to_account_metas(None),
+        data: bark_blinks::instruction::AddBlinkAbility {
+            name: "Fetch".to_string(),
+            description: "Brings back a random reward".to_string(),
+            cooldown: 3600,
+            power_cost: 10,
+        }
+        .data(),
+    };
+    let update_attribute_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: accounts.to_account_metas(None),
+        data: bark_blinks::instruction::UpdateBlinkAttribute {
+            trait_type: "Strength".to_string(),
+            value: "Legendary".to_string(),
+            numeric_value: Some(99),
+        }
+        .data(),
+    };
+    process(
+        &mut context,
+        &[add_attribute_ix, add_ability_ix, update_attribute_ix],
+        &[],
+    )
+    .await;
+
+    let account: BlinkAttributes = fetch(&mut context, attributes).await;
+    assert_eq!(account.blink, blink);
+    let strength = account.attribute("Strength").unwrap();
+    assert_eq!(strength.value, "Legendary");
+    assert_eq!(strength.numeric_value, Some(99));
+    assert_eq!(account.ability("Fetch").unwrap().cooldown, 3600);
+
+    let raw = context.banks_client.get_account(attributes).await.unwrap().unwrap();
+    assert_eq!(raw.data.len(), account.space());
+
+    let remove_attribute_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: accounts.to_account_metas(None),
+        data: bark_blinks::instruction::RemoveBlinkAttribute {
+            trait_type: "Strength".to_string(),
+        }
+        .data(),
+    };
+    let remove_ability_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: accounts.to_account_metas(None),
+        data: bark_blinks::instruction::RemoveBlinkAbility {
+            name: "Fetch".to_string(),
+        }
+        .data(),
+    };
+    process(&mut context, &[remove_attribute_ix, remove_ability_ix], &[]).await;
+
+    let account: BlinkAttributes = fetch(&mut context, attributes).await;
+    assert!(account.attributes.is_empty());
+    assert!(account.abilities.is_empty());
+
+    let raw = context.banks_client.get_account(attributes).await.unwrap().unwrap();
+    assert_eq!(raw.data.len(), BlinkAttributes::EMPTY_LEN);
+
+    // Deleting the blink closes its attributes account with it
+    let (registry, _) = find_owner_registry_address(&owner);
+    let delete_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::DeleteBlink {
+            blink,
+            registry,
+            owner,
+            handle_account: None,
+            attributes,
+            creator_registry: None,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::DeleteBlink {}.data(),
+    };
+    process(&mut context, &[delete_ix], &[]).await;
+
+    assert!(context.banks_client.get_account(blink).await.unwrap().is_none());
+    assert!(context.banks_client.get_account(attributes).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn handle_registration_and_transfer() {
+    let mut context = program_test().start_with_context().await;
+    let owner = context.payer.pubkey();
+    let blink = create_blink(&mut context, BlinkType::Standard, BlinkConfig::Standard).await;
+    let other_blink = create_blink(&mut context, BlinkType::Standard, BlinkConfig::Standard).await;
+    let (handle_account, _) = find_handle_address("Bark-Dogs");
+
+    let register_ix = |blink: Pubkey, handle: &str| Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::RegisterHandle {
+            blink,
+            handle_account: find_handle_address(handle).0,
+            owner,
+            system_program: system_program::id(),
+            protocol_config: find_protocol_config_address().0,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::RegisterHandle {
+            handle: handle.to_string(),
+        }
+        .data(),
+    };
+    // Handles longer than a seed are rejected rather than aborting address derivation
+    let result = try_process(&mut context, &[register_ix(blink, &"a".repeat(40))], &[]).await;
+    assert_blink_error(result, BlinkError::InvalidHandle);
+
+    let logs = process_with_logs(&mut context, &[register_ix(blink, "bark-dogs")], &[]).await;
+
+    let account: BlinkHandle = fetch(&mut context, handle_account).await;
+    assert_eq!(account.handle, "bark-dogs");
+    assert_eq!(account.blink, blink);
+    let account: Blink = fetch(&mut context, blink).await;
+    assert_eq!(account.handle, Some(handle_account));
+
+    let registered = events::<HandleRegistered>(&logs);
+    assert_eq!(registered.len(), 1);
+    assert_eq!(registered[0].handle_account, handle_account);
+    assert_eq!(registered[0].handle, "bark-dogs");
+    assert_eq!(registered[0].blink, blink);
+    assert_eq!(registered[0].owner, owner);
+
+    let result = try_process(&mut context, &[register_ix(blink, "bark-cats")], &[]).await;
+    assert_blink_error(result, BlinkError::BlinkHasHandle);
+
+    let transfer_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::TransferHandle {
+            handle_account,
+            blink,
+            owner,
+            new_blink: other_blink,
+            new_owner: owner,
+            protocol_config: find_protocol_config_address().0,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::TransferHandle {}.data(),
+    };
+    let logs = process_with_logs(&mut context, &[transfer_ix], &[]).await;
+
+    let account: BlinkHandle = fetch(&mut context, handle_account).await;
+    assert_eq!(account.blink, other_blink);
+    let transferred = events::<HandleTransferred>(&logs);
+    assert_eq!(transferred.len(), 1);
+    assert_eq!(transferred[0].previous_blink, blink);
+    assert_eq!(transferred[0].blink, other_blink);
+    assert_eq!(transferred[0].owner, owner);
+    let account: Blink = fetch(&mut context, blink).await;
+    assert_eq!(account.handle, None);
+    let account: Blink = fetch(&mut context, other_blink).await;
+    assert_eq!(account.handle, Some(handle_account));
+
+    let release_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::ReleaseHandle {
+            handle_account,
+            blink: other_blink,
+            owner,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::ReleaseHandle {}.data(),
+    };
+    let logs = process_with_logs(&mut context, &[release_ix], &[]).await;
+
+    let released = context.banks_client.get_account(handle_account).await.unwrap();
+    assert!(released.is_none());
+    let released = events::<HandleReleased>(&logs);
+    assert_eq!(released.len(), 1);
+    assert_eq!(released[0].handle, "bark-dogs");
+    assert_eq!(released[0].blink, other_blink);
+    let account: Blink = fetch(&mut context, other_blink).await;
+    assert_eq!(account.handle, None);
+}
+
+#[tokio::test]
+async fn handle_follows_blink_owner_and_is_released_on_delete() {
+    let mut context = program_test().start_with_context().await;
+    let owner = context.payer.pubkey();
+    let blink = create_blink(&mut context, BlinkType::Standard, BlinkConfig::Standard).await;
+    let (registry, _) = find_owner_registry_address(&owner);
+    let (handle_account, _) = find_handle_address("bark-dogs");
+    let new_owner = Keypair::new();
+
+    let register_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::RegisterHandle {
+            blink,
+            handle_account,
+            owner,
+            system_program: system_program::id(),
+            protocol_config: find_protocol_config_address().0,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::RegisterHandle {
+            handle: "bark-dogs".to_string(),
+        }
+        .data(),
+    };
+    let propose_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::TransferBlinkOwnership {
+            blink,
+            owner,
+            protocol_config: find_protocol_config_address().0,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::TransferBlinkOwnership {
+            new_owner: Some(new_owner.pubkey()),
+        }
+        .data(),
+    };
+    let fund_ix = system_instruction::transfer(&owner, &new_owner.pubkey(), 1_000_000_000);
+    process(&mut context, &[register_ix, propose_ix, fund_ix], &[]).await;
+
+    let accept_ix = |handle_account: Option<Pubkey>| Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::AcceptBlinkOwnership {
+            blink,
+            new_owner: new_owner.pubkey(),
+            protocol_config: find_protocol_config_address().0,
+            handle_account,
+            previous_registry: registry,
+            new_registry: find_owner_registry_address(&new_owner.pubkey()).0,
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::AcceptBlinkOwnership {}.data(),
+    };
+    // The handle can't be left behind with the previous owner
+    let result = try_process(&mut context, &[accept_ix(None)], &[&new_owner]).await;
+    assert_blink_error(result, BlinkError::InvalidHandleAccount);
+    process(&mut context, &[accept_ix(Some(handle_account))], &[&new_owner]).await;
+
+    let account: BlinkHandle = fetch(&mut context, handle_account).await;
+    assert_eq!(account.owner, new_owner.pubkey());
+    assert_eq!(account.blink, blink);
+
+    let delete_ix = |handle_account: Option<Pubkey>| Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::DeleteBlink {
+            blink,
+            registry: find_owner_registry_address(&new_owner.pubkey()).0,
+            owner: new_owner.pubkey(),
+            handle_account,
+            attributes: find_blink_attributes_address(&blink).0,
+            creator_registry: Some(registry),
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::DeleteBlink {}.data(),
+    };
+    let result = try_process(&mut context, &[delete_ix(None)], &[&new_owner]).await;
+    assert_blink_error(result, BlinkError::InvalidHandleAccount);
+    process(&mut context, &[delete_ix(Some(handle_account))], &[&new_owner]).await;
+
+    assert!(context.banks_client.get_account(blink).await.unwrap().is_none());
+    let released = context.banks_client.get_account(handle_account).await.unwrap();
+    assert!(released.is_none());
+}
+
+#[tokio::test]
+async fn moderation_freeze_appeal_and_clear() {
+    let moderator = Keypair::new();
+    let (moderators, _) = find_moderators_address();
+    let mut context = program_test_with_moderator(moderator.pubkey())
+        .start_with_context()
+        .await;
+    let owner = context.payer.pubkey();
+    let blink = create_blink(&mut context, BlinkType::Standard, BlinkConfig::Standard).await;
+    let moderate_accounts = bark_blinks::accounts::ModerateBlink {
+        blink,
+        moderators,
+        moderator: moderator.pubkey(),
+    };
+
+    let freeze_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: moderate_accounts.to_account_metas(None),
+        data: bark_blinks::instruction::ModerateBlink {
+            status: ModerationStatus::Frozen,
+            reason: 1,
+        }
+        .data(),
+    };
+    let appeal_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::AppealModeration { blink, owner }.to_account_metas(None),
+        data: bark_blinks::instruction::AppealModeration {}.data(),
+    };
+    process(&mut context, &[freeze_ix, appeal_ix], &[&moderator]).await;
+
+    let account: Blink = fetch(&mut context, blink).await;
+    let moderation = account.moderation.clone().unwrap();
+    assert!(moderation.status == ModerationStatus::Frozen);
+    assert_eq!(moderation.reason, 1);
+    assert!(moderation.appealed);
+    assert!(account.check_not_frozen().is_err());
+
+    let clear_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: moderate_accounts.to_account_metas(None),
+        data: bark_blinks::instruction::ClearModeration {}.data(),
+    };
+    process(&mut context, &[clear_ix], &[&moderator]).await;
+
+    let account: Blink = fetch(&mut context, blink).await;
+    assert!(account.moderation.is_none());
+}
+
+#[tokio::test]
+async fn frozen_blink_with_vault_can_be_deleted() {
+    let moderator = Keypair::new();
+    let (moderators, _) = find_moderators_address();
+    let mut context = program_test_with_moderator(moderator.pubkey())
+        .start_with_context()
+        .await;
+    let owner = context.payer.pubkey();
+
+    let mint = Keypair::new();
+    create_mint_account(&mut context, &mint).await;
+    let owner_token_account = create_token_account(&mut context, &mint.pubkey(), &owner, 0).await;
+    let blink = create_blink(&mut context, BlinkType::Standard, BlinkConfig::Standard).await;
+    let (vault, _) = find_blink_vault_address(&blink, &mint.pubkey());
+
+    let open_vault_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::OpenBlinkVault {
+            blink,
+            vault,
+            mint: mint.pubkey(),
+            payer: owner,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            rent: sysvar::rent::id(),
+            protocol_config: find_protocol_config_address().0,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::OpenBlinkVault {}.data(),
+    };
+    let freeze_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::ModerateBlink {
+            blink,
+            moderators,
+            moderator: moderator.pubkey(),
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::ModerateBlink {
+            status: ModerationStatus::Frozen,
+            reason: 1,
+        }
+        .data(),
+    };
+    process(&mut context, &[open_vault_ix, freeze_ix], &[&moderator]).await;
+
+    let close_vault_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::CloseBlinkVault {
+            blink,
+            vault,
+            destination: owner_token_account,
+            authority: owner,
+            owner,
+            token_program: spl_token::id(),
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::CloseBlinkVault {}.data(),
+    };
+    let delete_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::DeleteBlink {
+            blink,
+            registry: find_owner_registry_address(&owner).0,
+            owner,
+            handle_account: None,
+            attributes: find_blink_attributes_address(&blink).0,
+            creator_registry: None,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::DeleteBlink {}.data(),
+    };
+    process(&mut context, &[close_vault_ix, delete_ix], &[]).await;
+
+    assert!(context.banks_client.get_account(vault).await.unwrap().is_none());
+    assert!(context.banks_client.get_account(blink).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn blink_from_template_pays_author() {
+    let mut context = program_test().start_with_context().await;
+    let payer = context.payer.pubkey();
+    let author = Keypair::new();
+    let (template, _) = find_template_address(&author.pubkey(), 7);
+    let (registry, _) = find_owner_registry_address(&payer);
+    let (blink, _) = find_blink_address(&registry, 0);
+
+    let fund_ix = system_instruction::transfer(&payer, &author.pubkey(), 1_000_000_000);
+    let create_template_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::CreateBlinkTemplate {
+            template,
+            author: author.pubkey(),
+            system_program: system_program::id(),
+            protocol_config: find_protocol_config_address().0,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::CreateBlinkTemplate {
+            seed: 7,
+            name: "Tip jar".to_string(),
+            description: "Send a tip".to_string(),
+            image_url: "https://example.com/tip.png".to_string(),
+            config: BlinkConfig::Standard,
+            fee_lamports: 5_000,
+        }
+        .data(),
+    };
+    let logs = process_with_logs(&mut context, &[fund_ix, create_template_ix], &[&author]).await;
+
+    let created = events::<TemplateCreated>(&logs);
+    assert_eq!(created.len(), 1);
+    assert_eq!(created[0].template, template);
+    assert_eq!(created[0].author, author.pubkey());
+    assert_eq!(created[0].seed, 7);
+    assert!(created[0].blink_type == BlinkType::Standard);
+    assert_eq!(created[0].fee_lamports, 5_000);
+
+    let author_before = context.banks_client.get_balance(author.pubkey()).await.unwrap();
+    let create_ix = |max_fee_lamports: u64| Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::CreateBlinkFromTemplate {
+            template,
+            author: author.pubkey(),
+            registry,
+            blink,
+            owner: payer,
+            system_program: system_program::id(),
+            protocol_config: find_protocol_config_address().0,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::CreateBlinkFromTemplate {
+            name: Some("My tip jar".to_string()),
+            config: None,
+            max_fee_lamports,
+        }
+        .data(),
+    };
+
+    // The author raised the fee after the creator signed for the old one
+    let raise_fee_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::SetTemplateFee {
+            template,
+            author: author.pubkey(),
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::SetTemplateFee {
+            fee_lamports: 6_000,
+        }
+        .data(),
+    };
+    let logs = process_with_logs(&mut context, &[raise_fee_ix], &[&author]).await;
+    let fee_set = events::<TemplateFeeSet>(&logs);
+    assert_eq!(fee_set.len(), 1);
+    assert_eq!(fee_set[0].template, template);
+    assert_eq!(fee_set[0].fee_lamports, 6_000);
+
+    let result = try_process(&mut context, &[create_ix(5_000)], &[]).await;
+    assert_blink_error(result, BlinkError::FeeTooHigh);
+
+    process(&mut context, &[create_ix(6_000)], &[]).await;
+
+    let account: Blink = fetch(&mut context, blink).await;
+    assert_eq!(account.owner, payer);
+    assert_eq!(account.name, "My tip jar");
+    assert_eq!(account.description, "Send a tip");
+    assert_eq!(account.template, Some(template));
+
+    let template_account: BlinkTemplate = fetch(&mut context, template).await;
+    assert_eq!(template_account.use_count, 1);
+
+    let author_after = context.banks_client.get_balance(author.pubkey()).await.unwrap();
+    assert_eq!(author_after, author_before + 6_000);
+
+    let close_template_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::CloseBlinkTemplate {
+            template,
+            author: author.pubkey(),
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::CloseBlinkTemplate {}.data(),
+    };
+    let logs = process_with_logs(&mut context, &[close_template_ix], &[&author]).await;
+    let closed = events::<TemplateClosed>(&logs);
+    assert_eq!(closed.len(), 1);
+    assert_eq!(closed[0].template, template);
+    assert!(context.banks_client.get_account(template).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn batch_create_update_and_delete() {
+    let mut context = program_test().start_with_context().await;
+    let owner = context.payer.pubkey();
+    let (registry, _) = find_owner_registry_address(&owner);
+    let blinks: Vec<Pubkey> = (0..3).map(|index| find_blink_address(&registry, index).0).collect();
+    let writable = |blinks: &[Pubkey]| -> Vec<AccountMeta> {
+        blinks.iter().map(|blink| AccountMeta::new(*blink, false)).collect()
+    };
+
+    let mut accounts = bark_blinks::accounts::BatchCreateBlinks {
+        registry,
+        owner,
+        system_program: system_program::id(),
+        protocol_config: find_protocol_config_address().0,
+    }
+    .to_account_metas(None);
+    accounts.extend(writable(&blinks));
+    let create_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts,
+        data: bark_blinks::instruction::BatchCreateBlinks {
+            items: (0..3)
+                .map(|index| BlinkInput {
+                    name: format!("Campaign {}", index),
+                    description: "Campaign blink".to_string(),
+                    blink_type: BlinkType::Standard,
+                    image_url: "https://example.com/campaign.png".to_string(),
+                    config: BlinkConfig::Standard,
+                })
+                .collect(),
+        }
+        .data(),
+    };
+    process(&mut context, &[create_ix], &[]).await;
+
+    let registry_account: OwnerRegistry = fetch(&mut context, registry).await;
+    assert_eq!(registry_account.blink_count, 3);
+    let account: Blink = fetch(&mut context, blinks[2]).await;
+    assert_eq!(account.name, "Campaign 2");
+    assert_eq!(account.index, 2);
+
+    let mut accounts = bark_blinks::accounts::BatchUpdateBlinks {
+        authority: owner,
+        owner,
+        system_program: system_program::id(),
+        protocol_config: find_protocol_config_address().0,
+    }
+    .to_account_metas(None);
+    accounts.extend(writable(&blinks[..2]));
+    let update_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts,
+        data: bark_blinks::instruction::BatchUpdateBlinks {
+            items: (0..2)
+                .map(|index| BlinkUpdate {
+                    name: format!("Updated campaign {}", index),
+                    description: "Updated campaign blink".to_string(),
+                    image_url: "https://example.com/campaign.png".to_string(),
+                    config: None,
+                })
+                .collect(),
+        }
+        .data(),
+    };
+    process(&mut context, &[update_ix], &[]).await;
+
+    let account: Blink = fetch(&mut context, blinks[1]).await;
+    assert_eq!(account.name, "Updated campaign 1");
+
+    let mut accounts =
+        bark_blinks::accounts::BatchDeleteBlinks { registry, owner }.to_account_metas(None);
+    for blink in &blinks {
+        let (attributes, _) = find_blink_attributes_address(blink);
+        accounts.extend(writable(&[*blink, attributes]));
+    }
+    let delete_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts,
+        data: bark_blinks::instruction::BatchDeleteBlinks {}.data(),
+    };
+    process(&mut context, &[delete_ix], &[]).await;
+
+    for blink in blinks {
+        assert!(context.banks_client.get_account(blink).await.unwrap().is_none());
+    }
+    let registry_account: OwnerRegistry = fetch(&mut context, registry).await;
+    assert_eq!(registry_account.active_count, 0);
+}
+
+#[tokio::test]
+async fn batch_create_rolls_back_on_bad_item() {
+    let mut context = program_test().start_with_context().await;
+    let owner = context.payer.pubkey();
+    let (registry, _) = find_owner_registry_address(&owner);
+    let blinks: Vec<Pubkey> = (0..3).map(|index| find_blink_address(&registry, index).0).collect();
+
+    // Lamports sent to a blink address ahead of time must not block its creation
+    let prefund_ix = system_instruction::transfer(&owner, &blinks[0], 1_000_000);
+    process(&mut context, &[prefund_ix], &[]).await;
+
+    let create_ix = |items: Vec<BlinkInput>| {
+        let mut accounts = bark_blinks::accounts::BatchCreateBlinks {
+            registry,
+            owner,
+            system_program: system_program::id(),
+            protocol_config: find_protocol_config_address().0,
+        }
+        .to_account_metas(None);
+        accounts.extend(blinks.iter().map(|blink| AccountMeta::new(*blink, false)));
+        Instruction {
+            program_id: bark_blinks::id(),
+            accounts,
+            data: bark_blinks::instruction::BatchCreateBlinks { items }.data(),
+        }
+    };
+    let item = |index: usize, blink_type: BlinkType| BlinkInput {
+        name: format!("Campaign {}", index),
+        description: "Campaign blink".to_string(),
+        blink_type,
+        image_url: "https://example.com/campaign.png".to_string(),
+        config: BlinkConfig::Standard,
+    };
+
+    // The second item's type doesn't match its config
+    let items = vec![
+        item(0, BlinkType::Standard),
+        item(1, BlinkType::Donation),
+        item(2, BlinkType::Standard),
+    ];
+    let (result, logs) = try_process_with_logs(&mut context, &[create_ix(items)], &[]).await;
+    assert_blink_error(result, BlinkError::InvalidBlinkType);
+    let index_logged = logs.windows(2).any(|pair| {
+        pair[0] == format!("Program log: Left: {}", BATCH_ITEM_LABEL)
+            && pair[1] == "Program log: Right: 1"
+    });
+    assert!(index_logged);
+
+    // Nothing from the batch survives, including the item before the bad one
+    let first = get_account(&mut context, blinks[0]).await;
+    assert_eq!(first.owner, system_program::id());
+    assert!(first.data.is_empty());
+    assert!(context.banks_client.get_account(registry).await.unwrap().is_none());
+
+    let items = (0..3).map(|index| item(index, BlinkType::Standard)).collect();
+    process(&mut context, &[create_ix(items)], &[]).await;
+
+    let registry_account: OwnerRegistry = fetch(&mut context, registry).await;
+    assert_eq!(registry_account.blink_count, 3);
+    let account: Blink = fetch(&mut context, blinks[0]).await;
+    assert_eq!(account.name, "Campaign 0");
+}
+
+#[tokio::test]
+async fn blink_content_hash_verification() {
+    let mut context = program_test().start_with_context().await;
+    let owner = context.payer.pubkey();
+    let blink = create_blink(&mut context, BlinkType::Standard, BlinkConfig::Standard).await;
+    let image = b"<svg>bark</svg>".to_vec();
+
+    let set_hash_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::ManageBlink {
+            blink,
+            authority: owner,
+            protocol_config: find_protocol_config_address().0,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::SetBlinkImageHash {
+            image_hash: Some(hash(&image).to_bytes()),
+        }
+        .data(),
+    };
+    let verify_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::VerifyBlinkContent { blink }.to_account_metas(None),
+        data: bark_blinks::instruction::VerifyBlinkContent { data: image }.data(),
+    };
+    process(&mut context, &[set_hash_ix, verify_ix], &[]).await;
+
+    let account: Blink = fetch(&mut context, blink).await;
+    assert!(account.image_hash.is_some());
+}
+
+#[tokio::test]
+async fn token_gated_execution() {
+    let mut context = program_test().start_with_context().await;
+    let owner = context.payer.pubkey();
+    let merchant = Pubkey::new_unique();
+
+    let mint = Keypair::new();
+    let gate_mint = Keypair::new();
+    create_mint_account(&mut context, &mint).await;
+    create_mint_account(&mut context, &gate_mint).await;
+    let owner_token_account =
+        create_token_account(&mut context, &mint.pubkey(), &owner, 1_000).await;
+    let merchant_token_account =
+        create_token_account(&mut context, &mint.pubkey(), &merchant, 0).await;
+    let small_gate_account =
+        create_token_account(&mut context, &gate_mint.pubkey(), &owner, 5).await;
+    let gate_account = create_token_account(&mut context, &gate_mint.pubkey(), &owner, 20).await;
+
+    let blink = create_blink(
+        &mut context,
+        BlinkType::Payment,
+        BlinkConfig::Payment {
+            recipient: merchant,
+            mint: mint.pubkey(),
+            price: 75,
+        },
+    )
+    .await;
+
+    let gate_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::ManageBlink {
+            blink,
+            authority: owner,
+            protocol_config: find_protocol_config_address().0,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::SetBlinkGate {
+            gate: Some(TokenGate::MinBalance {
+                mint: gate_mint.pubkey(),
+                amount: 10,
+            }),
+        }
+        .data(),
+    };
+    let logs = process_with_logs(&mut context, &[gate_ix], &[]).await;
+    let gate_set = events::<BlinkGateSet>(&logs);
+    assert_eq!(gate_set.len(), 1);
+    assert_eq!(gate_set[0].blink, blink);
+    assert_eq!(gate_set[0].authority, owner);
+    assert!(
+        gate_set[0].gate
+            == Some(TokenGate::MinBalance {
+                mint: gate_mint.pubkey(),
+                amount: 10,
+            })
+    );
+
+    // The gate proof comes before the payment accounts
+    let execute_ix = |proof: Pubkey| {
+        let mut accounts = bark_blinks::accounts::ExecuteBlink {
+            blink,
+            executor: owner,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            history: None,
+            protocol_config: find_protocol_config_address().0,
+            treasury_token_account: None,
+        }
+        .to_account_metas(None);
+        accounts.push(AccountMeta::new_readonly(proof, false));
+        accounts.push(AccountMeta::new(owner_token_account, false));
+        accounts.push(AccountMeta::new(merchant_token_account, false));
+        Instruction {
+            program_id: bark_blinks::id(),
+            accounts,
+            data: bark_blinks::instruction::ExecuteBlink {
+                args: ExecuteBlinkArgs::default(),
+            }
+            .data(),
+        }
+    };
+
+    // A balance below the minimum is rejected
+    let transaction = Transaction::new_signed_with_payer(
+        &[execute_ix(small_gate_account)],
+        Some(&owner),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    assert!(context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .is_err());
+
+    process(&mut context, &[execute_ix(gate_account)], &[]).await;
+
+    assert_eq!(token_balance(&mut context, merchant_token_account).await, 75);
+    let account: Blink = fetch(&mut context, blink).await;
+    assert_eq!(account.execution_count, 1);
+}
+
+#[tokio::test]
+async fn protocol_config_update_and_admin_handover() {
+    let admin = Keypair::new();
+    let new_admin = Keypair::new();
+    let treasury = Pubkey::new_unique();
+    let (protocol_config, _) = find_protocol_config_address();
+    let mut context = program_test_with_admin(admin.pubkey())
+        .start_with_context()
+        .await;
+
+    let fees = ProtocolFees {
+        donation_bps: 100,
+        payment_bps: 50,
+        swap_bps: 30,
+        mint_fee_lamports: 10_000,
+    };
+    let update_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::UpdateProtocolConfig {
+            protocol_config,
+            admin: admin.pubkey(),
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::UpdateProtocolConfig {
+            treasury,
+            fees: fees.clone(),
+            features: ProtocolFeatures::default(),
+        }
+        .data(),
+    };
+    let propose_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::UpdateProtocolConfig {
+            protocol_config,
+            admin: admin.pubkey(),
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::ProposeProtocolAdmin {
+            new_admin: Some(new_admin.pubkey()),
+        }
+        .data(),
+    };
+    let logs = process_with_logs(&mut context, &[update_ix, propose_ix], &[&admin]).await;
+
+    let account: ProtocolConfig = fetch(&mut context, protocol_config).await;
+    assert_eq!(account.treasury, treasury);
+    assert!(account.fees == fees);
+    assert!(!account.features.blink_routing);
+    assert_eq!(account.admin, admin.pubkey());
+    assert_eq!(account.pending_admin, Some(new_admin.pubkey()));
+
+    let updated = events::<ProtocolConfigUpdated>(&logs);
+    assert_eq!(updated.len(), 1);
+    assert_eq!(updated[0].admin, admin.pubkey());
+    assert_eq!(updated[0].treasury, treasury);
+    assert!(updated[0].fees == fees);
+    let proposed = events::<ProtocolAdminProposed>(&logs);
+    assert_eq!(proposed.len(), 1);
+    assert_eq!(proposed[0].admin, admin.pubkey());
+    assert_eq!(proposed[0].pending_admin, Some(new_admin.pubkey()));
+
+    let accept_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::AcceptProtocolAdmin {
+            protocol_config,
+            new_admin: new_admin.pubkey(),
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::AcceptProtocolAdmin {}.data(),
+    };
+    let logs = process_with_logs(&mut context, &[accept_ix], &[&new_admin]).await;
+
+    let account: ProtocolConfig = fetch(&mut context, protocol_config).await;
+    assert_eq!(account.admin, new_admin.pubkey());
+    assert!(account.pending_admin.is_none());
+
+    let transferred = events::<ProtocolAdminTransferred>(&logs);
+    assert_eq!(transferred.len(), 1);
+    assert_eq!(transferred[0].previous_admin, admin.pubkey());
+    assert_eq!(transferred[0].new_admin, new_admin.pubkey());
+}
+
+#[tokio::test]
+async fn protocol_fees_rejected_above_caps() {
+    let admin = Keypair::new();
+    let (protocol_config, _) = find_protocol_config_address();
+    let mut context = program_test_with_admin(admin.pubkey())
+        .start_with_context()
+        .await;
+
+    let update_ix = |fees: ProtocolFees| Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::UpdateProtocolConfig {
+            protocol_config,
+            admin: admin.pubkey(),
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::UpdateProtocolConfig {
+            treasury: Pubkey::new_unique(),
+            fees,
+            features: ProtocolFeatures::default(),
+        }
+        .data(),
+    };
+
+    let over_cap = ProtocolFees {
+        donation_bps: MAX_DONATION_FEE_BPS + 1,
+        payment_bps: 0,
+        swap_bps: 0,
+        mint_fee_lamports: 0,
+    };
+    let result = try_process(&mut context, &[update_ix(over_cap)], &[&admin]).await;
+    assert_blink_error(result, BlinkError::FeeTooHigh);
+
+    let over_denominator = ProtocolFees {
+        donation_bps: 0,
+        payment_bps: 0,
+        swap_bps: BPS_DENOMINATOR + 1,
+        mint_fee_lamports: 0,
+    };
+    let result = try_process(&mut context, &[update_ix(over_denominator)], &[&admin]).await;
+    assert_blink_error(result, BlinkError::InvalidFeeBasisPoints);
+}
+
+#[tokio::test]
+async fn protocol_config_initialized_by_upgrade_authority() {
+    let upgrade_authority = Keypair::new();
+    let admin = Pubkey::new_unique();
+    let treasury = Pubkey::new_unique();
+    let (protocol_config, _) = find_protocol_config_address();
+    let (program_data, _) = Pubkey::find_program_address(
+        &[bark_blinks::id().as_ref()],
+        &bpf_loader_upgradeable::id(),
+    );
+    let mut context = program_test_with_upgrade_authority(upgrade_authority.pubkey())
+        .start_with_context()
+        .await;
+
+    let initialize_ix = |authority: Pubkey| Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::InitializeProtocolConfig {
+            protocol_config,
+            authority,
+            program: bark_blinks::id(),
+            program_data,
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::InitializeProtocolConfig {
+            admin,
+            treasury,
+            fees: ProtocolFees::default(),
+            features: ProtocolFeatures {
+                blink_routing: true,
+                fee_collection: false,
+            },
+        }
+        .data(),
+    };
+
+    // Only the program's upgrade authority can create the config
+    let impostor = Keypair::new();
+    let fund_ix = system_instruction::transfer(
+        &context.payer.pubkey(),
+        &impostor.pubkey(),
+        1_000_000_000,
+    );
+    process(&mut context, &[fund_ix], &[]).await;
+    let result = try_process(&mut context, &[initialize_ix(impostor.pubkey())], &[&impostor]).await;
+    assert_blink_error(result, BlinkError::Unauthorized);
+
+    let authority_ix = initialize_ix(upgrade_authority.pubkey());
+    let logs = process_with_logs(&mut context, &[authority_ix], &[&upgrade_authority]).await;
+
+    let account: ProtocolConfig = fetch(&mut context, protocol_config).await;
+    assert_eq!(account.admin, admin);
+    assert_eq!(account.treasury, treasury);
+    assert!(account.pending_admin.is_none());
+    assert!(account.features.blink_routing);
+    assert!(!account.features.fee_collection);
+
+    let initialized = events::<ProtocolConfigUpdated>(&logs);
+    assert_eq!(initialized.len(), 1);
+    assert_eq!(initialized[0].admin, admin);
+    assert_eq!(initialized[0].treasury, treasury);
+    assert!(initialized[0].features.blink_routing);
+}
+
+#[tokio::test]
+async fn blink_routing_disabled_rejects_execute_into_vault() {
+    let admin = Keypair::new();
+    let (protocol_config, _) = find_protocol_config_address();
+    let mut context = program_test_with_admin(admin.pubkey())
+        .start_with_context()
+        .await;
+    let owner = context.payer.pubkey();
+
+    let mint = Keypair::new();
+    create_mint_account(&mut context, &mint).await;
+    let owner_token_account =
+        create_token_account(&mut context, &mint.pubkey(), &owner, 1_000).await;
+
+    // The blink's address is known before it exists, so it can be its own recipient
+    let (registry, _) = find_owner_registry_address(&owner);
+    let (blink, _) = find_blink_address(&registry, 0);
+    create_blink(
+        &mut context,
+        BlinkType::Donation,
+        BlinkConfig::Donation {
+            recipient: blink,
+            mint: mint.pubkey(),
+            suggested_amounts: vec![],
+        },
+    )
+    .await;
+    let (vault, _) = find_blink_vault_address(&blink, &mint.pubkey());
+    let open_vault_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::OpenBlinkVault {
+            blink,
+            vault,
+            mint: mint.pubkey(),
+            payer: owner,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            rent: sysvar::rent::id(),
+            protocol_config,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::OpenBlinkVault {}.data(),
+    };
+    let disable_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::UpdateProtocolConfig {
+            protocol_config,
+            admin: admin.pubkey(),
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::UpdateProtocolConfig {
+            treasury: admin.pubkey(),
+            fees: ProtocolFees::default(),
+            features: ProtocolFeatures::default(),
+        }
+        .data(),
+    };
+    process(&mut context, &[open_vault_ix, disable_ix], &[&admin]).await;
+
+    let mut accounts = bark_blinks::accounts::ExecuteBlink {
+        blink,
+        executor: owner,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        history: None,
+        protocol_config,
+        treasury_token_account: None,
+    }
+    .to_account_metas(None);
+    accounts.push(AccountMeta::new(owner_token_account, false));
+    accounts.push(AccountMeta::new(vault, false));
+    let execute_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts,
+        data: bark_blinks::instruction::ExecuteBlink {
+            args: ExecuteBlinkArgs {
+                amount: Some(50),
+                ..ExecuteBlinkArgs::default()
+            },
+        }
+        .data(),
+    };
+    let result = try_process(&mut context, &[execute_ix], &[]).await;
+    assert_blink_error(result, BlinkError::FeatureDisabled);
+    assert_eq!(token_balance(&mut context, vault).await, 0);
+}
+
+#[tokio::test]
+async fn donation_protocol_fee() {
+    let admin = Keypair::new();
+    let treasury = Pubkey::new_unique();
+    let (protocol_config, _) = find_protocol_config_address();
+    let mut context = program_test_with_admin(admin.pubkey())
+        .start_with_context()
+        .await;
+    let donor = context.payer.pubkey();
+    let recipient = Pubkey::new_unique();
+
+    let mint = Keypair::new();
+    create_mint_account(&mut context, &mint).await;
+    let donor_token_account =
+        create_token_account(&mut context, &mint.pubkey(), &donor, 1_000).await;
+    let recipient_token_account =
+        create_token_account(&mut context, &mint.pubkey(), &recipient, 0).await;
+    let treasury_token_account =
+        create_token_account(&mut context, &mint.pubkey(), &treasury, 0).await;
+
+    let update_ix = |donation_bps: u16| Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::UpdateProtocolConfig {
+            protocol_config,
+            admin: admin.pubkey(),
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::UpdateProtocolConfig {
+            treasury,
+            fees: ProtocolFees {
+                donation_bps,
+                ..ProtocolFees::default()
+            },
+            features: ProtocolFeatures {
+                blink_routing: true,
+                fee_collection: true,
+            },
+        }
+        .data(),
+    };
+
+    // Fees above the module cap are rejected
+    let transaction = Transaction::new_signed_with_payer(
+        &[update_ix(MAX_DONATION_FEE_BPS + 1)],
+        Some(&donor),
+        &[&context.payer, &admin],
+        context.last_blockhash,
+    );
+    assert!(context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .is_err());
+
+    process(&mut context, &[update_ix(100)], &[&admin]).await;
+
+    let donation = Keypair::new();
+    let donation_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::CreateDonation {
+            donation: donation.pubkey(),
+            donor,
+            recipient,
+            donor_token_account,
+            recipient_token_account,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            protocol_config,
+            treasury_token_account: Some(treasury_token_account),
+            blink: None,
+            history: None,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::CreateDonation {
+            amount: 1_000,
+            currency: "BARK".to_string(),
+            message: "Thanks".to_string(),
+        }
+        .data(),
+    };
+    process(&mut context, &[donation_ix], &[&donation]).await;
+
+    let account: Donation = fetch(&mut context, donation.pubkey()).await;
+    assert_eq!(account.amount, 1_000);
+    assert_eq!(account.fee, 10);
+    assert_eq!(token_balance(&mut context, recipient_token_account).await, 990);
+    assert_eq!(token_balance(&mut context, treasury_token_account).await, 10);
+}
+
+#[tokio::test]
+async fn paused_module_rejects_instructions() {
+    let admin = Keypair::new();
+    let (protocol_config, _) = find_protocol_config_address();
+    let mut context = program_test_with_admin(admin.pubkey())
+        .start_with_context()
+        .await;
+    let owner = context.payer.pubkey();
+
+    let pause_ix = |module: ProtocolModule, paused: bool| Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::UpdateProtocolConfig {
+            protocol_config,
+            admin: admin.pubkey(),
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::SetModulePaused { module, paused }.data(),
+    };
+    let create_collection_ix = |collection: &Keypair| Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::CreateCollection {
+            collection: collection.pubkey(),
+            owner,
+            system_program: system_program::id(),
+            protocol_config,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::CreateCollection {
+            name: "Paused".to_string(),
+            symbol: "PSD".to_string(),
+            uri: "https://example.com/collection.json".to_string(),
+        }
+        .data(),
+    };
+
+    process(&mut context, &[pause_ix(ProtocolModule::Collections, true)], &[&admin]).await;
+    let account: ProtocolConfig = fetch(&mut context, protocol_config).await;
+    assert!(account.is_paused(ProtocolModule::Collections));
+    assert!(!account.is_paused(ProtocolModule::Swaps));
+
+    let collection = Keypair::new();
+    let result = try_process(
+        &mut context,
+        &[create_collection_ix(&collection)],
+        &[&collection],
+    )
+    .await;
+    assert_blink_error(result, BlinkError::ModulePaused);
+
+    // Other modules keep working while one is paused
+    create_blink(&mut context, BlinkType::Standard, BlinkConfig::Standard).await;
+
+    process(&mut context, &[pause_ix(ProtocolModule::Collections, false)], &[&admin]).await;
+    // The retry is the same transaction, so it needs a fresh blockhash
+    context.last_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    process(&mut context, &[create_collection_ix(&collection)], &[&collection]).await;
+    let account: Collection = fetch(&mut context, collection.pubkey()).await;
+    assert_eq!(account.owner, owner);
+}
+
+#[tokio::test]
+async fn paused_blinks_module_rejects_blink_updates() {
+    let admin = Keypair::new();
+    let (protocol_config, _) = find_protocol_config_address();
+    let mut context = program_test_with_admin(admin.pubkey())
+        .start_with_context()
+        .await;
+    let owner = context.payer.pubkey();
+
+    let mint = Keypair::new();
+    create_mint_account(&mut context, &mint).await;
+    let owner_token_account =
+        create_token_account(&mut context, &mint.pubkey(), &owner, 1_000).await;
+
+    let poll_blink = create_blink(
+        &mut context,
+        BlinkType::Poll,
+        BlinkConfig::Poll {
+            options: vec!["Yes".to_string(), "No".to_string()],
+        },
+    )
+    .await;
+    let (poll, _) =
+        Pubkey::find_program_address(&[POLL_SEED, poll_blink.as_ref()], &bark_blinks::id());
+    let open_poll_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::OpenPoll {
+            blink: poll_blink,
+            poll,
+            owner,
+            system_program: system_program::id(),
+            protocol_config,
+            vote_mint: None,
+            vote_vault: None,
+            token_program: spl_token::id(),
+            rent: sysvar::rent::id(),
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::OpenPoll { closes_at: i64::MAX }.data(),
+    };
+    process(&mut context, &[open_poll_ix], &[]).await;
+
+    let donation_blink = create_blink(
+        &mut context,
+        BlinkType::Donation,
+        BlinkConfig::Donation {
+            recipient: Pubkey::new_unique(),
+            mint: mint.pubkey(),
+            suggested_amounts: vec![],
+        },
+    )
+    .await;
+    let (vault, _) = find_blink_vault_address(&donation_blink, &mint.pubkey());
+    let open_vault_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::OpenBlinkVault {
+            blink: donation_blink,
+            vault,
+            mint: mint.pubkey(),
+            payer: owner,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            rent: sysvar::rent::id(),
+            protocol_config,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::OpenBlinkVault {}.data(),
+    };
+    process(&mut context, &[open_vault_ix], &[]).await;
+
+    let pause_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::UpdateProtocolConfig {
+            protocol_config,
+            admin: admin.pubkey(),
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::SetModulePaused {
+            module: ProtocolModule::Blinks,
+            paused: true,
+        }
+        .data(),
+    };
+    process(&mut context, &[pause_ix], &[&admin]).await;
+
+    let (vote_receipt, _) = Pubkey::find_program_address(
+        &[VOTE_RECEIPT_SEED, poll.as_ref(), owner.as_ref()],
+        &bark_blinks::id(),
+    );
+    let vote_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::CastVote {
+            blink: poll_blink,
+            poll,
+            vote_receipt,
+            voter: owner,
+            voter_token_account: None,
+            system_program: system_program::id(),
+            history: None,
+            protocol_config,
+            vote_vault: None,
+            token_program: spl_token::id(),
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::CastVote { option: 0 }.data(),
+    };
+    let result = try_process(&mut context, &[vote_ix], &[]).await;
+    assert_blink_error(result, BlinkError::ModulePaused);
+
+    let gate_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::ManageBlink {
+            blink: donation_blink,
+            authority: owner,
+            protocol_config,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::SetBlinkGate { gate: None }.data(),
+    };
+    let result = try_process(&mut context, &[gate_ix], &[]).await;
+    assert_blink_error(result, BlinkError::ModulePaused);
+
+    let template_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::CreateBlinkTemplate {
+            template: find_template_address(&owner, 0).0,
+            author: owner,
+            system_program: system_program::id(),
+            protocol_config,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::CreateBlinkTemplate {
+            seed: 0,
+            name: "Tip jar".to_string(),
+            description: "Send a tip".to_string(),
+            image_url: String::new(),
+            config: BlinkConfig::Standard,
+            fee_lamports: 0,
+        }
+        .data(),
+    };
+    let result = try_process(&mut context, &[template_ix], &[]).await;
+    assert_blink_error(result, BlinkError::ModulePaused);
+
+    // Donations stay open, but not when they route into a blink
+    let donation = Keypair::new();
+    let donation_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::CreateDonation {
+            donation: donation.pubkey(),
+            donor: owner,
+            recipient: donation_blink,
+            donor_token_account: owner_token_account,
+            recipient_token_account: vault,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            protocol_config,
+            treasury_token_account: None,
+            blink: Some(donation_blink),
+            history: None,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::CreateDonation {
+            amount: 50,
+            currency: "BARK".to_string(),
+            message: "Tip".to_string(),
+        }
+        .data(),
+    };
+    let result = try_process(&mut context, &[donation_ix], &[&donation]).await;
+    assert_blink_error(result, BlinkError::ModulePaused);
+    assert_eq!(token_balance(&mut context, vault).await, 0);
+}
+
+#[tokio::test]
+async fn state_changes_emit_events() {
+    let mut context = program_test().start_with_context().await;
+    let owner = context.payer.pubkey();
+
+    let mint = Keypair::new();
+    create_mint_account(&mut context, &mint).await;
+    let owner_token_account =
+        create_token_account(&mut context, &mint.pubkey(), &owner, 1_000).await;
+
+    let (registry, _) = find_owner_registry_address(&owner);
+    let (blink, _) = find_blink_address(&registry, 0);
+    let config = BlinkConfig::Donation {
+        recipient: blink,
+        mint: mint.pubkey(),
+        suggested_amounts: vec![],
+    };
+    let create_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::CreateBlink {
+            registry,
+            blink,
+            owner,
+            system_program: system_program::id(),
+            protocol_config: find_protocol_config_address().0,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::CreateBlink {
+            name: "Tip Jar".to_string(),
+            description: "Donations".to_string(),
+            blink_type: BlinkType::Donation,
+            image_url: String::new(),
+            config: config.clone(),
+        }
+        .data(),
+    };
+    let logs = process_with_logs(&mut context, &[create_ix], &[]).await;
+    let created = events::<BlinkCreated>(&logs);
+    assert_eq!(created.len(), 1);
+    assert_eq!(created[0].blink, blink);
+    assert_eq!(created[0].owner, owner);
+    assert_eq!(created[0].index, 0);
+    assert!(created[0].config == config);
+
+    let vault = find_blink_vault_address(&blink, &mint.pubkey()).0;
+    let open_vault_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::OpenBlinkVault {
+            blink,
+            vault,
+            mint: mint.pubkey(),
+            payer: owner,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            rent: sysvar::rent::id(),
+            protocol_config: find_protocol_config_address().0,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::OpenBlinkVault {}.data(),
+    };
+    process(&mut context, &[open_vault_ix], &[]).await;
+
+    let donation = Keypair::new();
+    let donation_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::CreateDonation {
+            donation: donation.pubkey(),
+            donor: owner,
+            recipient: blink,
+            donor_token_account: owner_token_account,
+            recipient_token_account: vault,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            protocol_config: find_protocol_config_address().0,
+            treasury_token_account: None,
+            blink: Some(blink),
+            history: None,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::CreateDonation {
+            amount: 40,
+            currency: "BARK".to_string(),
+            message: "Tip".to_string(),
+        }
+        .data(),
+    };
+    let logs = process_with_logs(&mut context, &[donation_ix], &[&donation]).await;
+
+    let donated = events::<DonationCreated>(&logs);
+    assert_eq!(donated.len(), 1);
+    assert_eq!(donated[0].donation, donation.pubkey());
+    assert_eq!(donated[0].donor, owner);
+    assert_eq!(donated[0].mint, mint.pubkey());
+    assert_eq!(donated[0].amount, 40);
+    assert_eq!(donated[0].fee, 0);
+    assert_eq!(donated[0].blink, Some(blink));
+
+    let executed = events::<BlinkExecuted>(&logs);
+    assert_eq!(executed.len(), 1);
+    assert_eq!(executed[0].executor, owner);
+    assert_eq!(executed[0].amount, 40);
+    assert_eq!(executed[0].execution_count, 1);
+}
+
+// Stands in for a hook program. It always fails, so an action with a hook only succeeds
+// when the hook was skipped.
+fn failing_hook(_: &Pubkey, _: &[AccountInfo], _: &[u8]) -> ProgramResult {
+    Err(ProgramError::Custom(7))
+}
+
+#[tokio::test]
+async fn post_action_hook_allowlist_and_failure_modes() {
+    let admin = Keypair::new();
+    let hook_program = Pubkey::new_unique();
+    let (protocol_config, _) = find_protocol_config_address();
+    let (allowlist, _) = find_hook_allowlist_address();
+    let mut program_test = program_test_with_admin(admin.pubkey());
+    let hook: Option<BuiltinFunctionWithContext> = processor!(failing_hook);
+    program_test.add_builtin_program("failing_hook", hook_program, hook.unwrap());
+    let mut context = program_test.start_with_context().await;
+    let owner = context.payer.pubkey();
+    let recipient = Pubkey::new_unique();
+
+    let mint = Keypair::new();
+    create_mint_account(&mut context, &mint).await;
+    let owner_token_account =
+        create_token_account(&mut context, &mint.pubkey(), &owner, 1_000).await;
+    let recipient_token_account =
+        create_token_account(&mut context, &mint.pubkey(), &recipient, 0).await;
+
+    let blink = create_blink(
+        &mut context,
+        BlinkType::Donation,
+        BlinkConfig::Donation {
+            recipient,
+            mint: mint.pubkey(),
+            suggested_amounts: vec![],
+        },
+    )
+    .await;
+
+    let allow_ix = |allowed: bool| Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::SetHookProgramAllowed {
+            allowlist,
+            protocol_config,
+            admin: admin.pubkey(),
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::SetHookProgramAllowed {
+            program: hook_program,
+            allowed,
+        }
+        .data(),
+    };
+    let set_hook_ix = |compute_budget: u32, failure_mode: HookFailureMode| Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::SetBlinkHook {
+            blink,
+            allowlist,
+            owner,
+            protocol_config,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::SetBlinkHook {
+            hook: Some(BlinkHook {
+                program: hook_program,
+                compute_budget,
+                failure_mode,
+            }),
+        }
+        .data(),
+    };
+    // Amounts differ between calls so no two transactions are identical
+    let donate_ix = |amount: u64| {
+        let mut accounts = bark_blinks::accounts::ExecuteBlink {
+            blink,
+            executor: owner,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            history: None,
+            protocol_config,
+            treasury_token_account: None,
+        }
+        .to_account_metas(None);
+        accounts.push(AccountMeta::new(owner_token_account, false));
+        accounts.push(AccountMeta::new(recipient_token_account, false));
+        accounts.push(AccountMeta::new_readonly(allowlist, false));
+        accounts.push(AccountMeta::new_readonly(hook_program, false));
+        Instruction {
+            program_id: bark_blinks::id(),
+            accounts,
+            data: bark_blinks::instruction::ExecuteBlink {
+                args: ExecuteBlinkArgs {
+                    amount: Some(amount),
+                    ..ExecuteBlinkArgs::default()
+                },
+            }
+            .data(),
+        }
+    };
+
+    // Only allowlisted programs can be registered. The admin pays for the allowlist.
+    let fund_ix = system_instruction::transfer(&owner, &admin.pubkey(), 1_000_000_000);
+    process(&mut context, &[fund_ix, allow_ix(false)], &[&admin]).await;
+    let result = try_process(&mut context, &[set_hook_ix(0, HookFailureMode::Skip)], &[]).await;
+    assert_blink_error(result, BlinkError::HookProgramNotAllowed);
+
+    // A hook that runs and fails takes the action down with it, whatever the failure mode
+    process(&mut context, &[allow_ix(true)], &[&admin]).await;
+    // A fresh blockhash keeps the retry from matching the failed transaction
+    context.last_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let logs = process_with_logs(&mut context, &[set_hook_ix(0, HookFailureMode::Skip)], &[]).await;
+    let hook_set = events::<BlinkHookSet>(&logs);
+    assert_eq!(hook_set.len(), 1);
+    assert_eq!(hook_set[0].blink, blink);
+    assert_eq!(hook_set[0].owner, owner);
+    assert!(hook_set[0].hook.as_ref().map(|hook| hook.program) == Some(hook_program));
+    let result = try_process(&mut context, &[donate_ix(10)], &[]).await;
+    assert_eq!(
+        result.unwrap_err(),
+        TransactionError::InstructionError(0, InstructionError::Custom(7))
+    );
+
+    // A budget above the compute left skips the hook or aborts the action
+    process(&mut context, &[set_hook_ix(u32::MAX, HookFailureMode::Skip)], &[]).await;
+    process(&mut context, &[donate_ix(20)], &[]).await;
+    process(&mut context, &[set_hook_ix(u32::MAX, HookFailureMode::Abort)], &[]).await;
+    let result = try_process(&mut context, &[donate_ix(30)], &[]).await;
+    assert_blink_error(result, BlinkError::HookUnavailable);
+
+    // So does a hook the admin has since removed from the allowlist
+    let delist_ix = allow_ix(false);
+    let abort_ix = set_hook_ix(0, HookFailureMode::Abort);
+    process(&mut context, &[abort_ix, delist_ix.clone()], &[&admin]).await;
+    let result = try_process(&mut context, &[donate_ix(40)], &[]).await;
+    assert_blink_error(result, BlinkError::HookUnavailable);
+
+    let skip_ix = set_hook_ix(0, HookFailureMode::Skip);
+    process(&mut context, &[allow_ix(true), skip_ix, delist_ix], &[&admin]).await;
+    process(&mut context, &[donate_ix(50)], &[]).await;
+
+    assert_eq!(token_balance(&mut context, recipient_token_account).await, 70);
+}
+
+#[tokio::test]
+async fn inactive_blinks_reject_actions() {
+    let mut context = program_test().start_with_context().await;
+    let owner = context.payer.pubkey();
+    let (protocol_config, _) = find_protocol_config_address();
+
+    let mint = Keypair::new();
+    create_mint_account(&mut context, &mint).await;
+    let owner_token_account =
+        create_token_account(&mut context, &mint.pubkey(), &owner, 1_000).await;
+
+    // The donation and payment blinks collect into their own vaults
+    let (registry, _) = find_owner_registry_address(&owner);
+    let (donation_blink, _) = find_blink_address(&registry, 0);
+    let (payment_blink, _) = find_blink_address(&registry, 1);
+    create_blink(
+        &mut context,
+        BlinkType::Donation,
+        BlinkConfig::Donation {
+            recipient: donation_blink,
+            mint: mint.pubkey(),
+            suggested_amounts: vec![],
+        },
+    )
+    .await;
+    create_blink(
+        &mut context,
+        BlinkType::Payment,
+        BlinkConfig::Payment {
+            recipient: payment_blink,
+            mint: mint.pubkey(),
+            price: 75,
+        },
+    )
+    .await;
+    let claim_key = Keypair::new();
+    let gift_blink = create_blink(
+        &mut context,
+        BlinkType::Gift,
+        BlinkConfig::Gift {
+            mint: mint.pubkey(),
+            amount: 400,
+            recipient: None,
+            claim_key: Some(claim_key.pubkey()),
+            expires_at: i64::MAX,
+        },
+    )
+    .await;
+
+    let open_vault_ix = |blink: Pubkey| Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::OpenBlinkVault {
+            blink,
+            vault: find_blink_vault_address(&blink, &mint.pubkey()).0,
+            mint: mint.pubkey(),
+            payer: owner,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            rent: sysvar::rent::id(),
+            protocol_config,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::OpenBlinkVault {}.data(),
+    };
+    let (donation_vault, _) = find_blink_vault_address(&donation_blink, &mint.pubkey());
+    let (payment_vault, _) = find_blink_vault_address(&payment_blink, &mint.pubkey());
+    let (escrow, _) =
+        Pubkey::find_program_address(&[GIFT_ESCROW_SEED, gift_blink.as_ref()], &bark_blinks::id());
+    let (gift_vault, _) =
+        Pubkey::find_program_address(&[GIFT_VAULT_SEED, gift_blink.as_ref()], &bark_blinks::id());
+    let fund_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::FundGift {
+            blink: gift_blink,
+            escrow,
+            vault: gift_vault,
+            mint: mint.pubkey(),
+            owner_token_account,
+            owner,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            rent: sysvar::rent::id(),
+            protocol_config,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::FundGift {}.data(),
+    };
+    process(
+        &mut context,
+        &[open_vault_ix(donation_blink), open_vault_ix(payment_blink), fund_ix],
+        &[],
+    )
+    .await;
+
+    let donation_ix = |donation: &Keypair| Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::CreateDonation {
+            donation: donation.pubkey(),
+            donor: owner,
+            recipient: donation_blink,
+            donor_token_account: owner_token_account,
+            recipient_token_account: donation_vault,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            protocol_config,
+            treasury_token_account: None,
+            blink: Some(donation_blink),
+            history: None,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::CreateDonation {
+            amount: 50,
+            currency: "BARK".to_string(),
+            message: "Tip".to_string(),
+        }
+        .data(),
+    };
+    let payment_ix = |payment: &Keypair| Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::CreatePayment {
+            payment: payment.pubkey(),
+            payer: owner,
+            recipient: payment_blink,
+            payer_token_account: owner_token_account,
+            recipient_token_account: payment_vault,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            protocol_config,
+            treasury_token_account: None,
+            blink: Some(payment_blink),
+            history: None,
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::CreatePayment {
+            amount: 75,
+            currency: "BARK".to_string(),
+            description: "Order".to_string(),
+        }
+        .data(),
+    };
+    let mut execute_accounts = bark_blinks::accounts::ExecuteBlink {
+        blink: donation_blink,
+        executor: owner,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        history: None,
+        protocol_config,
+        treasury_token_account: None,
+    }
+    .to_account_metas(None);
+    execute_accounts.push(AccountMeta::new(owner_token_account, false));
+    execute_accounts.push(AccountMeta::new(donation_vault, false));
+    let execute_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: execute_accounts,
+        data: bark_blinks::instruction::ExecuteBlink {
+            args: ExecuteBlinkArgs {
+                amount: Some(50),
+                ..ExecuteBlinkArgs::default()
+            },
+        }
+        .data(),
+    };
+    let claimant = Keypair::new();
+    let claimant_token_account =
+        create_token_account(&mut context, &mint.pubkey(), &claimant.pubkey(), 0).await;
+    let claim_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::ClaimGift {
+            blink: gift_blink,
+            escrow,
+            vault: gift_vault,
+            sender: owner,
+            claimant: claimant.pubkey(),
+            claimant_token_account,
+            token_program: spl_token::id(),
+            history: None,
+            protocol_config,
+            claim_key: Some(claim_key.pubkey()),
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::ClaimGift {}.data(),
+    };
+
+    let manage_ix = |blink: Pubkey, data: Vec<u8>| Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::ManageBlink {
+            blink,
+            authority: owner,
+            protocol_config,
+        }
+        .to_account_metas(None),
+        data,
+    };
+    let blinks = [donation_blink, payment_blink, gift_blink];
+    let now = context
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .unix_timestamp;
+    let paused = bark_blinks::instruction::SetBlinkPaused { paused: true }.data();
+    let not_started = bark_blinks::instruction::SetBlinkSchedule {
+        starts_at: now + 3_600,
+        ends_at: None,
+    }
+    .data();
+    let expired = bark_blinks::instruction::SetBlinkSchedule {
+        starts_at: now - 3_600,
+        ends_at: Some(now),
+    }
+    .data();
+    let resume = bark_blinks::instruction::SetBlinkPaused { paused: false }.data();
+    let reopen = bark_blinks::instruction::SetBlinkSchedule {
+        starts_at: 0,
+        ends_at: None,
+    }
+    .data();
+
+    for (data, expected) in [
+        (paused, BlinkError::BlinkPaused),
+        (not_started, BlinkError::BlinkNotStarted),
+        (expired, BlinkError::BlinkExpired),
+    ] {
+        // Each round retries the same transactions, so it needs a fresh blockhash
+        context.last_blockhash = context.get_new_latest_blockhash().await.unwrap();
+        let state_ixs: Vec<Instruction> =
+            blinks.iter().map(|blink| manage_ix(*blink, data.clone())).collect();
+        process(&mut context, &state_ixs, &[]).await;
+
+        let donation = Keypair::new();
+        let result = try_process(&mut context, &[donation_ix(&donation)], &[&donation]).await;
+        assert_blink_error(result, expected);
+        let payment = Keypair::new();
+        let result = try_process(&mut context, &[payment_ix(&payment)], &[&payment]).await;
+        assert_blink_error(result, expected);
+        let result = try_process(&mut context, std::slice::from_ref(&execute_ix), &[]).await;
+        assert_blink_error(result, expected);
+        let result =
+            try_process(&mut context, std::slice::from_ref(&claim_ix), &[&claimant, &claim_key])
+                .await;
+        assert_blink_error(result, expected);
+
+        let reset_ixs: Vec<Instruction> = blinks
+            .iter()
+            .flat_map(|blink| {
+                [
+                    manage_ix(*blink, resume.clone()),
+                    manage_ix(*blink, reopen.clone()),
+                ]
+            })
+            .collect();
+        let logs = process_with_logs(&mut context, &reset_ixs, &[]).await;
+        let pause_set = events::<BlinkPauseSet>(&logs);
+        assert_eq!(pause_set.len(), blinks.len());
+        assert!(pause_set.iter().all(|event| !event.paused));
+        let schedule_set = events::<BlinkScheduleSet>(&logs);
+        assert_eq!(schedule_set.len(), blinks.len());
+        assert!(schedule_set.iter().all(|event| event.starts_at == 0 && event.ends_at.is_none()));
+    }
+
+    // Active again, every action goes through
+    let donation = Keypair::new();
+    let payment = Keypair::new();
+    process(
+        &mut context,
+        &[donation_ix(&donation), payment_ix(&payment), execute_ix, claim_ix],
+        &[&donation, &payment, &claimant, &claim_key],
+    )
+    .await;
+    assert_eq!(token_balance(&mut context, donation_vault).await, 100);
+    assert_eq!(token_balance(&mut context, payment_vault).await, 75);
+    assert_eq!(token_balance(&mut context, claimant_token_account).await, 400);
+}
+
+#[tokio::test]
+async fn nft_blink_mints_up_to_max_supply() {
+    let mut context = program_test().start_with_context().await;
+    let owner = context.payer.pubkey();
+
+    let mint = Keypair::new();
+    create_mint_account(&mut context, &mint).await;
+    let owner_token_account = create_token_account(&mut context, &mint.pubkey(), &owner, 0).await;
+
+    let blink = create_blink(
+        &mut context,
+        BlinkType::NFT,
+        BlinkConfig::NFT {
+            mint: mint.pubkey(),
+            collection: None,
+            max_supply: 2,
+        },
+    )
+    .await;
+    // The blink mints, so it takes over the mint authority
+    let authority_ix = spl_token::instruction::set_authority(
+        &spl_token::id(),
+        &mint.pubkey(),
+        Some(&blink),
+        spl_token::instruction::AuthorityType::MintTokens,
+        &owner,
+        &[],
+    )
+    .unwrap();
+    process(&mut context, &[authority_ix], &[]).await;
+
+    let mut accounts = bark_blinks::accounts::ExecuteBlink {
+        blink,
+        executor: owner,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        history: None,
+        protocol_config: find_protocol_config_address().0,
+        treasury_token_account: None,
+    }
+    .to_account_metas(None);
+    accounts.push(AccountMeta::new(mint.pubkey(), false));
+    accounts.push(AccountMeta::new(owner_token_account, false));
+    let execute_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts,
+        data: bark_blinks::instruction::ExecuteBlink {
+            args: ExecuteBlinkArgs::default(),
+        }
+        .data(),
+    };
+
+    for _ in 0..2 {
+        // Each mint is the same transaction, so it needs a fresh blockhash
+        context.last_blockhash = context.get_new_latest_blockhash().await.unwrap();
+        process(&mut context, std::slice::from_ref(&execute_ix), &[]).await;
+    }
+    assert_eq!(token_balance(&mut context, owner_token_account).await, 2);
+
+    context.last_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let result = try_process(&mut context, &[execute_ix], &[]).await;
+    assert_blink_error(result, BlinkError::MintSupplyExhausted);
+    assert_eq!(token_balance(&mut context, owner_token_account).await, 2);
+}
+
+#[tokio::test]
+async fn executed_vote_with_prefunded_receipt() {
+    let mut context = program_test().start_with_context().await;
+    let owner = context.payer.pubkey();
+    let blink = create_blink(
+        &mut context,
+        BlinkType::Poll,
+        BlinkConfig::Poll {
+            options: vec!["Yes".to_string(), "No".to_string()],
+        },
+    )
+    .await;
+    let (poll, _) = find_poll_address(&blink);
+    let (vote_receipt, _) = find_vote_receipt_address(&poll, &owner);
+
+    let open_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts: bark_blinks::accounts::OpenPoll {
+            blink,
+            poll,
+            owner,
+            system_program: system_program::id(),
+            protocol_config: find_protocol_config_address().0,
+            vote_mint: None,
+            vote_vault: None,
+            token_program: spl_token::id(),
+            rent: sysvar::rent::id(),
+        }
+        .to_account_metas(None),
+        data: bark_blinks::instruction::OpenPoll { closes_at: i64::MAX }.data(),
+    };
+    // Lamports sent to the receipt address ahead of time must not block the vote
+    let griefer = Keypair::new();
+    let grief_ixs = [
+        system_instruction::transfer(&owner, &griefer.pubkey(), 1_000_000_000),
+        system_instruction::transfer(&griefer.pubkey(), &vote_receipt, 1_000_000),
+    ];
+    process(&mut context, &[open_ix], &[]).await;
+    process(&mut context, &grief_ixs, &[&griefer]).await;
+
+    let mut accounts = bark_blinks::accounts::ExecuteBlink {
+        blink,
+        executor: owner,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        history: None,
+        protocol_config: find_protocol_config_address().0,
+        treasury_token_account: None,
+    }
+    .to_account_metas(None);
+    accounts.push(AccountMeta::new(poll, false));
+    accounts.push(AccountMeta::new(vote_receipt, false));
+    let execute_ix = Instruction {
+        program_id: bark_blinks::id(),
+        accounts,
+        data: bark_blinks::instruction::ExecuteBlink {
+            args: ExecuteBlinkArgs {
+                option: Some(1),
+                ..ExecuteBlinkArgs::default()
+            },
+        }
+        .data(),
+    };
+    process(&mut context, std::slice::from_ref(&execute_ix), &[]).await;
+
+    let account: Poll = fetch(&mut context, poll).await;
+    assert_eq!(account.tallies, vec![0, 1]);
+    let receipt = get_account(&mut context, vote_receipt).await;
+    assert_eq!(receipt.owner, bark_blinks::id());
+    assert_eq!(receipt.data.len(), VoteReceipt::LEN);
+
+    // The receipt still blocks a second vote
+    context.last_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let result = try_process(&mut context, &[execute_ix], &[]).await;
+    assert_blink_error(result, BlinkError::AlreadyVoted);
+}
@@ -0,0 +1,257 @@
+// Fixture helpers shared by the program and client integration tests
+#![allow(dead_code)]
+
+use anchor_lang::{AccountDeserialize, AccountSerialize, Space};
+use bark_blinks::errors::BlinkError;
+use bark_blinks::protocol::{
+    find_protocol_config_address, ProtocolConfig, ProtocolFeatures, ProtocolFees,
+};
+use solana_program_test::{find_file, read_file, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+    instruction::{Instruction, InstructionError},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::{Transaction, TransactionError},
+};
+
+// Requires `cargo build-sbf` for the program and a dump of the token metadata
+// program at `tests/fixtures/mpl_token_metadata.so` (see `tests/fixtures/fetch.sh`).
+pub fn program_test() -> ProgramTest {
+    program_test_with_admin(Pubkey::new_unique())
+}
+
+// The protocol config is normally created by the upgrade authority (see
+// `program_test_with_upgrade_authority`), so here it is seeded directly with no fees and
+// every feature enabled.
+pub fn program_test_with_admin(admin: Pubkey) -> ProgramTest {
+    let mut program_test = ProgramTest::new("bark_blinks", bark_blinks::id(), None);
+    program_test.add_program("mpl_token_metadata", mpl_token_metadata::ID, None);
+
+    let (protocol_config, bump) = find_protocol_config_address();
+    let mut data = Vec::new();
+    ProtocolConfig {
+        admin,
+        pending_admin: None,
+        treasury: admin,
+        fees: ProtocolFees::default(),
+        features: ProtocolFeatures {
+            blink_routing: true,
+            fee_collection: true,
+        },
+        paused_modules: 0,
+        bump,
+    }
+    .try_serialize(&mut data)
+    .unwrap();
+    // Sized like the `init` in `InitializeProtocolConfig` so optional fields can be set later
+    data.resize(8 + ProtocolConfig::INIT_SPACE, 0);
+    program_test.add_account(
+        protocol_config,
+        Account {
+            lamports: 1_000_000_000,
+            data,
+            owner: bark_blinks::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    program_test
+}
+
+// `add_program` deploys with the non-upgradeable loader, which leaves no program data
+// account for `initialize_protocol_config` to check. This deploys the program the way
+// `solana program deploy` does instead, and leaves the protocol config uncreated.
+pub fn program_test_with_upgrade_authority(upgrade_authority: Pubkey) -> ProgramTest {
+    let mut program_test = ProgramTest::default();
+    program_test.add_program("mpl_token_metadata", mpl_token_metadata::ID, None);
+
+    let elf = read_file(find_file("bark_blinks.so").expect("bark_blinks.so not found"));
+    let (program_data, _) = Pubkey::find_program_address(
+        &[bark_blinks::id().as_ref()],
+        &bpf_loader_upgradeable::id(),
+    );
+    let mut data = bincode::serialize(&UpgradeableLoaderState::ProgramData {
+        slot: 0,
+        upgrade_authority_address: Some(upgrade_authority),
+    })
+    .unwrap();
+    data.extend_from_slice(&elf);
+    program_test.add_account(
+        program_data,
+        Account {
+            lamports: 1_000_000_000,
+            data,
+            owner: bpf_loader_upgradeable::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    program_test.add_account(
+        bark_blinks::id(),
+        Account {
+            lamports: 1_000_000_000,
+            data: bincode::serialize(&UpgradeableLoaderState::Program {
+                programdata_address: program_data,
+            })
+            .unwrap(),
+            owner: bpf_loader_upgradeable::id(),
+            executable: true,
+            rent_epoch: 0,
+        },
+    );
+
+    program_test
+}
+
+pub async fn try_process(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<(), TransactionError> {
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .map_err(|error| error.unwrap())
+}
+
+// Fails unless the transaction was rejected with `expected` by one of its instructions
+pub fn assert_blink_error(result: Result<(), TransactionError>, expected: BlinkError) {
+    match result {
+        Err(TransactionError::InstructionError(_, InstructionError::Custom(code))) => {
+            assert_eq!(code, u32::from(expected), "expected {}", expected.name());
+        }
+        other => panic!("expected {}, got {:?}", expected.name(), other),
+    }
+}
+
+pub async fn process(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) {
+    try_process(context, instructions, signers).await.unwrap();
+}
+
+pub async fn process_with_logs(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Vec<String> {
+    let (result, logs) = try_process_with_logs(context, instructions, signers).await;
+    result.unwrap();
+    logs
+}
+
+// Logs are returned whether or not the transaction succeeded
+pub async fn try_process_with_logs(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> (Result<(), TransactionError>, Vec<String>) {
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        context.last_blockhash,
+    );
+    let result = context
+        .banks_client
+        .process_transaction_with_metadata(transaction)
+        .await
+        .unwrap();
+    // Transactions rejected before they run have no logs
+    let logs = result.metadata.map(|metadata| metadata.log_messages).unwrap_or_default();
+    (result.result, logs)
+}
+
+pub async fn get_account(context: &mut ProgramTestContext, address: Pubkey) -> Account {
+    context
+        .banks_client
+        .get_account(address)
+        .await
+        .unwrap()
+        .expect("account not found")
+}
+
+pub async fn fetch<T: AccountDeserialize>(context: &mut ProgramTestContext, address: Pubkey) -> T {
+    let account = get_account(context, address).await;
+    T::try_deserialize(&mut account.data.as_slice()).unwrap()
+}
+
+pub async fn create_mint_account(context: &mut ProgramTestContext, mint: &Keypair) {
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let payer = context.payer.pubkey();
+    let instructions = [
+        system_instruction::create_account(
+            &payer,
+            &mint.pubkey(),
+            rent.minimum_balance(spl_token::state::Mint::LEN),
+            spl_token::state::Mint::LEN as u64,
+            &spl_token::id(),
+        ),
+        spl_token::instruction::initialize_mint(&spl_token::id(), &mint.pubkey(), &payer, None, 0)
+            .unwrap(),
+    ];
+    process(context, &instructions, &[mint]).await;
+}
+
+pub async fn create_token_account(
+    context: &mut ProgramTestContext,
+    mint: &Pubkey,
+    owner: &Pubkey,
+    amount: u64,
+) -> Pubkey {
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let payer = context.payer.pubkey();
+    let account = Keypair::new();
+    let instructions = [
+        system_instruction::create_account(
+            &payer,
+            &account.pubkey(),
+            rent.minimum_balance(spl_token::state::Account::LEN),
+            spl_token::state::Account::LEN as u64,
+            &spl_token::id(),
+        ),
+        spl_token::instruction::initialize_account(
+            &spl_token::id(),
+            &account.pubkey(),
+            mint,
+            owner,
+        )
+        .unwrap(),
+        spl_token::instruction::mint_to(
+            &spl_token::id(),
+            mint,
+            &account.pubkey(),
+            &payer,
+            &[],
+            amount,
+        )
+        .unwrap(),
+    ];
+    process(context, &instructions, &[&account]).await;
+    account.pubkey()
+}
+
+pub async fn token_balance(context: &mut ProgramTestContext, address: Pubkey) -> u64 {
+    let account = get_account(context, address).await;
+    spl_token::state::Account::unpack(&account.data)
+        .unwrap()
+        .amount
+}
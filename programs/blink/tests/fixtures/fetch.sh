@@ -0,0 +1,6 @@
+#!/usr/bin/env sh
+# Dumps the token metadata program from mainnet into tests/fixtures so
+# solana-program-test can load it. Needs the Solana CLI.
+set -e
+cd "$(dirname "$0")"
+solana program dump -u mainnet-beta metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s mpl_token_metadata.so
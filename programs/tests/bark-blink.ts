@@ -14,7 +14,9 @@ describe("bark-blinks", () => {
 
   let blinkMint: PublicKey;
   let blinkTokenAccount: PublicKey;
+  let registryPDA: PublicKey;
   let blinkPDA: PublicKey;
+  let protocolConfigPDA: PublicKey;
 
   before(async () => {
     // Create a new mint for testing
@@ -44,11 +46,40 @@ describe("bark-blinks", () => {
       1000 * LAMPORTS_PER_SOL
     );
 
-    // Derive the blink PDA
+    // Derive the owner registry and the PDA of the owner's first blink
+    [registryPDA] = await PublicKey.findProgramAddress(
+      [Buffer.from("owner_registry"), wallet.publicKey.toBuffer()],
+      program.programId
+    );
     [blinkPDA] = await PublicKey.findProgramAddress(
-      [Buffer.from("blink"), blinkMint.toBuffer()],
+      [Buffer.from("blink"), registryPDA.toBuffer(), new anchor.BN(0).toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+
+    // The deploying wallet is the upgrade authority, so it can create the protocol config
+    [protocolConfigPDA] = await PublicKey.findProgramAddress(
+      [Buffer.from("protocol_config")],
       program.programId
     );
+    const [programData] = await PublicKey.findProgramAddress(
+      [program.programId.toBuffer()],
+      new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+    );
+    await program.methods
+      .initializeProtocolConfig(
+        wallet.publicKey,
+        wallet.publicKey,
+        { donationBps: 0, paymentBps: 0, swapBps: 0, mintFeeLamports: new anchor.BN(0) },
+        { blinkRouting: true, feeCollection: true }
+      )
+      .accounts({
+        protocolConfig: protocolConfigPDA,
+        authority: wallet.publicKey,
+        program: program.programId,
+        programData,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
   });
 
   it("Creates a blink", async () => {
@@ -58,11 +89,13 @@ describe("bark-blinks", () => {
     const imageUrl = "https://example.com/image.png";
 
     await program.methods
-      .createBlink(name, description, blinkType, imageUrl)
+      .createBlink(name, description, blinkType, imageUrl, { standard: {} })
       .accounts({
+        registry: registryPDA,
         blink: blinkPDA,
         owner: wallet.publicKey,
         systemProgram: SystemProgram.programId,
+        protocolConfig: protocolConfigPDA,
       })
       .rpc();
 
@@ -80,10 +113,13 @@ describe("bark-blinks", () => {
     const newImageUrl = "https://example.com/new-image.png";
 
     await program.methods
-      .updateBlink(newName, newDescription, newImageUrl)
+      .updateBlink(newName, newDescription, newImageUrl, null)
       .accounts({
         blink: blinkPDA,
+        authority: wallet.publicKey,
         owner: wallet.publicKey,
+        systemProgram: SystemProgram.programId,
+        protocolConfig: protocolConfigPDA,
       })
       .rpc();
 
@@ -111,6 +147,7 @@ describe("bark-blinks", () => {
         tokenAccount: await getAssociatedTokenAddress(nftKeypair.publicKey, wallet.publicKey),
         metadataProgram: METADATA_PROGRAM_ID,
         rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        protocolConfig: protocolConfigPDA,
       })
       .signers([nftKeypair])
       .rpc();
@@ -143,6 +180,10 @@ describe("bark-blinks", () => {
         ),
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
+        protocolConfig: protocolConfigPDA,
+        treasuryTokenAccount: null,
+        blink: null,
+        history: null,
       })
       .signers([donationKeypair])
       .rpc();
@@ -174,6 +215,7 @@ describe("bark-blinks", () => {
         ),
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
+        protocolConfig: protocolConfigPDA,
       })
       .signers([swapKeypair])
       .rpc();